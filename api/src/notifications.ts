@@ -3,6 +3,12 @@ declare global {
     __HC_LAUNCHER_API__: {
       notify: (notifications: Array<HappNotification>) => void;
       resetNotificationCount: (notificationIds: Array<NotificationId>) => void;
+      signBytes: (data: Uint8Array) => Promise<Uint8Array>;
+      verifySignature: (
+        agentPubKey: Uint8Array,
+        data: Uint8Array,
+        signature: Uint8Array
+      ) => Promise<boolean>;
     };
   }
 }
@@ -0,0 +1,29 @@
+/**
+ * Signs arbitrary data with the public key of the app. The Launcher asks the
+ * user for consent first, unless the user chose to always allow it for this app.
+ *
+ * @param data Bytes to sign
+ * @returns The ed25519 signature
+ */
+export async function signBytes(data: Uint8Array): Promise<Uint8Array> {
+  return window.__HC_LAUNCHER_API__.signBytes(data);
+}
+
+/**
+ * Verifies an ed25519 signature created by the given agent over the given data
+ *
+ * @param agentPubKey Public key of the agent that signed the data
+ * @param data Bytes that were signed
+ * @param signature The signature to verify
+ */
+export async function verifySignature(
+  agentPubKey: Uint8Array,
+  data: Uint8Array,
+  signature: Uint8Array
+): Promise<boolean> {
+  return window.__HC_LAUNCHER_API__.verifySignature(
+    agentPubKey,
+    data,
+    signature
+  );
+}
@@ -5,6 +5,12 @@ declare global {
     __HC_LAUNCHER_API__: {
       notify: (notifications: Array<HappNotification>) => void;
       resetNotificationCount: (notificationIds: Array<NotificationId>) => void;
+      signBytes: (data: Uint8Array) => Promise<Uint8Array>;
+      verifySignature: (
+        agentPubKey: Uint8Array,
+        data: Uint8Array,
+        signature: Uint8Array
+      ) => Promise<boolean>;
     };
     __HC_LAUNCHER_ENV__: {
       APP_INTERFACE_PORT: number;
@@ -36,6 +42,22 @@ window.__HC_LAUNCHER_API__ = {
       notificationIds,
     });
   },
+  signBytes: async (data: Uint8Array) => {
+    const signature: number[] = await invoke("sign_bytes", {
+      data: Array.from(data),
+    });
+    return Uint8Array.from(signature);
+  },
+  verifySignature: async (
+    agentPubKey: Uint8Array,
+    data: Uint8Array,
+    signature: Uint8Array
+  ) =>
+    invoke("verify_signature", {
+      agentPubKey: Array.from(agentPubKey),
+      data: Array.from(data),
+      signature: Array.from(signature),
+    }),
 };
 
 window.addEventListener("focus", async () => {
@@ -43,3 +65,16 @@ window.addEventListener("focus", async () => {
     appId: window.__HC_LAUNCHER_ENV__.INSTALLED_APP_ID,
   });
 });
+
+// report user input to reset the keystore's idle-lock timer, at most every 30 seconds
+let lastActivityReport = 0;
+const reportActivity = () => {
+  const now = Date.now();
+  if (now - lastActivityReport > 30000) {
+    lastActivityReport = now;
+    invokeTauriCommand("report_user_activity", {});
+  }
+};
+["keydown", "mousedown", "mousemove", "wheel"].forEach((eventType) =>
+  window.addEventListener(eventType, reportActivity, { passive: true })
+);
@@ -2,7 +2,7 @@ use holochain_types::prelude::{ZomeCallUnsigned, AgentPubKey};
 use holochain_conductor_api::ZomeCall;
 use lair_keystore_api::LairClient;
 use std::collections::HashMap;
-use holochain_launcher_utils::zome_call_signing::{ZomeCallUnsignedTauri, sign_zome_call_with_client};
+use holochain_launcher_utils::zome_call_signing::{ZomeCallUnsignedTauri, ZomeCallValidator, sign_zome_call_with_client, sign_zome_calls_with_client};
 
 use crate::error::HcLaunchError;
 
@@ -13,6 +13,7 @@ pub async fn sign_zome_call(
   window: tauri::Window,
   lair_clients_state: tauri::State<'_, HashMap<String, LairClient>>,
   pubkey_map_state: tauri::State<'_, HashMap<String, AgentPubKey>>,
+  zome_call_validator: tauri::State<'_, ZomeCallValidator>,
   zome_call_unsigned: ZomeCallUnsignedTauri,
 ) -> Result<ZomeCall, HcLaunchError> {
 
@@ -26,30 +27,101 @@ pub async fn sign_zome_call(
 
   // validate that the agent public key added to the ZomeCallUnsigned field is actually the
   // one associated to the UI that's making the call
-  let maybe_authorized_pubkey = (*pubkey_map_state).get(window_label);
-
-  match maybe_authorized_pubkey {
-    Some(pubkey) => {
-      if pubkey != &unsigned_zome_call_converted.provenance {
-        return Err(HcLaunchError::SignZomeCallError(String::from("The provided public key in the provenance field is not authorized to make a zome call to the requested cell.")));
-      }
-    },
-    None => {
-      return Err(HcLaunchError::SignZomeCallError(String::from("No authorized public key found for this window.")));
-    }
-  }
+  let authorized_pubkey = authorized_pubkey(&pubkey_map_state, window_label)?;
+  authorize_provenance(authorized_pubkey, &unsigned_zome_call_converted)?;
 
   // get the right lair client from the hashmap
-  let client = (*lair_clients_state).get(window_label)
-    .expect(format!("No lair client for this window with label '{}'", window_label).as_str());
+  let client = lair_client(&lair_clients_state, window_label)?;
 
   // sign the zome call
   sign_zome_call_with_client(
     unsigned_zome_call_converted,
     client,
+    &zome_call_validator,
   )
   .await
-  .map_err(|e| HcLaunchError::SignZomeCallError(e))
+  .map_err(HcLaunchError::from)
+}
+
+
+/// Signs multiple zome calls in one go. Returns one result per zome call, in the same order.
+#[tauri::command]
+pub async fn sign_zome_calls(
+  window: tauri::Window,
+  lair_clients_state: tauri::State<'_, HashMap<String, LairClient>>,
+  pubkey_map_state: tauri::State<'_, HashMap<String, AgentPubKey>>,
+  zome_call_validator: tauri::State<'_, ZomeCallValidator>,
+  zome_calls_unsigned: Vec<ZomeCallUnsignedTauri>,
+) -> Result<Vec<Result<ZomeCall, HcLaunchError>>, HcLaunchError> {
+
+  let window_label = window.label();
+
+  let authorized_pubkey = authorized_pubkey(&pubkey_map_state, window_label)?;
+
+  let authorizations: Vec<Result<ZomeCallUnsigned, HcLaunchError>> = zome_calls_unsigned
+    .into_iter()
+    .map(|zome_call_unsigned| {
+      let zome_call_unsigned: ZomeCallUnsigned = zome_call_unsigned.into();
+      authorize_provenance(authorized_pubkey, &zome_call_unsigned).map(|_| zome_call_unsigned)
+    })
+    .collect();
+
+  let client = lair_client(&lair_clients_state, window_label)?;
+
+  // only the authorized zome calls get signed
+  let authorized_zome_calls = authorizations
+    .iter()
+    .filter_map(|authorization| authorization.as_ref().ok().cloned())
+    .collect();
+  let mut signed_zome_calls = sign_zome_calls_with_client(
+    authorized_zome_calls,
+    client,
+    &zome_call_validator,
+  )
+  .await
+  .into_iter();
+
+  Ok(
+    authorizations
+      .into_iter()
+      .map(|authorization| match authorization {
+        Ok(_) => match signed_zome_calls.next() {
+          Some(result) => result.map_err(HcLaunchError::from),
+          None => Err(HcLaunchError::SignZomeCallError(String::from("No signing result for this zome call."))),
+        },
+        Err(e) => Err(e),
+      })
+      .collect()
+  )
+}
+
+
+fn lair_client<'a>(
+  lair_clients: &'a HashMap<String, LairClient>,
+  window_label: &str,
+) -> Result<&'a LairClient, HcLaunchError> {
+  lair_clients
+    .get(window_label)
+    .ok_or(HcLaunchError::SignZomeCallError(format!("No lair client for this window with label '{}'", window_label)))
+}
+
+fn authorized_pubkey<'a>(
+  pubkey_map: &'a HashMap<String, AgentPubKey>,
+  window_label: &str,
+) -> Result<&'a AgentPubKey, HcLaunchError> {
+  pubkey_map
+    .get(window_label)
+    .ok_or(HcLaunchError::SignZomeCallError(String::from("No authorized public key found for this window.")))
+}
+
+fn authorize_provenance(
+  authorized_pubkey: &AgentPubKey,
+  zome_call_unsigned: &ZomeCallUnsigned,
+) -> Result<(), HcLaunchError> {
+  if authorized_pubkey != &zome_call_unsigned.provenance {
+    return Err(HcLaunchError::SignZomeCallError(String::from("The provided public key in the provenance field is not authorized to make a zome call to the requested cell.")));
+  }
+  Ok(())
 }
 
 
@@ -1,5 +1,6 @@
 use thiserror;
 use serde::{Deserialize, Serialize};
+use holochain_launcher_utils::zome_call_signing::ZomeCallSigningError;
 use std::{fmt, io};
 
 
@@ -13,6 +14,9 @@ pub enum HcLaunchError {
 
     #[error("Failed to sign by public key: \"{0}\"")]
     SignZomeCallError(String),
+
+    #[error("Refused to sign zome call: {0}")]
+    ZomeCallRejected(#[from] ZomeCallSigningError),
 }
 
 /// HcBundle Result type.
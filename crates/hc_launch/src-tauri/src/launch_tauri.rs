@@ -7,6 +7,7 @@
 use holochain_client::AdminWebsocket;
 use holochain_types::prelude::AgentPubKey;
 use holochain_launcher_utils::window_builder::{happ_window_builder, UISource};
+use holochain_launcher_utils::zome_call_signing::ZomeCallValidator;
 use tauri::utils::config::AppUrl;
 use tauri::WindowUrl;
 use tauri::{CustomMenuItem, Menu, Submenu};
@@ -54,6 +55,7 @@ pub fn launch_tauri(
   let builder_result = builder
     .invoke_handler(tauri::generate_handler![
       crate::commands::sign_zome_call::sign_zome_call,
+      crate::commands::sign_zome_call::sign_zome_calls,
       holochain_launcher_utils::shared_commands::open_url_cmd,
     ]) // uncomment when testing with right version
     .setup(move |app| {
@@ -233,6 +235,7 @@ pub fn launch_tauri(
 
       app.manage(lair_clients);
       app.manage(pubkey_map);
+      app.manage(ZomeCallValidator::default());
 
       // watch for file changes in the UI folder if requested
       match (watch, ui_source) {
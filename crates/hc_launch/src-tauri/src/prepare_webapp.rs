@@ -1,7 +1,9 @@
 use std::fs;
+use std::io::Cursor;
 use std::path::{PathBuf, Path};
 
 
+use holochain_launcher_utils::unzip::{unzip_file, UnzipLimits};
 use holochain_types::web_app::WebAppBundle;
 
 use crate::error::FileSystemError;
@@ -47,31 +49,10 @@ pub async fn read_and_prepare_webapp(web_happ_path: &PathBuf, out_path: &PathBuf
 
 
   let ui_folder_path = out_path.join("ui");
-  // remove existing assets first
-  if path_exists(&ui_folder_path) {
-    fs::remove_dir_all(ui_folder_path.clone()).unwrap();
-  }
-
-  fs::create_dir(ui_folder_path.clone())
-    .map_err(|e| format!("Failed to create ui directory: {:?}", e))?;
-
-
-  // writing ui.zip
-  let ui_zip_path = ui_folder_path.join("ui.zip");
-  fs::write(ui_zip_path.clone(), web_ui_zip_bytes.to_vec())
-    .map_err(|e| format!("Error writing ui.zip: {:?}", e))?;
 
-  // opening ui.zip
-  let file = fs::File::open(ui_zip_path.clone())
-    .map_err(|e| format!("Error opening ui.zip: {:?}", e))?;
-
-  // Unzipping ui.zip
-  unzip_file(file, ui_folder_path)
-    .map_err(|e| format!("Could not unzip ui.zip: {:?}", e))?;
-
-  // remove ui.zip after extraction
-  fs::remove_file(ui_zip_path)
-    .map_err(|e| format!("Failed to remove ui.zip: {:?}", e))?;
+  // Unzipping ui.zip, replacing existing assets
+  unzip_file(Cursor::new(web_ui_zip_bytes.to_vec()), &ui_folder_path, &UnzipLimits::default())
+    .map_err(|e| format!("Could not unzip ui.zip: {}", e))?;
 
   // Writing .happ file
   let app_id = web_happ_path.as_path().file_stem()
@@ -86,35 +67,3 @@ pub async fn read_and_prepare_webapp(web_happ_path: &PathBuf, out_path: &PathBuf
 
   Ok(())
 }
-
-
-
-pub fn unzip_file(reader: fs::File, outpath: PathBuf) -> Result<(), String> {
-  let mut archive = match zip::ZipArchive::new(reader) {
-    Ok(a) => a,
-    Err(e) => return Err(format!("Failed to unpack zip archive: {}", e)),
-  };
-
-  for i in 0..archive.len() {
-    let mut file = archive.by_index(i).unwrap();
-    let outpath = match file.enclosed_name() {
-      Some(path) => outpath.join(path).to_owned(),
-      None => continue,
-    };
-
-    if (&*file.name()).ends_with('/') {
-      fs::create_dir_all(&outpath).unwrap();
-    } else {
-      if let Some(p) = outpath.parent() {
-        if !p.exists() {
-          fs::create_dir_all(&p).unwrap();
-        }
-      }
-      let mut outfile = fs::File::create(&outpath).unwrap();
-      std::io::copy(&mut file, &mut outfile).unwrap();
-    }
-  }
-
-  Ok(())
-}
-
@@ -4,6 +4,7 @@
 pub mod window_builder;
 pub mod zome_call_signing;
 pub mod shared_commands;
+pub mod unzip;
 
 
 
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  io::{self, Read, Seek},
+  path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+
+/// Entries smaller than this are not checked against the maximum compression ratio
+/// since small text files legitimately compress very well
+const MIN_SIZE_FOR_RATIO_CHECK: u64 = 1024 * 1024;
+
+#[derive(Error, Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum UnzipError {
+  #[error("Failed to read zip archive: {0}")]
+  InvalidArchive(String),
+  #[error("Zip archive has more than {0} entries")]
+  TooManyEntries(usize),
+  #[error("Zip archive exceeds the maximum uncompressed size of {0} bytes")]
+  TooLarge(u64),
+  #[error("Zip entry '{0}' exceeds the maximum compression ratio")]
+  CompressionRatioExceeded(String),
+  #[error("Zip entry '{0}' has a path outside of the extraction directory")]
+  UnsafePath(String),
+  #[error("Zip entry '{0}' is a symlink or special file")]
+  UnsupportedEntryType(String),
+  #[error("IO error while extracting zip archive: {0}")]
+  IoError(String),
+}
+
+impl From<io::Error> for UnzipError {
+  fn from(err: io::Error) -> Self {
+    Self::IoError(err.to_string())
+  }
+}
+
+impl From<UnzipError> for String {
+  fn from(err: UnzipError) -> Self {
+    err.to_string()
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnzipLimits {
+  /// Maximum sum of the uncompressed sizes of all entries, in bytes
+  pub max_total_size: u64,
+  pub max_entries: usize,
+  /// Maximum ratio between the uncompressed and the compressed size of an entry
+  pub max_compression_ratio: u64,
+}
+
+impl Default for UnzipLimits {
+  fn default() -> Self {
+    UnzipLimits {
+      max_total_size: 1024 * 1024 * 1024,
+      max_entries: 20_000,
+      max_compression_ratio: 200,
+    }
+  }
+}
+
+/// Extracts a zip archive into the directory at `outpath`, replacing it if it exists already.
+/// Entries are extracted into a staging directory next to it that only gets moved into place
+/// once the whole archive has been extracted, so a failed extraction leaves nothing behind and
+/// keeps the existing directory.
+pub fn unzip_file<R: Read + Seek>(
+  reader: R,
+  outpath: &PathBuf,
+  limits: &UnzipLimits,
+) -> Result<(), UnzipError> {
+  let staging_path = sibling_dir(outpath, ".unzipping")?;
+  if staging_path.exists() {
+    fs::remove_dir_all(&staging_path)?;
+  }
+  fs::create_dir_all(&staging_path)?;
+
+  let result = extract(reader, &staging_path, limits).and_then(|()| move_into_place(&staging_path, outpath));
+  if result.is_err() && staging_path.exists() {
+    if let Err(e) = fs::remove_dir_all(&staging_path) {
+      log::error!("Failed to remove staging directory of failed extraction: {}", e);
+    }
+  }
+  result
+}
+
+/// Renames the staging directory to the target. An existing target is moved aside first and only
+/// removed once the staging directory took its place, or moved back if that failed.
+fn move_into_place(staging_path: &Path, outpath: &PathBuf) -> Result<(), UnzipError> {
+  if !outpath.exists() {
+    fs::rename(staging_path, outpath)?;
+    return Ok(());
+  }
+
+  let replaced_path = sibling_dir(outpath, ".replaced")?;
+  if replaced_path.exists() {
+    fs::remove_dir_all(&replaced_path)?;
+  }
+  fs::rename(outpath, &replaced_path)?;
+
+  if let Err(err) = fs::rename(staging_path, outpath) {
+    if let Err(e) = fs::rename(&replaced_path, outpath) {
+      log::error!("Failed to restore directory replaced by a failed extraction: {}", e);
+    }
+    return Err(err.into());
+  }
+
+  if let Err(e) = fs::remove_dir_all(&replaced_path) {
+    log::error!("Failed to remove directory replaced by extraction: {}", e);
+  }
+  Ok(())
+}
+
+fn sibling_dir(outpath: &PathBuf, suffix: &str) -> Result<PathBuf, UnzipError> {
+  let name = outpath
+    .file_name()
+    .ok_or(UnzipError::IoError(format!("Invalid extraction target {:?}", outpath)))?;
+
+  let mut sibling_name = name.to_os_string();
+  sibling_name.push(suffix);
+
+  Ok(outpath.with_file_name(sibling_name))
+}
+
+fn extract<R: Read + Seek>(reader: R, target: &Path, limits: &UnzipLimits) -> Result<(), UnzipError> {
+  let mut archive =
+    zip::ZipArchive::new(reader).map_err(|e| UnzipError::InvalidArchive(e.to_string()))?;
+
+  if archive.len() > limits.max_entries {
+    return Err(UnzipError::TooManyEntries(limits.max_entries));
+  }
+
+  let mut total_size: u64 = 0;
+
+  for i in 0..archive.len() {
+    let mut file = archive
+      .by_index(i)
+      .map_err(|e| UnzipError::InvalidArchive(e.to_string()))?;
+    let name = file.name().to_string();
+
+    let relative_path = file
+      .enclosed_name()
+      .map(|p| p.to_owned())
+      .ok_or(UnzipError::UnsafePath(name.clone()))?;
+    let entry_path = target.join(relative_path);
+
+    let is_dir = match file.unix_mode().map(|mode| mode & S_IFMT) {
+      None | Some(0) => file.is_dir(),
+      Some(S_IFDIR) => true,
+      Some(S_IFREG) => false,
+      Some(_) => return Err(UnzipError::UnsupportedEntryType(name)),
+    };
+
+    if is_dir {
+      fs::create_dir_all(&entry_path)?;
+      continue;
+    }
+
+    if file.size() > MIN_SIZE_FOR_RATIO_CHECK
+      && file.size() > file.compressed_size().saturating_mul(limits.max_compression_ratio)
+    {
+      return Err(UnzipError::CompressionRatioExceeded(name));
+    }
+
+    if let Some(parent) = entry_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    // The declared size of an entry can't be trusted, so the limit is enforced on the bytes actually written
+    let remaining = limits.max_total_size - total_size;
+    let mut outfile = fs::File::create(&entry_path)?;
+    let written = io::copy(&mut (&mut file).take(remaining.saturating_add(1)), &mut outfile)?;
+    if written > remaining {
+      return Err(UnzipError::TooLarge(limits.max_total_size));
+    }
+    total_size += written;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Cursor, Write};
+  use tempfile::TempDir;
+  use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+  /// Signature of a central directory file header
+  const CENTRAL_DIRECTORY_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x01, 0x02];
+  /// Offset of the external attributes in a central directory file header
+  const EXTERNAL_ATTRIBUTES_OFFSET: usize = 38;
+  const S_IFLNK: u32 = 0o120000;
+
+  /// The extraction target inside the given temporary directory
+  fn outpath(dir: &TempDir) -> PathBuf {
+    dir.path().join("out")
+  }
+
+  fn zip_archive(files: Vec<(&str, Vec<u8>)>, method: CompressionMethod) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+      writer
+        .start_file(name, FileOptions::default().compression_method(method))
+        .unwrap();
+      writer.write_all(&content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+  }
+
+  fn unzip(bytes: Vec<u8>, outpath: &PathBuf, limits: &UnzipLimits) -> Result<(), UnzipError> {
+    unzip_file(Cursor::new(bytes), outpath, limits)
+  }
+
+  #[test]
+  fn extracts_files_and_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = zip_archive(
+      vec![("index.html", b"<html></html>".to_vec()), ("assets/app.js", b"app".to_vec())],
+      CompressionMethod::Deflated,
+    );
+
+    unzip(bytes, &outpath(&dir), &UnzipLimits::default()).unwrap();
+
+    assert_eq!(fs::read(outpath(&dir).join("index.html")).unwrap(), b"<html></html>");
+    assert_eq!(fs::read(outpath(&dir).join("assets/app.js")).unwrap(), b"app");
+    assert!(!sibling_dir(&outpath(&dir), ".unzipping").unwrap().exists());
+  }
+
+  #[test]
+  fn replaces_existing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(outpath(&dir)).unwrap();
+    fs::write(outpath(&dir).join("old.js"), b"old").unwrap();
+
+    let bytes = zip_archive(vec![("new.js", b"new".to_vec())], CompressionMethod::Stored);
+    unzip(bytes, &outpath(&dir), &UnzipLimits::default()).unwrap();
+
+    assert!(!outpath(&dir).join("old.js").exists());
+    assert_eq!(fs::read(outpath(&dir).join("new.js")).unwrap(), b"new");
+    assert!(!sibling_dir(&outpath(&dir), ".replaced").unwrap().exists());
+  }
+
+  #[test]
+  fn failed_extraction_keeps_existing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(outpath(&dir)).unwrap();
+    fs::write(outpath(&dir).join("old.js"), b"old").unwrap();
+
+    let bytes = zip_archive(vec![("../evil.js", b"evil".to_vec())], CompressionMethod::Stored);
+    assert!(unzip(bytes, &outpath(&dir), &UnzipLimits::default()).is_err());
+
+    assert_eq!(fs::read(outpath(&dir).join("old.js")).unwrap(), b"old");
+    assert!(!sibling_dir(&outpath(&dir), ".unzipping").unwrap().exists());
+  }
+
+  #[test]
+  fn rejects_too_many_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = zip_archive(
+      vec![("a", vec![]), ("b", vec![]), ("c", vec![])],
+      CompressionMethod::Stored,
+    );
+    let limits = UnzipLimits {
+      max_entries: 2,
+      ..UnzipLimits::default()
+    };
+
+    let result = unzip(bytes, &outpath(&dir), &limits);
+
+    assert!(matches!(result, Err(UnzipError::TooManyEntries(2))));
+    assert!(!outpath(&dir).exists());
+  }
+
+  #[test]
+  fn rejects_archives_exceeding_total_size() {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = zip_archive(
+      vec![("a", vec![1; 60]), ("b", vec![2; 60])],
+      CompressionMethod::Stored,
+    );
+    let limits = UnzipLimits {
+      max_total_size: 100,
+      ..UnzipLimits::default()
+    };
+
+    let result = unzip(bytes, &outpath(&dir), &limits);
+
+    assert!(matches!(result, Err(UnzipError::TooLarge(100))));
+    assert!(!outpath(&dir).exists());
+  }
+
+  #[test]
+  fn rejects_entries_exceeding_compression_ratio() {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = zip_archive(
+      vec![("zeros", vec![0; 4 * 1024 * 1024])],
+      CompressionMethod::Deflated,
+    );
+
+    let result = unzip(bytes, &outpath(&dir), &UnzipLimits::default());
+
+    assert!(matches!(result, Err(UnzipError::CompressionRatioExceeded(name)) if name == "zeros"));
+    assert!(!outpath(&dir).exists());
+  }
+
+  #[test]
+  fn rejects_path_traversal() {
+    let dir = tempfile::tempdir().unwrap();
+    let bytes = zip_archive(vec![("../evil.js", b"evil".to_vec())], CompressionMethod::Stored);
+
+    let result = unzip(bytes, &outpath(&dir), &UnzipLimits::default());
+
+    assert!(matches!(result, Err(UnzipError::UnsafePath(name)) if name == "../evil.js"));
+    assert!(!dir.path().join("evil.js").exists());
+  }
+
+  #[test]
+  fn rejects_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut bytes = zip_archive(vec![("link", b"/etc/passwd".to_vec())], CompressionMethod::Stored);
+
+    // the zip writer only creates regular files, so the entry is turned into a symlink afterwards
+    let header = bytes
+      .windows(CENTRAL_DIRECTORY_SIGNATURE.len())
+      .position(|window| window == CENTRAL_DIRECTORY_SIGNATURE)
+      .unwrap();
+    let attributes = header + EXTERNAL_ATTRIBUTES_OFFSET;
+    bytes[attributes..attributes + 4].copy_from_slice(&((S_IFLNK | 0o777) << 16).to_le_bytes());
+
+    let result = unzip(bytes, &outpath(&dir), &UnzipLimits::default());
+
+    assert!(matches!(result, Err(UnzipError::UnsupportedEntryType(name)) if name == "link"));
+    assert!(!outpath(&dir).exists());
+  }
+}
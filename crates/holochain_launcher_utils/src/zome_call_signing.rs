@@ -1,33 +1,178 @@
 
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::Mutex,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use holochain_client::AgentPubKey;
 use holochain_conductor_api::ZomeCall;
-use holochain_types::prelude::ZomeCallUnsigned;
+use holochain_types::prelude::{Nonce256Bits, ZomeCallUnsigned};
 use holochain_zome_types::{Signature, CellId, ZomeName, FunctionName, CapSecret, ExternIO, Timestamp};
 use lair_keystore_api::LairClient;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Zome calls expiring further in the future than this are not signed. Clients set
+/// expires_at to a few minutes after creating the zome call.
+pub const MAX_ZOME_CALL_VALIDITY: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Error, Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum ZomeCallSigningError {
+  #[error("The zome call has expired already.")]
+  Expired,
+  #[error("The zome call expires too far in the future. It must expire within {max_validity_secs} seconds.")]
+  ExpiresTooLate { max_validity_secs: u64 },
+  #[error("The nonce of the zome call has been used recently for the same provenance.")]
+  NonceReused,
+  #[error("Failed to get data to sign from unsigned zome call: {0}")]
+  DataToSignError(String),
+  #[error("Failed to sign zome call by pubkey: {0}")]
+  SignError(String),
+}
+
+impl From<ZomeCallSigningError> for String {
+  fn from(err: ZomeCallSigningError) -> Self {
+    err.to_string()
+  }
+}
+
+/// Rejects zome calls with an expiry outside of the accepted window and nonces that have been
+/// used for the same provenance before. Nonces are remembered until their zome call expires,
+/// since the conductor rejects expired zome calls anyway.
+#[derive(Default)]
+pub struct ZomeCallValidator {
+  seen_nonces: Mutex<SeenNonces>,
+}
+
+#[derive(Default)]
+struct SeenNonces {
+  /// expires_at in microseconds by nonce by provenance
+  by_provenance: HashMap<AgentPubKey, HashMap<Nonce256Bits, i64>>,
+  /// The nonces by expires_at, to forget the expired ones without going through all of them
+  by_expiry: BTreeMap<i64, Vec<(AgentPubKey, Nonce256Bits)>>,
+}
+
+impl SeenNonces {
+  fn forget_expired(&mut self, now: i64) {
+    let unexpired = self.by_expiry.split_off(&(now + 1));
+    let expired = std::mem::replace(&mut self.by_expiry, unexpired);
+
+    for (expires_at, entries) in expired {
+      for (provenance, nonce) in entries {
+        // skip nonces that got released and reused for a zome call expiring later
+        let reused = self
+          .by_provenance
+          .get(&provenance)
+          .and_then(|nonces| nonces.get(&nonce))
+          .map_or(false, |nonce_expires_at| *nonce_expires_at != expires_at);
+        if !reused {
+          self.forget(&provenance, &nonce);
+        }
+      }
+    }
+  }
 
+  fn forget(&mut self, provenance: &AgentPubKey, nonce: &Nonce256Bits) {
+    if let Some(nonces) = self.by_provenance.get_mut(provenance) {
+      nonces.remove(nonce);
+      if nonces.is_empty() {
+        self.by_provenance.remove(provenance);
+      }
+    }
+  }
+}
 
-/// Signs an unsigned zome call with the given LairClient
+impl ZomeCallValidator {
+  /// Reserves the nonce of a valid zome call. Concurrent zome calls with the same nonce get
+  /// rejected while it is being signed, and the nonce gets released if signing fails.
+  pub fn validate(&self, zome_call_unsigned: &ZomeCallUnsigned) -> Result<(), ZomeCallSigningError> {
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_micros() as i64)
+      .unwrap_or(0);
+    let expires_at = zome_call_unsigned.expires_at.as_micros();
+
+    if expires_at <= now {
+      return Err(ZomeCallSigningError::Expired);
+    }
+    if expires_at > now + MAX_ZOME_CALL_VALIDITY.as_micros() as i64 {
+      return Err(ZomeCallSigningError::ExpiresTooLate {
+        max_validity_secs: MAX_ZOME_CALL_VALIDITY.as_secs(),
+      });
+    }
+
+    let mut seen_nonces = self.seen_nonces.lock().unwrap_or_else(|e| e.into_inner());
+    // forget the nonces of expired zome calls of all agents
+    seen_nonces.forget_expired(now);
+
+    let nonces = seen_nonces
+      .by_provenance
+      .entry(zome_call_unsigned.provenance.clone())
+      .or_default();
+    if nonces.contains_key(&zome_call_unsigned.nonce) {
+      return Err(ZomeCallSigningError::NonceReused);
+    }
+    nonces.insert(zome_call_unsigned.nonce.clone(), expires_at);
+    seen_nonces
+      .by_expiry
+      .entry(expires_at)
+      .or_default()
+      .push((zome_call_unsigned.provenance.clone(), zome_call_unsigned.nonce.clone()));
+
+    Ok(())
+  }
+
+  /// Makes the nonce of a zome call that didn't get signed usable again
+  pub fn release(&self, provenance: &AgentPubKey, nonce: &Nonce256Bits) {
+    self
+      .seen_nonces
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .forget(provenance, nonce);
+  }
+}
+
+
+/// Validates the expiry and nonce of an unsigned zome call and signs it with the given LairClient
 pub async fn sign_zome_call_with_client(
   zome_call_unsigned: ZomeCallUnsigned,
   client: &LairClient,
-) -> Result<ZomeCall, String> {
+  validator: &ZomeCallValidator,
+) -> Result<ZomeCall, ZomeCallSigningError> {
+
+  validator.validate(&zome_call_unsigned)?;
+
+  let provenance = zome_call_unsigned.provenance.clone();
+  let nonce = zome_call_unsigned.nonce.clone();
 
+  let result = sign_validated_zome_call(zome_call_unsigned, client).await;
+  if result.is_err() {
+    validator.release(&provenance, &nonce);
+  }
+  result
+}
+
+async fn sign_validated_zome_call(
+  zome_call_unsigned: ZomeCallUnsigned,
+  client: &LairClient,
+) -> Result<ZomeCall, ZomeCallSigningError> {
   // sign the zome call
   let pub_key = zome_call_unsigned.provenance.clone();
   let mut pub_key_2 = [0; 32];
   pub_key_2.copy_from_slice(pub_key.get_raw_32());
 
   let data_to_sign = zome_call_unsigned.data_to_sign()
-    .map_err(|e| format!("Failed to get data to sign from unsigned zome call: {}", e))?;
+    .map_err(|e| ZomeCallSigningError::DataToSignError(e.to_string()))?;
 
   let sig = client.sign_by_pub_key(
     pub_key_2.into(),
      None,
     data_to_sign)
     .await
-    .map_err(|e| format!("Failed to sign zome call by pubkey: {}", e.str_kind()))?;
+    .map_err(|e| ZomeCallSigningError::SignError(e.str_kind().to_string()))?;
 
   let signature = Signature(*sig.0);
 
@@ -47,6 +192,21 @@ pub async fn sign_zome_call_with_client(
 
 }
 
+/// Validates and signs multiple unsigned zome calls concurrently with the given LairClient.
+/// Returns one result per zome call, in the same order.
+pub async fn sign_zome_calls_with_client(
+  zome_calls_unsigned: Vec<ZomeCallUnsigned>,
+  client: &LairClient,
+  validator: &ZomeCallValidator,
+) -> Vec<Result<ZomeCall, ZomeCallSigningError>> {
+  futures::future::join_all(
+    zome_calls_unsigned
+      .into_iter()
+      .map(|zome_call_unsigned| sign_zome_call_with_client(zome_call_unsigned, client, validator)),
+  )
+  .await
+}
+
 
 
 
@@ -97,4 +257,124 @@ impl Clone for ZomeCallUnsignedTauri {
       expires_at: self.expires_at.clone(),
     }
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use holochain_types::prelude::DnaHash;
+
+  fn agent_pub_key(byte: u8) -> AgentPubKey {
+    AgentPubKey::from_raw_36(vec![byte; 36])
+  }
+
+  fn micros_from_now(offset: Duration) -> i64 {
+    (SystemTime::now().duration_since(UNIX_EPOCH).unwrap() + offset).as_micros() as i64
+  }
+
+  fn zome_call(provenance: AgentPubKey, nonce: u8, expires_at: i64) -> ZomeCallUnsigned {
+    ZomeCallUnsigned {
+      cell_id: CellId::new(DnaHash::from_raw_36(vec![0; 36]), provenance.clone()),
+      provenance,
+      zome_name: ZomeName::from("zome"),
+      fn_name: FunctionName::from("fn"),
+      cap_secret: None,
+      payload: ExternIO::encode(()).unwrap(),
+      nonce: Nonce256Bits::from([nonce; 32]),
+      expires_at: Timestamp::from_micros(expires_at),
+    }
+  }
+
+  #[test]
+  fn accepts_zome_calls_expiring_soon() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::from_secs(60));
+
+    assert!(validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).is_ok());
+  }
+
+  #[test]
+  fn rejects_expired_zome_calls() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::ZERO) - 1_000_000;
+
+    assert!(matches!(
+      validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)),
+      Err(ZomeCallSigningError::Expired)
+    ));
+  }
+
+  #[test]
+  fn rejects_zome_calls_expiring_too_late() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(MAX_ZOME_CALL_VALIDITY + Duration::from_secs(60));
+
+    assert!(matches!(
+      validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)),
+      Err(ZomeCallSigningError::ExpiresTooLate { .. })
+    ));
+  }
+
+  #[test]
+  fn rejects_reused_nonces_of_the_same_provenance_only() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::from_secs(60));
+
+    validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).unwrap();
+
+    assert!(matches!(
+      validator.validate(&zome_call(agent_pub_key(1), 1, expires_at + 1)),
+      Err(ZomeCallSigningError::NonceReused)
+    ));
+    assert!(validator.validate(&zome_call(agent_pub_key(2), 1, expires_at)).is_ok());
+    assert!(validator.validate(&zome_call(agent_pub_key(1), 2, expires_at)).is_ok());
+  }
+
+  #[test]
+  fn released_nonces_can_be_used_again() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::from_secs(60));
+
+    validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).unwrap();
+    validator.release(&agent_pub_key(1), &Nonce256Bits::from([1; 32]));
+
+    assert!(validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).is_ok());
+  }
+
+  #[test]
+  fn forgets_nonces_once_their_zome_call_expired() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::from_secs(60));
+    validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).unwrap();
+    validator.validate(&zome_call(agent_pub_key(1), 2, expires_at + 10)).unwrap();
+
+    let mut seen_nonces = validator.seen_nonces.lock().unwrap();
+    seen_nonces.forget_expired(expires_at);
+
+    let nonces = seen_nonces.by_provenance.get(&agent_pub_key(1)).unwrap();
+    assert!(!nonces.contains_key(&Nonce256Bits::from([1; 32])));
+    assert!(nonces.contains_key(&Nonce256Bits::from([2; 32])));
+    assert_eq!(seen_nonces.by_expiry.keys().cloned().collect::<Vec<i64>>(), vec![expires_at + 10]);
+  }
+
+  #[test]
+  fn keeps_released_nonces_reused_for_a_later_zome_call() {
+    let validator = ZomeCallValidator::default();
+    let expires_at = micros_from_now(Duration::from_secs(60));
+    validator.validate(&zome_call(agent_pub_key(1), 1, expires_at)).unwrap();
+    validator.release(&agent_pub_key(1), &Nonce256Bits::from([1; 32]));
+    validator.validate(&zome_call(agent_pub_key(1), 1, expires_at + 10)).unwrap();
+
+    let mut seen_nonces = validator.seen_nonces.lock().unwrap();
+    seen_nonces.forget_expired(expires_at);
+
+    assert!(seen_nonces
+      .by_provenance
+      .get(&agent_pub_key(1))
+      .unwrap()
+      .contains_key(&Nonce256Bits::from([1; 32])));
+
+    seen_nonces.forget_expired(expires_at + 10);
+
+    assert!(seen_nonces.by_provenance.is_empty());
+  }
+}
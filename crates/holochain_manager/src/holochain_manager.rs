@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 use std::env::temp_dir;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::{fs, time::Duration};
 
 // NEW_VERSION change holochain_types version
 use holochain_client::{AdminWebsocket, AgentPubKey, AppInfo, InstallAppPayload};
+use holochain_conductor_api_0_2_7::CellInfo;
 use holochain_types_0_2_7::prelude::{
-  AppBundleSource, CellId, CloneCellId, DisableCloneCellPayload,
+  AppBundleSource, AppManifest, CellId, CloneCellId, CoordinatorBundle, CoordinatorSource,
+  DisableCloneCellPayload, DnaBundle, DnaFile, DnaHash, DnaManifest, DnaModifiersOpt, DnaWasm,
+  UpdateCoordinatorsPayload, WasmHash,
 };
-use lair_keystore_manager::utils::create_dir_if_necessary;
+use mr_bundle::{Bundle, Location, ResourceBytes};
+use lair_keystore_manager::{sodoken::BufRead, utils::create_dir_if_necessary};
 use tauri::api::process::CommandChild;
 
 use crate::versions::holochain_types_latest::prelude::{AppBundle, MembraneProof};
@@ -21,6 +25,13 @@ use crate::{
   versions::{version_manager::VersionManager, HolochainVersion},
 };
 
+/// Outcome of updating the coordinator zomes of an installed app
+pub enum CoordinatorUpdate {
+  Updated,
+  /// The integrity zomes of these roles changed, so their cells can't be updated in place
+  IntegrityChanged(Vec<String>),
+}
+
 pub struct HolochainManager {
   pub version: HolochainVersion,
 
@@ -31,14 +42,15 @@ pub struct HolochainManager {
   // If it changes, move this property down in the HolochainVersion
   ws: AdminWebsocket,
 
-  command_child: CommandChild,
+  /// None once the conductor has been killed
+  command_child: Option<CommandChild>,
 }
 
 impl HolochainManager {
   pub async fn launch(
     version: HolochainVersion,
     config: LaunchHolochainConfig,
-    password: String,
+    password: BufRead,
   ) -> Result<Self, LaunchHolochainError> {
     let conductor_config_path = config.conductor_config_dir.join("conductor-config.yaml");
     create_dir_if_necessary(&config.conductor_config_dir)?;
@@ -122,7 +134,7 @@ impl HolochainManager {
       ws,
       admin_interface_port: config.admin_port,
       app_interface_port,
-      command_child,
+      command_child: Some(command_child),
     })
   }
 
@@ -134,11 +146,12 @@ impl HolochainManager {
     self.app_interface_port
   }
 
-  pub fn kill(mut self) -> Result<(), String> {
-    self
-      .command_child
-      .kill()
-      .map_err(|err| format!("Could not kill the holochain process: {}", err))?;
+  pub fn kill(&mut self) -> Result<(), String> {
+    if let Some(command_child) = self.command_child.take() {
+      command_child
+        .kill()
+        .map_err(|err| format!("Could not kill the holochain process: {}", err))?;
+    }
 
     Ok(())
   }
@@ -152,13 +165,9 @@ impl HolochainManager {
     agent_pub_key: Option<AgentPubKey>,
   ) -> Result<(), String> {
     let agent_key = match agent_pub_key {
-      Some(pub_key) => Ok(pub_key),
-      None => self
-        .ws
-        .generate_agent_pub_key()
-        .await
-        .map_err(|err| format!("Error generating public key: {:?}", err)),
-    }?;
+      Some(pub_key) => pub_key,
+      None => self.generate_agent_pub_key().await?,
+    };
 
     // TODO: make this more performant
     // We could be passing the app bundle path directly if what we want to install is a headless app
@@ -173,17 +182,19 @@ impl HolochainManager {
       .map_err(|err| format!("Could not write app bundle to temp file: {}", err))?;
 
     let payload = InstallAppPayload {
-      source: AppBundleSource::Path(path),
+      source: AppBundleSource::Path(path.clone()),
       agent_key,
       installed_app_id: Some(app_id.clone().into()),
       membrane_proofs,
       network_seed,
     };
-    self
-      .ws
-      .install_app(payload)
-      .await
-      .map_err(|err| format!("Error install hApp bundle: {:?}", err))?;
+    let install_result = self.ws.install_app(payload).await;
+
+    if let Err(err) = fs::remove_file(&path) {
+      log::warn!("Failed to remove temporary app bundle file {:?}: {}", path, err);
+    }
+
+    install_result.map_err(|err| format!("Error install hApp bundle: {:?}", err))?;
 
     self
       .ws
@@ -194,6 +205,15 @@ impl HolochainManager {
     Ok(())
   }
 
+  /// Generates a new agent key in the keystore this conductor is connected to
+  pub async fn generate_agent_pub_key(&mut self) -> Result<AgentPubKey, String> {
+    self
+      .ws
+      .generate_agent_pub_key()
+      .await
+      .map_err(|err| format!("Error generating public key: {:?}", err))
+  }
+
   pub async fn uninstall_app(&mut self, app_id: String) -> Result<(), String> {
     self
       .ws
@@ -258,6 +278,205 @@ impl HolochainManager {
     Ok(installed_apps)
   }
 
+  /// Maps the agent public keys used by the installed apps to the ids of these apps
+  pub async fn agent_keys_in_use(&mut self) -> Result<HashMap<AgentPubKey, Vec<String>>, String> {
+    let mut keys_in_use: HashMap<AgentPubKey, Vec<String>> = HashMap::new();
+
+    for app_info in self.list_apps().await? {
+      keys_in_use
+        .entry(app_info.agent_pub_key)
+        .or_default()
+        .push(app_info.installed_app_id);
+    }
+
+    Ok(keys_in_use)
+  }
+
+  /// Replaces the coordinator zomes of all the cells of the given app with the ones in the given app bundle.
+  /// Nothing gets updated if the new bundle would result in a different DNA hash for any of the cells.
+  pub async fn update_coordinators(
+    &mut self,
+    app_id: &String,
+    app_bundle: &AppBundle,
+  ) -> Result<CoordinatorUpdate, String> {
+    if !self.version.manager().supports_coordinator_updates() {
+      return Err(format!(
+        "Holochain {} does not support updating coordinator zomes",
+        self.version
+      ));
+    }
+
+    let app_info = self
+      .list_apps()
+      .await?
+      .into_iter()
+      .find(|app_info| &app_info.installed_app_id == app_id)
+      .ok_or(format!("There is no app installed with the id '{}'", app_id))?;
+
+    let mut updates: Vec<(DnaHash, CoordinatorBundle)> = Vec::new();
+    let mut changed_roles: Vec<String> = Vec::new();
+
+    for role in app_bundle.manifest().app_roles() {
+      let cells = match app_info.cell_info.get(&role.name) {
+        Some(cells) => cells,
+        None => {
+          changed_roles.push(role.name.clone());
+          continue;
+        }
+      };
+
+      let location = role
+        .dna
+        .location
+        .clone()
+        .ok_or(format!("The role '{}' has no DNA location", role.name))?;
+      let dna_bytes = app_bundle
+        .resolve(&location)
+        .await
+        .map_err(|e| format!("Could not resolve the DNA of role '{}': {}", role.name, e))?;
+      let dna_bundle = DnaBundle::decode(&dna_bytes.into_owned().into_inner())
+        .map_err(|e| format!("Could not decode the DNA of role '{}': {}", role.name, e))?;
+      let coordinator_bundle = coordinator_bundle_from_dna_bundle(&dna_bundle)?;
+
+      for cell in cells {
+        let (cell_id, dna_modifiers) = match cell {
+          CellInfo::Provisioned(cell) => (cell.cell_id.clone(), cell.dna_modifiers.clone()),
+          CellInfo::Cloned(cell) => (cell.cell_id.clone(), cell.dna_modifiers.clone()),
+          CellInfo::Stem(_) => continue,
+        };
+
+        let (_dna_file, dna_hash) = dna_bundle
+          .clone()
+          .into_dna_file(DnaModifiersOpt {
+            network_seed: Some(dna_modifiers.network_seed),
+            properties: Some(dna_modifiers.properties),
+            origin_time: Some(dna_modifiers.origin_time),
+            quantum_time: Some(dna_modifiers.quantum_time),
+          })
+          .await
+          .map_err(|e| format!("Could not build the DNA of role '{}': {}", role.name, e))?;
+
+        if &dna_hash != cell_id.dna_hash() {
+          changed_roles.push(role.name.clone());
+          break;
+        }
+
+        updates.push((dna_hash, coordinator_bundle.clone()));
+      }
+    }
+
+    if !changed_roles.is_empty() {
+      return Ok(CoordinatorUpdate::IntegrityChanged(changed_roles));
+    }
+
+    for (dna_hash, coordinator_bundle) in updates {
+      self
+        .ws
+        .update_coordinators(UpdateCoordinatorsPayload {
+          dna_hash,
+          source: CoordinatorSource::Bundle(Box::new(coordinator_bundle)),
+        })
+        .await
+        .map_err(|err| format!("Error updating coordinator zomes: {:?}", err))?;
+    }
+
+    Ok(CoordinatorUpdate::Updated)
+  }
+
+  /// Rebuilds the hApp bundle of an installed app the way it is installed: the DNA of each role is
+  /// built from its DNA definition in the conductor, which carries the modifiers (network seed,
+  /// properties and origin time) of the installed cell and the coordinator zomes it runs. The admin
+  /// API doesn't hand out wasm code, so the code gets looked up by its hash in the given bundle.
+  pub async fn rebuild_app_bundle(
+    &mut self,
+    app_id: &String,
+    code_bundle: &AppBundle,
+  ) -> Result<Vec<u8>, String> {
+    let app_info = self
+      .list_apps()
+      .await?
+      .into_iter()
+      .find(|app_info| &app_info.installed_app_id == app_id)
+      .ok_or(format!("There is no app installed with the id '{}'", app_id))?;
+
+    let mut wasms: HashMap<WasmHash, DnaWasm> = HashMap::new();
+    for role in code_bundle.manifest().app_roles() {
+      if let Some(location) = role.dna.location.clone() {
+        let dna_bytes = code_bundle
+          .resolve(&location)
+          .await
+          .map_err(|e| format!("Could not resolve the DNA of role '{}': {}", role.name, e))?;
+        let dna_bundle = DnaBundle::decode(&dna_bytes.into_owned().into_inner())
+          .map_err(|e| format!("Could not decode the DNA of role '{}': {}", role.name, e))?;
+        let (dna_file, _dna_hash) = dna_bundle
+          .into_dna_file(DnaModifiersOpt::none())
+          .await
+          .map_err(|e| format!("Could not build the DNA of role '{}': {}", role.name, e))?;
+        wasms.extend(dna_file.code().clone());
+      }
+    }
+
+    let mut manifest = app_info.manifest.clone();
+    let roles = match &mut manifest {
+      AppManifest::V1(manifest) => &mut manifest.roles,
+    };
+
+    let mut resources = Vec::new();
+
+    for role in roles.iter_mut() {
+      let cell_id = app_info
+        .cell_info
+        .get(&role.name)
+        .into_iter()
+        .flatten()
+        .find_map(|cell| match cell {
+          CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+          _ => None,
+        })
+        .ok_or(format!("The role '{}' has no provisioned cell", role.name))?;
+
+      let dna_def = self
+        .ws
+        .get_dna_definition(cell_id.dna_hash().clone())
+        .await
+        .map_err(|e| format!("Could not get the DNA definition of role '{}': {:?}", role.name, e))?;
+
+      let code = dna_def
+        .all_zomes()
+        .map(|(zome_name, zome_def)| {
+          zome_def
+            .wasm_hash(zome_name)
+            .ok()
+            .and_then(|wasm_hash| wasms.get(&wasm_hash).cloned())
+            .ok_or(format!("The code of zome '{}' of role '{}' is not available", zome_name, role.name))
+        })
+        .collect::<Result<Vec<DnaWasm>, String>>()?;
+
+      let dna_file = DnaFile::new(dna_def, code).await;
+      if dna_file.dna_hash() != cell_id.dna_hash() {
+        return Err(format!("The rebuilt DNA of role '{}' doesn't match the installed one", role.name));
+      }
+
+      let dna_bundle = DnaBundle::from_dna_file(dna_file)
+        .await
+        .map_err(|e| format!("Could not bundle the DNA of role '{}': {}", role.name, e))?;
+      let dna_bytes = dna_bundle
+        .encode()
+        .map_err(|e| format!("Could not encode the DNA of role '{}': {}", role.name, e))?;
+
+      // the DNA bundle carries the modifiers of the installed cell
+      let dna_path = PathBuf::from(format!("{}.dna", role.name));
+      role.dna.location = Some(Location::Bundled(dna_path.clone()));
+      role.dna.modifiers = DnaModifiersOpt::none();
+      role.dna.installed_hash = Some(cell_id.dna_hash().clone().into());
+      resources.push((dna_path, ResourceBytes::from(dna_bytes)));
+    }
+
+    Bundle::new_unchecked(manifest, resources)
+      .and_then(|bundle| bundle.encode())
+      .map_err(|e| format!("Failed to assemble the hApp bundle: {}", e))
+  }
+
   pub async fn dump_network_stats(&mut self) -> Result<String, String> {
     let network_stats = self
       .ws
@@ -268,3 +487,15 @@ impl HolochainManager {
     Ok(network_stats)
   }
 }
+
+fn coordinator_bundle_from_dna_bundle(dna_bundle: &DnaBundle) -> Result<CoordinatorBundle, String> {
+  let coordinator_manifest = match &**dna_bundle.manifest() {
+    DnaManifest::V1(manifest) => manifest.coordinator.clone(),
+  };
+
+  CoordinatorBundle::new_unchecked(
+    coordinator_manifest,
+    dna_bundle.bundled_resources().clone().into_iter(),
+  )
+  .map_err(|e| format!("Could not build coordinator bundle: {}", e))
+}
@@ -2,7 +2,7 @@ use log;
 use std::{collections::HashMap, path::PathBuf};
 use tauri::api::process::{Command, CommandChild, CommandEvent};
 
-use lair_keystore_manager::error::LaunchChildError;
+use lair_keystore_manager::{error::LaunchChildError, sodoken::BufRead};
 
 use crate::{errors::LaunchHolochainError, errors::InitializeConductorError, versions::HolochainVersion};
 
@@ -18,7 +18,7 @@ pub async fn launch_holochain_process(
   version: HolochainVersion,
   command: Command,
   conductor_config_path: PathBuf,
-  password: String,
+  password: BufRead,
 ) -> Result<CommandChild, LaunchHolochainError> {
   let mut envs = HashMap::new();
   envs.insert(String::from("RUST_LOG"), String::from(log_level.as_str()));
@@ -41,7 +41,7 @@ pub async fn launch_holochain_process(
 
 
   holochain_child
-    .write(password.as_bytes())
+    .write(&*password.read_lock())
     .map_err(|err| LaunchHolochainError::ErrorWritingPassword(format!("{:?}", err)))?;
   holochain_child
     .write("\n".as_bytes())
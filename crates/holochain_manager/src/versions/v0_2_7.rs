@@ -30,6 +30,11 @@ impl VersionManager for HolochainV0_2_7 {
     HdiVersion::V0_3_7
   }
 
+  // NEW_VERSION: Indicate here whether the admin API of this Holochain version has update_coordinators
+  fn supports_coordinator_updates(&self) -> bool {
+    true
+  }
+
   // NEW_VERSION: Duplicate and change whatever config is necessary to change
   fn initial_config(
     &self,
@@ -18,6 +18,9 @@ pub trait VersionManager {
     LairKeystoreVersion::V0_3
   }
 
+  /// Whether the conductor can replace the coordinator zomes of installed cells in place
+  fn supports_coordinator_updates(&self) -> bool;
+
   fn initial_config(
     &self,
     admin_port: u16,
@@ -0,0 +1,56 @@
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use holochain_manager::versions::{holochain_types_latest::prelude::CellId, HolochainVersion};
+use serde::{Deserialize, Serialize};
+
+/// Name of the tauri event the AppLifecycleEventPayloads are emitted as
+pub const APP_LIFECYCLE_EVENT: &str = "app-lifecycle-event";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum AppLifecycleEvent {
+  AppInstalled { app_id: String },
+  AppUninstalled { app_id: String },
+  AppEnabled { app_id: String },
+  AppDisabled { app_id: String },
+  /// The coordinator zomes of the app changed, either by an upgrade or by rolling one back
+  AppUpgraded { app_id: String },
+  /// A UI got added, updated, rolled back or removed
+  UiUpdated { app_id: String, ui_name: String },
+  CloneDeleted { app_id: String, cell_id: CellId },
+}
+
+impl AppLifecycleEvent {
+  pub fn app_id(&self) -> &String {
+    match self {
+      AppLifecycleEvent::AppInstalled { app_id }
+      | AppLifecycleEvent::AppUninstalled { app_id }
+      | AppLifecycleEvent::AppEnabled { app_id }
+      | AppLifecycleEvent::AppDisabled { app_id }
+      | AppLifecycleEvent::AppUpgraded { app_id }
+      | AppLifecycleEvent::UiUpdated { app_id, .. }
+      | AppLifecycleEvent::CloneDeleted { app_id, .. } => app_id,
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AppLifecycleEventPayload {
+  pub holochain_version: HolochainVersion,
+  pub event: AppLifecycleEvent,
+}
+
+#[derive(Default)]
+pub(crate) struct AppEventSenders(Vec<UnboundedSender<AppLifecycleEvent>>);
+
+impl AppEventSenders {
+  pub fn subscribe(&mut self) -> UnboundedReceiver<AppLifecycleEvent> {
+    let (sender, receiver) = unbounded();
+    self.0.push(sender);
+    receiver
+  }
+
+  pub fn emit(&mut self, event: &AppLifecycleEvent) {
+    // drop the subscribers that went away
+    self.0.retain(|sender| sender.unbounded_send(event.clone()).is_ok());
+  }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::{zome_call_policy::ZomeCallPolicy, AppUpgrade, ReleaseInfo};
+
+/// Version of the format of the metadata index, to be bumped on incompatible changes
+pub const APP_METADATA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum InstallSource {
+  Filesystem,
+  AppLibrary,
+  /// Installed as the new release of another app whose integrity zomes changed
+  Upgrade { previous_app_id: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UiMetadata {
+  pub gui_release_info: Option<ReleaseInfo>,
+  /// Kept to be able to revert UI updates
+  pub previous_gui_release_info: Option<ReleaseInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AppMetadata {
+  pub happ_release_info: Option<ReleaseInfo>,
+  /// Kept to be able to revert hApp upgrades
+  pub previous_happ_release_info: Option<ReleaseInfo>,
+  pub uis: BTreeMap<String, UiMetadata>,
+  /// Base64 string of the format 'data:image/png;base64,[...]'
+  pub icon_src: Option<String>,
+  /// Unknown for apps installed before it got recorded
+  pub install_source: Option<InstallSource>,
+  /// Milliseconds since the unix epoch, unknown for apps installed before it got recorded
+  pub installed_at: Option<u64>,
+  pub network_seed: Option<String>,
+  /// Name given to the app by the user
+  pub label: Option<String>,
+  pub last_upgrade: Option<AppUpgrade>,
+  /// Declared by the app or set by the user, see ZomeCallPolicy
+  pub zome_call_policy: Option<ZomeCallPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AppMetadataIndex {
+  version: u32,
+  apps: BTreeMap<String, AppMetadata>,
+  #[serde(default)]
+  ui_assets_manifests_recorded: bool,
+}
+
+/// Metadata of all the apps installed in one conductor, stored in a single yaml file
+pub struct AppMetadataStore {
+  path: PathBuf,
+  apps: BTreeMap<String, AppMetadata>,
+  /// Whether the assets manifests of the UIs installed before they got recorded have been recorded
+  ui_assets_manifests_recorded: bool,
+}
+
+impl AppMetadataStore {
+  /// Loads the store from the given path. If it doesn't exist yet, the metadata gets imported from the
+  /// dot files that older versions of the launcher kept in the data directory of each app. The dot
+  /// files are left in place for at least one release, so that downgrading the launcher keeps working.
+  pub fn load(path: PathBuf, apps_data_dir: &PathBuf) -> Result<Self, String> {
+    if !path.exists() {
+      let store = AppMetadataStore {
+        apps: import_dot_files(apps_data_dir),
+        path,
+        ui_assets_manifests_recorded: false,
+      };
+      store.save()?;
+
+      return Ok(store);
+    }
+
+    let s = fs::read_to_string(&path)
+      .map_err(|e| format!("Failed to read app metadata: {}", e))?;
+    let index = serde_yaml::from_str::<AppMetadataIndex>(s.as_str())
+      .map_err(|e| format!("Failed to parse app metadata: {}", e))?;
+
+    if index.version > APP_METADATA_VERSION {
+      return Err(format!(
+        "The app metadata at {:?} was written by a newer version of the launcher (format version {})",
+        path, index.version
+      ));
+    }
+
+    Ok(AppMetadataStore {
+      path,
+      apps: index.apps,
+      ui_assets_manifests_recorded: index.ui_assets_manifests_recorded,
+    })
+  }
+
+  pub fn app_ids(&self) -> Vec<String> {
+    self.apps.keys().cloned().collect()
+  }
+
+  pub fn get(&self, app_id: &String) -> Option<&AppMetadata> {
+    self.apps.get(app_id)
+  }
+
+  /// Applies the given change to the metadata of an app, creating it if necessary, and saves the store
+  pub fn update<F: FnOnce(&mut AppMetadata)>(&mut self, app_id: &String, change: F) -> Result<(), String> {
+    change(self.apps.entry(app_id.clone()).or_default());
+    self.save()
+  }
+
+  pub fn ui_assets_manifests_recorded(&self) -> bool {
+    self.ui_assets_manifests_recorded
+  }
+
+  pub fn set_ui_assets_manifests_recorded(&mut self) -> Result<(), String> {
+    self.ui_assets_manifests_recorded = true;
+    self.save()
+  }
+
+  pub fn remove(&mut self, app_id: &String) -> Result<(), String> {
+    if self.apps.remove(app_id).is_some() {
+      self.save()?;
+    }
+    Ok(())
+  }
+
+  /// Writes to a temporary file first so that a crash while writing can't corrupt the store
+  fn save(&self) -> Result<(), String> {
+    let index = AppMetadataIndex {
+      version: APP_METADATA_VERSION,
+      apps: self.apps.clone(),
+      ui_assets_manifests_recorded: self.ui_assets_manifests_recorded,
+    };
+    let s = serde_yaml::to_string(&index)
+      .map_err(|e| format!("Failed to serialize app metadata: {}", e))?;
+
+    let temp_path = self.path.with_extension("yaml.tmp");
+    fs::write(&temp_path, s).map_err(|e| format!("Failed to write app metadata: {}", e))?;
+    fs::rename(&temp_path, &self.path).map_err(|e| format!("Failed to write app metadata: {}", e))
+  }
+}
+
+fn read_dot_file<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+  let s = fs::read_to_string(path).ok()?;
+  match serde_yaml::from_str::<T>(s.as_str()) {
+    Ok(value) => Some(value),
+    Err(e) => {
+      log::warn!("Ignoring unreadable metadata file {:?}: {}", path, e);
+      None
+    }
+  }
+}
+
+fn import_dot_files(apps_data_dir: &PathBuf) -> BTreeMap<String, AppMetadata> {
+  let mut apps = BTreeMap::new();
+
+  let app_dirs = match fs::read_dir(apps_data_dir) {
+    Ok(entries) => entries.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()),
+    Err(_) => return apps,
+  };
+
+  for app_dir in app_dirs {
+    let app_id = match app_dir.file_name().to_str() {
+      Some(app_id) => app_id.to_string(),
+      None => continue,
+    };
+    let app_dir = app_dir.path();
+
+    let mut metadata = AppMetadata {
+      happ_release_info: read_dot_file(&app_dir.join(".happrelease")),
+      previous_happ_release_info: read_dot_file(&app_dir.join(".happrelease.previous")),
+      icon_src: fs::read_to_string(app_dir.join(".icon")).ok(),
+      last_upgrade: read_dot_file(&app_dir.join(".upgrade")),
+      ..Default::default()
+    };
+
+    if let Ok(ui_dirs) = fs::read_dir(app_dir.join("uis")) {
+      for ui_dir in ui_dirs.filter_map(|e| e.ok()) {
+        if let Some(ui_name) = ui_dir.file_name().to_str() {
+          metadata.uis.insert(
+            ui_name.to_string(),
+            UiMetadata {
+              gui_release_info: read_dot_file(&ui_dir.path().join(".guirelease")),
+              previous_gui_release_info: read_dot_file(&ui_dir.path().join(".guirelease.previous")),
+            },
+          );
+        }
+      }
+    }
+
+    log::info!("Imported metadata of app '{}' from dot files", app_id);
+    apps.insert(app_id, metadata);
+  }
+
+  apps
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  /// Temporary directory with an empty apps data directory
+  fn temp_dir() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(apps_dir(&dir)).unwrap();
+    dir
+  }
+
+  fn store_path(dir: &TempDir) -> PathBuf {
+    dir.path().join("app-metadata.yaml")
+  }
+
+  fn apps_dir(dir: &TempDir) -> PathBuf {
+    dir.path().join("apps")
+  }
+
+  fn release_info(version: &str) -> ReleaseInfo {
+    ReleaseInfo {
+      resource_locator: None,
+      version: Some(version.to_string()),
+    }
+  }
+
+  fn write_dot_file(path: PathBuf, release_info: &ReleaseInfo) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, serde_yaml::to_string(release_info).unwrap()).unwrap();
+  }
+
+  #[test]
+  fn imports_dot_files_and_keeps_them() {
+    let dir = temp_dir();
+    let app_dir = apps_dir(&dir).join("app");
+    write_dot_file(app_dir.join(".happrelease"), &release_info("1.0"));
+    write_dot_file(app_dir.join(".happrelease.previous"), &release_info("0.9"));
+    write_dot_file(app_dir.join("uis").join("default").join(".guirelease"), &release_info("2.0"));
+    fs::write(app_dir.join(".icon"), "data:image/png;base64,AAAA").unwrap();
+
+    let store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+
+    let metadata = store.get(&String::from("app")).unwrap();
+    assert_eq!(metadata.happ_release_info.as_ref().unwrap().version, Some(String::from("1.0")));
+    assert_eq!(metadata.previous_happ_release_info.as_ref().unwrap().version, Some(String::from("0.9")));
+    assert_eq!(metadata.icon_src, Some(String::from("data:image/png;base64,AAAA")));
+    let ui = metadata.uis.get("default").unwrap();
+    assert_eq!(ui.gui_release_info.as_ref().unwrap().version, Some(String::from("2.0")));
+    assert!(ui.previous_gui_release_info.is_none());
+
+    assert!(store_path(&dir).exists());
+    assert!(app_dir.join(".happrelease").exists());
+  }
+
+  #[test]
+  fn ignores_unreadable_dot_files() {
+    let dir = temp_dir();
+    let app_dir = apps_dir(&dir).join("app");
+    fs::create_dir_all(&app_dir).unwrap();
+    fs::write(app_dir.join(".happrelease"), "version: [").unwrap();
+
+    let store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+
+    assert!(store.get(&String::from("app")).unwrap().happ_release_info.is_none());
+  }
+
+  #[test]
+  fn saved_metadata_gets_loaded_again() {
+    let dir = temp_dir();
+    let mut store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+    store
+      .update(&String::from("app"), |metadata| {
+        metadata.label = Some(String::from("My App"));
+        metadata.happ_release_info = Some(release_info("1.0"));
+      })
+      .unwrap();
+    store.set_ui_assets_manifests_recorded().unwrap();
+
+    // dot files are only imported as long as there is no store yet
+    write_dot_file(apps_dir(&dir).join("other").join(".happrelease"), &release_info("1.0"));
+    let store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+
+    assert_eq!(store.app_ids(), vec![String::from("app")]);
+    let metadata = store.get(&String::from("app")).unwrap();
+    assert_eq!(metadata.label, Some(String::from("My App")));
+    assert_eq!(metadata.happ_release_info.as_ref().unwrap().version, Some(String::from("1.0")));
+    assert!(store.ui_assets_manifests_recorded());
+    assert!(!store_path(&dir).with_extension("yaml.tmp").exists());
+  }
+
+  #[test]
+  fn removed_apps_stay_removed() {
+    let dir = temp_dir();
+    let mut store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+    store.update(&String::from("app"), |metadata| metadata.label = Some(String::from("My App"))).unwrap();
+    store.remove(&String::from("app")).unwrap();
+
+    let store = AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).unwrap();
+
+    assert!(store.get(&String::from("app")).is_none());
+  }
+
+  #[test]
+  fn refuses_metadata_of_newer_launchers() {
+    let dir = temp_dir();
+    fs::write(store_path(&dir), format!("version: {}\napps: {{}}\n", APP_METADATA_VERSION + 1)).unwrap();
+
+    assert!(AppMetadataStore::load(store_path(&dir), &apps_dir(&dir)).is_err());
+  }
+}
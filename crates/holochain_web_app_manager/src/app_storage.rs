@@ -0,0 +1,159 @@
+use holochain_manager::versions::{
+  holochain_conductor_api_latest::{AppInfo, CellInfo},
+  holochain_types_latest::prelude::{CellId, DnaHashB64},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::{BTreeSet, HashMap},
+  fs,
+  path::PathBuf,
+};
+
+use crate::{web_app_manager::compute_storage_info, StorageInfo};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CellStorageInfo {
+  pub role_name: String,
+  pub dna_hash: DnaHashB64,
+  /// Source chain of the agent in this cell
+  pub authored: u64,
+  /// The dht and cache databases exist once per DNA, so they are shared by all cells of the same DNA
+  pub dht: u64,
+  pub cache: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppStorageInfo {
+  pub app_id: String,
+  /// UI assets, previous UI revisions and localStorage of all UIs
+  pub uis: u64,
+  /// localStorage part of uis
+  pub local_storage: u64,
+  /// Everything else in the app's data directory, e.g. the stored hApp bundles
+  pub other_app_data: u64,
+  pub cells: Vec<CellStorageInfo>,
+  /// Apps sharing the dht and cache databases of at least one DNA with this app
+  pub shares_dna_databases_with: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorageReport {
+  pub totals: StorageInfo,
+  pub apps: Vec<AppStorageInfo>,
+}
+
+/// Everything needed to calculate the storage used by the apps of a conductor. Walking
+/// the directories is slow, so this is meant to be computed on a blocking thread.
+pub struct StorageScan {
+  pub(crate) apps_data_dir: PathBuf,
+  pub(crate) conductor_dir: PathBuf,
+  pub(crate) installed_apps: Vec<AppInfo>,
+}
+
+impl StorageScan {
+  pub fn compute(self) -> Result<StorageReport, String> {
+    let totals = compute_storage_info(&self.apps_data_dir, &self.conductor_dir)?;
+
+    let authored = dir_entry_sizes(&self.conductor_dir.join("authored"));
+    let dht = dir_entry_sizes(&self.conductor_dir.join("dht"));
+    let cache = dir_entry_sizes(&self.conductor_dir.join("cache"));
+
+    let mut apps_by_dna: HashMap<DnaHashB64, BTreeSet<String>> = HashMap::new();
+    for app in self.installed_apps.iter() {
+      for cell in app.cell_info.values().flatten() {
+        if let Some(cell_id) = cell_id(cell) {
+          apps_by_dna
+            .entry(DnaHashB64::from(cell_id.dna_hash().clone()))
+            .or_default()
+            .insert(app.installed_app_id.clone());
+        }
+      }
+    }
+
+    let mut apps = Vec::new();
+    for app in self.installed_apps.iter() {
+      let app_dir = self.apps_data_dir.join(&app.installed_app_id);
+      let app_data_size = dir_size(&app_dir);
+      let uis_size = dir_size(&app_dir.join("uis"));
+      let local_storage_size = match fs::read_dir(app_dir.join("uis")) {
+        Ok(entries) => entries
+          .filter_map(|e| e.ok())
+          .map(|e| dir_size(&e.path().join("tauri")))
+          .sum(),
+        Err(_) => 0,
+      };
+
+      let mut cells = Vec::new();
+      let mut shares_with = BTreeSet::new();
+      for (role_name, role_cells) in app.cell_info.iter() {
+        for cell_id in role_cells.iter().filter_map(cell_id) {
+          // the conductor names the database files after the hashes in this format
+          let dna_prefix = cell_id.dna_hash().to_string();
+          let cell_prefix = format!("{}-{}", cell_id.dna_hash(), cell_id.agent_pubkey());
+          let dna_hash = DnaHashB64::from(cell_id.dna_hash().clone());
+
+          if let Some(sharing_apps) = apps_by_dna.get(&dna_hash) {
+            shares_with.extend(sharing_apps.iter().filter(|a| **a != app.installed_app_id).cloned());
+          }
+
+          cells.push(CellStorageInfo {
+            role_name: role_name.clone(),
+            dna_hash,
+            authored: sum_with_prefix(&authored, &cell_prefix),
+            dht: sum_with_prefix(&dht, &dna_prefix),
+            cache: sum_with_prefix(&cache, &dna_prefix),
+          });
+        }
+      }
+
+      apps.push(AppStorageInfo {
+        app_id: app.installed_app_id.clone(),
+        uis: uis_size,
+        local_storage: local_storage_size,
+        other_app_data: app_data_size.saturating_sub(uis_size),
+        cells,
+        shares_dna_databases_with: shares_with.into_iter().collect(),
+      });
+    }
+
+    Ok(StorageReport { totals, apps })
+  }
+}
+
+fn cell_id(cell: &CellInfo) -> Option<&CellId> {
+  match cell {
+    CellInfo::Provisioned(cell) => Some(&cell.cell_id),
+    CellInfo::Cloned(cell) => Some(&cell.cell_id),
+    CellInfo::Stem(_) => None,
+  }
+}
+
+fn dir_size(path: &PathBuf) -> u64 {
+  match path.exists() {
+    true => fs_extra::dir::get_size(path).unwrap_or(0),
+    false => 0,
+  }
+}
+
+/// Sizes of the entries of a directory by file name. Databases consist of several files
+/// (e.g. the -wal and -shm files of sqlite), which share the name of the database as prefix.
+fn dir_entry_sizes(dir: &PathBuf) -> Vec<(String, u64)> {
+  match fs::read_dir(dir) {
+    Ok(entries) => entries
+      .filter_map(|e| e.ok())
+      .filter_map(|e| {
+        let name = e.file_name().to_str()?.to_string();
+        Some((name, fs_extra::dir::get_size(e.path()).unwrap_or(0)))
+      })
+      .collect(),
+    Err(_) => vec![],
+  }
+}
+
+fn sum_with_prefix(entries: &Vec<(String, u64)>, prefix: &String) -> u64 {
+  entries
+    .iter()
+    .filter(|(name, _)| name.starts_with(prefix.as_str()))
+    .map(|(_, size)| size)
+    .sum()
+}
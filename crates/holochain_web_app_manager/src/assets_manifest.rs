@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+  collections::BTreeMap,
+  fs,
+  io,
+  path::{Path, PathBuf},
+};
+
+/// SHA-256 hashes of all the files of an installed UI, recorded when the UI gets installed
+/// or updated to be able to detect if its assets have been tampered with afterwards
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AssetsManifest {
+  /// Hash over all the files and their paths, in hex
+  pub hash: String,
+  /// Relative path of each file mapped to the hex hash of its content
+  pub files: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum AssetsVerification {
+  Valid,
+  /// No manifest has been recorded for this UI. Manifests of UIs installed by older versions of the
+  /// launcher get recorded at launch, so a missing manifest means it has been removed.
+  NoManifest,
+  /// The paths of the files that were changed, added or removed since the manifest was recorded
+  Tampered(Vec<String>),
+}
+
+impl AssetsManifest {
+  pub fn compute(assets_dir: &PathBuf) -> Result<Self, String> {
+    let mut files = BTreeMap::new();
+    hash_dir(assets_dir, assets_dir, &mut files)
+      .map_err(|e| format!("Failed to hash UI assets in {:?}: {}", assets_dir, e))?;
+
+    let mut hasher = Sha256::new();
+    for (path, file_hash) in files.iter() {
+      hasher.update(path.as_bytes());
+      hasher.update([0u8]);
+      hasher.update(file_hash.as_bytes());
+      hasher.update([b'\n']);
+    }
+
+    Ok(AssetsManifest {
+      hash: hex::encode(hasher.finalize()),
+      files,
+    })
+  }
+
+  pub fn read(path: &PathBuf) -> Result<Option<Self>, String> {
+    match fs::read_to_string(path) {
+      Ok(s) => serde_yaml::from_str::<Self>(s.as_str())
+        .map(Some)
+        .map_err(|e| format!("Failed to parse UI assets manifest: {}", e)),
+      Err(_) => Ok(None),
+    }
+  }
+
+  pub fn write(&self, path: &PathBuf) -> Result<(), String> {
+    let s = serde_yaml::to_string(self)
+      .map_err(|e| format!("Failed to serialize UI assets manifest: {}", e))?;
+    fs::write(path, s).map_err(|e| format!("Failed to write UI assets manifest: {}", e))
+  }
+
+  /// Compares the given assets directory against the manifest stored at the given path. Hashes all
+  /// the files, so it should be run on a blocking thread.
+  pub fn verify_at(manifest_path: &PathBuf, assets_dir: &PathBuf) -> Result<AssetsVerification, String> {
+    match Self::read(manifest_path)? {
+      Some(manifest) => manifest.verify(assets_dir),
+      None => Ok(AssetsVerification::NoManifest),
+    }
+  }
+
+  /// Compares the given assets directory against this manifest
+  pub fn verify(&self, assets_dir: &PathBuf) -> Result<AssetsVerification, String> {
+    let current = Self::compute(assets_dir)?;
+    if current.hash == self.hash {
+      return Ok(AssetsVerification::Valid);
+    }
+
+    let mut changed: Vec<String> = self
+      .files
+      .iter()
+      .filter(|(path, file_hash)| current.files.get(*path) != Some(file_hash))
+      .map(|(path, _)| path.clone())
+      .collect();
+    changed.extend(
+      current
+        .files
+        .keys()
+        .filter(|path| !self.files.contains_key(*path))
+        .cloned(),
+    );
+
+    Ok(AssetsVerification::Tampered(changed))
+  }
+}
+
+fn hash_dir(root: &Path, dir: &Path, files: &mut BTreeMap<String, String>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      hash_dir(root, &path, files)?;
+    } else {
+      let mut hasher = Sha256::new();
+      io::copy(&mut fs::File::open(&path)?, &mut hasher)?;
+
+      let relative_path = path
+        .strip_prefix(root)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/");
+      files.insert(relative_path, hex::encode(hasher.finalize()));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  /// Temporary UI directory with a few assets
+  fn ui_dir() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(assets_dir(&dir).join("js")).unwrap();
+    fs::write(assets_dir(&dir).join("index.html"), "<html></html>").unwrap();
+    fs::write(assets_dir(&dir).join("js").join("app.js"), "console.log('app')").unwrap();
+    dir
+  }
+
+  fn assets_dir(dir: &TempDir) -> PathBuf {
+    dir.path().join("assets")
+  }
+
+  fn tampered_files(verification: AssetsVerification) -> Vec<String> {
+    match verification {
+      AssetsVerification::Tampered(mut files) => {
+        files.sort();
+        files
+      }
+      other => panic!("Expected tampered assets, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn records_all_files_with_relative_paths() {
+    let dir = ui_dir();
+
+    let manifest = AssetsManifest::compute(&assets_dir(&dir)).unwrap();
+
+    assert_eq!(
+      manifest.files.keys().cloned().collect::<Vec<String>>(),
+      vec![String::from("index.html"), String::from("js/app.js")]
+    );
+    assert_eq!(manifest, AssetsManifest::compute(&assets_dir(&dir)).unwrap());
+  }
+
+  #[test]
+  fn unchanged_assets_are_valid() {
+    let dir = ui_dir();
+    let manifest = AssetsManifest::compute(&assets_dir(&dir)).unwrap();
+
+    assert!(matches!(manifest.verify(&assets_dir(&dir)).unwrap(), AssetsVerification::Valid));
+  }
+
+  #[test]
+  fn detects_changed_added_and_removed_files() {
+    let dir = ui_dir();
+    let manifest = AssetsManifest::compute(&assets_dir(&dir)).unwrap();
+
+    fs::write(assets_dir(&dir).join("js").join("app.js"), "steal()").unwrap();
+    fs::write(assets_dir(&dir).join("extra.js"), "steal()").unwrap();
+    fs::remove_file(assets_dir(&dir).join("index.html")).unwrap();
+
+    assert_eq!(
+      tampered_files(manifest.verify(&assets_dir(&dir)).unwrap()),
+      vec![String::from("extra.js"), String::from("index.html"), String::from("js/app.js")]
+    );
+  }
+
+  #[test]
+  fn renamed_files_are_detected() {
+    let dir = ui_dir();
+    let manifest = AssetsManifest::compute(&assets_dir(&dir)).unwrap();
+
+    fs::rename(
+      assets_dir(&dir).join("index.html"),
+      assets_dir(&dir).join("main.html"),
+    )
+    .unwrap();
+
+    assert_eq!(
+      tampered_files(manifest.verify(&assets_dir(&dir)).unwrap()),
+      vec![String::from("index.html"), String::from("main.html")]
+    );
+  }
+
+  #[test]
+  fn verifies_against_the_written_manifest() {
+    let dir = ui_dir();
+    let manifest_path = dir.path().join("assets-manifest.yaml");
+
+    assert!(matches!(
+      AssetsManifest::verify_at(&manifest_path, &assets_dir(&dir)).unwrap(),
+      AssetsVerification::NoManifest
+    ));
+
+    let manifest = AssetsManifest::compute(&assets_dir(&dir)).unwrap();
+    manifest.write(&manifest_path).unwrap();
+
+    assert_eq!(AssetsManifest::read(&manifest_path).unwrap(), Some(manifest));
+    assert!(matches!(
+      AssetsManifest::verify_at(&manifest_path, &assets_dir(&dir)).unwrap(),
+      AssetsVerification::Valid
+    ));
+  }
+}
@@ -12,6 +12,7 @@ pub enum WebUiInfo {
     path_to_ui: PathBuf,
     app_ui_port: u16,
     gui_release_info: Option<ReleaseInfo>, // B64 hash
+    assets_hash: Option<String>, // hex SHA-256 hash over all UI assets, see AssetsManifest
   },
 }
 
@@ -19,6 +20,8 @@ pub enum WebUiInfo {
 pub struct InstalledWebAppInfo {
   pub installed_app_info: AppInfo,
   pub happ_release_info: Option<ReleaseInfo>,
-  pub web_uis: HashMap<String, WebUiInfo>, // Name of the UI as key. Currently only "default"
+  pub web_uis: HashMap<String, WebUiInfo>, // Name of the UI as key. The UI shipped with the .webhapp is called "default"
   pub icon_src: Option<String>,
+  pub label: Option<String>, // Name given to the app by the user
+  pub installed_at: Option<u64>, // Milliseconds since the unix epoch, unknown for apps installed by older launcher versions
 }
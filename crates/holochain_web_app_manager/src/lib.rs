@@ -1,6 +1,12 @@
 mod web_app_manager;
+pub mod app_events;
+pub mod app_metadata;
+pub mod app_storage;
+pub mod assets_manifest;
 pub mod error;
-mod utils;
 pub mod installed_web_app_info;
+mod utils;
+pub mod window_registry;
+pub mod zome_call_policy;
 
 pub use web_app_manager::*;
@@ -1,32 +1,42 @@
 use std::{
-  fs::{self, File},
-  io,
-  path::PathBuf,
+  fs,
+  io::{self, Cursor, Write},
+  path::Path,
 };
+use zip::{write::FileOptions, ZipWriter};
 
-pub fn unzip_file(reader: File, outpath: PathBuf) -> Result<(), String> {
-  let mut archive = match zip::ZipArchive::new(reader) {
-    Ok(a) => a,
-    Err(e) => return Err(format!("Failed to unpack zip archive: {}", e)),
-  };
-
-  for i in 0..archive.len() {
-    let mut file = archive.by_index(i).unwrap();
-    let outpath = match file.enclosed_name() {
-      Some(path) => outpath.join(path).to_owned(),
-      None => continue,
-    };
-
-    if (&*file.name()).ends_with('/') {
-      fs::create_dir_all(&outpath).unwrap();
+/// Zips the contents of the given directory, with paths relative to it
+pub fn zip_dir(dir: &Path) -> Result<Vec<u8>, String> {
+  let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+  add_dir_to_zip(&mut writer, dir, dir)
+    .map_err(|e| format!("Failed to zip directory {:?}: {}", dir, e))?;
+
+  let cursor = writer
+    .finish()
+    .map_err(|e| format!("Failed to zip directory {:?}: {}", dir, e))?;
+
+  Ok(cursor.into_inner())
+}
+
+fn add_dir_to_zip(writer: &mut ZipWriter<Cursor<Vec<u8>>>, root: &Path, dir: &Path) -> io::Result<()> {
+  let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+  entries.sort_by_key(|entry| entry.file_name());
+
+  for entry in entries {
+    let path = entry.path();
+    let name = path
+      .strip_prefix(root)
+      .unwrap_or(&path)
+      .to_string_lossy()
+      .replace('\\', "/");
+
+    if path.is_dir() {
+      writer.add_directory(format!("{}/", name), FileOptions::default())?;
+      add_dir_to_zip(writer, root, &path)?;
     } else {
-      if let Some(p) = outpath.parent() {
-        if !p.exists() {
-          fs::create_dir_all(&p).unwrap();
-        }
-      }
-      let mut outfile = fs::File::create(&outpath).unwrap();
-      io::copy(&mut file, &mut outfile).unwrap();
+      writer.start_file(name, FileOptions::default())?;
+      writer.write_all(&fs::read(&path)?)?;
     }
   }
 
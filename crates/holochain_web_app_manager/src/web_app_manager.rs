@@ -1,30 +1,40 @@
 use holochain_manager::{
   config::LaunchHolochainConfig,
   versions::{
-    holochain_conductor_api_latest::AppInfo,
+    holochain_conductor_api_latest::{AppInfo, AppInfoStatus, CellInfo},
     holochain_types_latest::{
-      prelude::{AgentPubKey, AppBundle, MembraneProof, CellId, DnaHash, AnyDhtHash, DnaHashB64, AnyDhtHashB64},
-      web_app::WebAppBundle,
+      prelude::{AgentPubKey, AppBundle, AppManifest, MembraneProof, CellId, DnaHash, AnyDhtHash, DnaHashB64, AnyDhtHashB64},
+      web_app::{AppManifestLocation, WebAppBundle, WebAppManifest, WebAppManifestV1, WebUI},
     },
-    mr_bundle_latest::ResourceBytes,
+    mr_bundle_latest::{Bundle, Location, ResourceBytes},
     HolochainVersion,
   },
-  HolochainManager,
+  CoordinatorUpdate, HolochainManager,
 };
-use lair_keystore_manager::utils::create_dir_if_necessary;
+use holochain_launcher_utils::unzip::{unzip_file, UnzipLimits};
+use lair_keystore_manager::{sodoken::BufRead, utils::create_dir_if_necessary};
 use serde::{Serialize, Deserialize};
-use futures::lock::Mutex;
 use tauri::{AppHandle, Manager};
 use std::{
-  collections::HashMap,
-  fs::{self, File},
-  path::{Path, PathBuf}, sync::Arc,
+  collections::{HashMap, HashSet},
+  fs,
+  io::Cursor,
+  path::{Path, PathBuf}, sync::{Arc, RwLock},
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use futures::channel::mpsc::UnboundedReceiver;
+
 use crate::{
+  app_events::{AppEventSenders, AppLifecycleEvent, AppLifecycleEventPayload, APP_LIFECYCLE_EVENT},
+  app_storage::StorageScan,
+  app_metadata::{AppMetadata, AppMetadataStore, InstallSource},
+  assets_manifest::{AssetsManifest, AssetsVerification},
   error::LaunchWebAppManagerError,
   installed_web_app_info::{InstalledWebAppInfo, WebUiInfo},
-  utils::unzip_file,
+  utils::zip_dir,
+  window_registry::{WindowIdentity, WindowRegistryState},
+  zome_call_policy::{ZomeCallPolicy, ZOME_CALL_POLICY_RESOURCE},
 };
 
 
@@ -54,18 +64,113 @@ impl Into<ResourceLocator> for ResourceLocatorB64 {
   }
 }
 
+/// Name of the UI that ships with the .webhapp an app got installed from
+pub const DEFAULT_UI_NAME: &str = "default";
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ReleaseInfo {
   pub resource_locator: Option<ResourceLocatorB64>,
   pub version: Option<String>,
 }
 
+/// Outcome of the last upgrade of an app, kept to be able to roll it back
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum AppUpgrade {
+  /// The coordinator zomes got replaced in place, keeping all the data of the app
+  CoordinatorsUpdated,
+  /// The integrity zomes changed, so the new release got installed as a separate app
+  /// with the same agent key and the previous app got disabled
+  Migrated {
+    previous_app_id: String,
+    new_app_id: String,
+    /// Roles whose cells start out empty in the new app. Their data stays with the disabled previous app.
+    #[serde(default)]
+    changed_roles: Vec<String>,
+  },
+}
+
+/// Release info written into exported .webhapp files next to the hApp and the UI
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExportedReleaseInfo {
+  pub happ_release_info: Option<ReleaseInfo>,
+  pub gui_release_info: Option<ReleaseInfo>,
+}
+
+/// Name of the resource holding the ExportedReleaseInfo in exported .webhapp files
+pub const EXPORTED_RELEASE_INFO_RESOURCE: &str = "release-info.yaml";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AppUpgradeProgress {
+  pub app_id: String,
+  pub message: String,
+}
+
+/// Data left behind by failed or interrupted operations, or by apps that are not installed in the conductor anymore
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum OrphanedData {
+  AppDataDir { app_id: String, path: PathBuf, size: u64 },
+  AppMetadata { app_id: String },
+  /// Leftover of an interrupted installation, UI update or extraction
+  LeftoverFile { path: PathBuf, size: u64 },
+}
+
+impl OrphanedData {
+  fn is_same_as(&self, other: &OrphanedData) -> bool {
+    match (self, other) {
+      (OrphanedData::AppDataDir { path: a, .. }, OrphanedData::AppDataDir { path: b, .. }) => a == b,
+      (OrphanedData::AppMetadata { app_id: a }, OrphanedData::AppMetadata { app_id: b }) => a == b,
+      (OrphanedData::LeftoverFile { path: a, .. }, OrphanedData::LeftoverFile { path: b, .. }) => a == b,
+      _ => false,
+    }
+  }
+}
+
+/// Temporary app bundles younger than this may belong to an installation in progress
+const TEMP_BUNDLE_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Cached app lists older than this get fetched again from the conductor, to pick up changes
+/// made through the admin websocket by other clients
+const INSTALLED_APPS_CACHE_MAX_AGE: Duration = Duration::from_secs(30);
+
+struct InstalledAppsCache {
+  apps: Vec<InstalledWebAppInfo>,
+  fetched_at: Instant,
+}
+
+/// What the admin window shows of a WebAppManager, readable without locking it. The installed apps
+/// are the ones last fetched from the conductor by `list_apps`.
+#[derive(Clone)]
+pub struct WebAppManagerSnapshot {
+  pub version: HolochainVersion,
+  pub app_interface_port: u16,
+  pub admin_interface_port: u16,
+  installed_apps: Arc<RwLock<Option<Vec<InstalledWebAppInfo>>>>,
+}
+
+impl WebAppManagerSnapshot {
+  pub fn installed_apps(&self) -> Option<Vec<InstalledWebAppInfo>> {
+    self
+      .installed_apps
+      .read()
+      .unwrap_or_else(|e| e.into_inner())
+      .clone()
+  }
+}
 
 pub struct WebAppManager {
   environment_path: PathBuf,
   app_handle: Arc<AppHandle>,
   pub holochain_manager: HolochainManager,
   allocated_ports: HashMap<String, u16>,
+  /// Number of previous UI revisions kept per app UI to be able to roll back updates
+  ui_revisions_to_keep: usize,
+  metadata: AppMetadataStore,
+  app_events: AppEventSenders,
+  /// Invalidated by every operation of this manager that changes installed apps, their UIs or metadata
+  installed_apps_cache: Option<InstalledAppsCache>,
+  snapshot: WebAppManagerSnapshot,
 }
 
 impl WebAppManager {
@@ -73,7 +178,8 @@ impl WebAppManager {
     version: HolochainVersion,
     mut config: LaunchHolochainConfig,
     app_handle: Arc<AppHandle>,
-    password: String,
+    password: BufRead,
+    ui_revisions_to_keep: usize,
   ) -> Result<Self, LaunchWebAppManagerError> {
     let environment_path = config.environment_path.clone();
 
@@ -86,22 +192,49 @@ impl WebAppManager {
     create_dir_if_necessary(&conductor_data_path)?;
     create_dir_if_necessary(&apps_data_dir)?;
 
+    let metadata = AppMetadataStore::load(app_metadata_path(&environment_path), &apps_data_dir)
+      .map_err(|err| LaunchWebAppManagerError::Other(err))?;
+
     let holochain_manager = HolochainManager::launch(version, config, password)
       .await
       .map_err(|err| LaunchWebAppManagerError::LaunchHolochainError(err))?;
 
+    let snapshot = WebAppManagerSnapshot {
+      version,
+      app_interface_port: holochain_manager.app_interface_port(),
+      admin_interface_port: holochain_manager.admin_interface_port(),
+      installed_apps: Arc::new(RwLock::new(None)),
+    };
+
     // Fetch the running apps
     let mut manager = WebAppManager {
       holochain_manager,
       app_handle,
       environment_path,
       allocated_ports: HashMap::new(),
+      ui_revisions_to_keep,
+      metadata,
+      app_events: AppEventSenders::default(),
+      installed_apps_cache: None,
+      snapshot,
     };
     manager
-      .on_running_apps_changed()
+      .record_missing_ui_assets_manifests()
+      .map_err(|err| LaunchWebAppManagerError::Other(err))?;
+    manager
+      .on_running_apps_changed(vec![])
       .await
       .map_err(|err| LaunchWebAppManagerError::Other(err))?;
 
+    // Only report orphaned data at launch, removing it needs the confirmation of the user
+    match manager.find_orphaned_data().await {
+      Ok(orphans) if !orphans.is_empty() => {
+        log::warn!("[HOLOCHAIN {}] Found orphaned data: {:?}", version, orphans)
+      }
+      Ok(_) => (),
+      Err(e) => log::error!("[HOLOCHAIN {}] Failed to look for orphaned data: {}", version, e),
+    }
+
     Ok(manager)
   }
 
@@ -127,15 +260,19 @@ impl WebAppManager {
       .await
       .or(Err("Failed to resolve Web UI"))?;
 
+    let zome_call_policy = ZomeCallPolicy::from_web_app_bundle(&web_app_bundle)?;
 
-    // Assuming only one single default UI per app at the moment.
-    let default_ui_name = String::from("default");
+    let default_ui_name = String::from(DEFAULT_UI_NAME);
+    let install_source = match happ_release_info.is_some() {
+      true => InstallSource::AppLibrary,
+      false => InstallSource::Filesystem,
+    };
 
     // Try to write hashes first so if that fails, don't even install the app
     match (happ_release_info, gui_release_info.clone()) {
       (Some(h_info), Some(_g_info)) => {
         self.store_happ_release_info(h_info, &app_id)
-          .map_err(|e| format!("Failed to store happ release hash to .happrelease file: {:?}", e))?;
+          .map_err(|e| format!("Failed to store happ release info: {:?}", e))?;
         // gui release hash will be stored during install_app_ui
       },
       (None, None) => (),
@@ -145,13 +282,17 @@ impl WebAppManager {
     // Install app UI in folder
     self.install_app_ui(app_id.clone(), web_ui_zip_bytes.into_owned(), &default_ui_name, gui_release_info)?;
 
+    if let Err(e) = self.store_happ_bundle(&app_id, &app_bundle).await {
+      log::warn!("Failed to store hApp bundle, upgrades of this app won't be revertable: {}", e);
+    }
+
     // Install app in conductor manager
     if let Err(err) = self
       .holochain_manager
       .install_app(
         app_id.clone(),
         app_bundle,
-        network_seed,
+        network_seed.clone(),
         membrane_proofs,
         agent_pub_key,
       )
@@ -162,7 +303,12 @@ impl WebAppManager {
       return Err(err);
     }
 
-    self.on_running_apps_changed().await?;
+    self.record_installation(&app_id, install_source, network_seed)?;
+    if zome_call_policy.is_some() {
+      self.update_metadata(&app_id, |metadata| metadata.zome_call_policy = zome_call_policy)?;
+    }
+
+    self.on_running_apps_changed(vec![AppLifecycleEvent::AppInstalled { app_id }]).await?;
 
     Ok(())
   }
@@ -186,14 +332,10 @@ impl WebAppManager {
     // Careful! The ui_folder_path here needs to be the same as the one being deleted in update_app_ui()
     // in case of a failed installation. Otherwise wrong stuff may be deleted.
     let ui_folder_path = app_assets_dir(&self.environment_path, &app_id, ui_name);
-    let ui_zip_path = apps_data_dir(&self.environment_path).join(format!("{}.zip", app_id));
 
-    fs::write(ui_zip_path.clone(), web_ui_zip_bytes.into_inner()).or(Err("Failed to write Web UI Zip file"))?;
+    unzip_file(Cursor::new(web_ui_zip_bytes.into_inner()), &ui_folder_path, &UnzipLimits::default())?;
 
-    let file = File::open(ui_zip_path.clone()).or(Err("Failed to read Web UI Zip file"))?;
-    unzip_file(file, ui_folder_path)?;
-
-    fs::remove_file(ui_zip_path).or(Err("Failed to remove happ bundle"))?;
+    self.record_ui_assets_manifest(&app_id, ui_name)?;
 
     Ok(())
   }
@@ -208,6 +350,10 @@ impl WebAppManager {
     // move folder of previous assets to a temporary backup folder in case installation fails
     let ui_folder_path = app_assets_dir(&self.environment_path, &app_id, ui_name);
     let temp_old_ui_path = app_ui_dir(&self.environment_path, &app_id, ui_name).join("assets_temp_backup");
+    let previous_release_info = self.get_gui_release_info(&app_id, ui_name);
+    // kept with the previous assets, since installing the new ones overwrites it
+    let assets_manifest_path = app_ui_assets_manifest_path(&self.environment_path, &app_id, ui_name);
+    let previous_assets_manifest = AssetsManifest::read(&assets_manifest_path)?;
     fs::rename(ui_folder_path.clone(), temp_old_ui_path.clone())
       .map_err(|e| format!("Failed to move currently installed UI assets to temporary backup location: {:?}", e))?;
 
@@ -215,44 +361,298 @@ impl WebAppManager {
       log::warn!("WARNING: App UI updated without passing a gui release hash. This only expected if a GUI is updated from the filesystem instead of through fetching it form the DevHub");
     }
 
-    // write zip file with new UI assets to disk in order to unpack it
-    match self.install_app_ui(app_id, web_ui_zip_bytes, ui_name, gui_release_info) {
+    // unpack the new UI assets
+    match self.install_app_ui(app_id.clone(), web_ui_zip_bytes, ui_name, gui_release_info) {
       Ok(()) => (),
       Err(e) => {
         log::error!("Failed to install app ui during update_app_ui: {:?}", e);
         // restore previous state
-        fs::remove_dir_all(ui_folder_path.clone())
-          .map_err(|e| format!("Failed to remove assets dir when trying to restore the pre-update state due to failed installation of the new app UI: {:?}", e))?;
+        if ui_folder_path.exists() {
+          fs::remove_dir_all(ui_folder_path.clone())
+            .map_err(|e| format!("Failed to remove assets dir when trying to restore the pre-update state due to failed installation of the new app UI: {:?}", e))?;
+        }
         fs::rename(temp_old_ui_path, ui_folder_path)
           .map_err(|e| format!("Failed to rename temporary assets backup dir when trying to restore the pre-update state due to failed installation of the new app UI: {:?}", e))?;
+        if let Some(manifest) = previous_assets_manifest {
+          manifest.write(&assets_manifest_path)?;
+        }
         return Err(e)
       }
     }
 
-    // If installation was successful, remove the temporary backup directory
-    fs::remove_dir_all(temp_old_ui_path)
-      .map_err(|e| format!("Failed to remove temporary backup folder for assets after successful installation: {:?}", e))?;
+    // If installation was successful, keep the previous assets as a revision to be able to roll back
+    if let Err(e) = self.store_ui_revision(
+      &app_id,
+      ui_name,
+      temp_old_ui_path.clone(),
+      previous_release_info,
+      previous_assets_manifest,
+    ) {
+      log::error!("Failed to keep previous UI assets as revision: {}", e);
+      if temp_old_ui_path.exists() {
+        fs::remove_dir_all(temp_old_ui_path)
+          .map_err(|e| format!("Failed to remove temporary backup folder for assets after successful installation: {:?}", e))?;
+      }
+    }
+
+    self.emit_app_event(AppLifecycleEvent::UiUpdated { app_id, ui_name: ui_name.clone() });
+
+    Ok(())
+  }
+
+  /// Moves the given assets into a new revision of the app UI together with their manifest, and
+  /// removes revisions exceeding the number of revisions to keep
+  fn store_ui_revision(
+    &self,
+    app_id: &String,
+    ui_name: &String,
+    assets_path: PathBuf,
+    release_info: Option<ReleaseInfo>,
+    assets_manifest: Option<AssetsManifest>,
+  ) -> Result<(), String> {
+    if self.ui_revisions_to_keep == 0 {
+      return fs::remove_dir_all(assets_path)
+        .map_err(|e| format!("Failed to remove previous UI assets: {:?}", e));
+    }
+
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_err(|e| format!("Failed to get current time: {:?}", e))?
+      .as_millis();
+    let revision_dir = app_ui_revisions_dir(&self.environment_path, app_id, ui_name).join(timestamp.to_string());
+
+    create_dir_if_necessary(&revision_dir)
+      .map_err(|e| format!("Failed to create UI revision directory: {:?}", e))?;
+    fs::rename(assets_path, revision_dir.join("assets"))
+      .map_err(|e| format!("Failed to move previous UI assets to revision directory: {:?}", e))?;
+
+    if let Some(info) = release_info {
+      write_yaml(&revision_dir.join(".guirelease"), &info)?;
+    }
+    if let Some(manifest) = assets_manifest {
+      manifest.write(&revision_dir.join(".assets-manifest.yaml"))?;
+    }
+
+    let revisions = self.list_ui_revision_dirs(app_id, ui_name);
+    if revisions.len() > self.ui_revisions_to_keep {
+      for outdated_revision in &revisions[..revisions.len() - self.ui_revisions_to_keep] {
+        fs::remove_dir_all(outdated_revision)
+          .map_err(|e| format!("Failed to remove outdated UI revision: {:?}", e))?;
+      }
+    }
 
     Ok(())
   }
 
-  /// Uninstalls the UI assets and tauri's localStorage associated to the given app
-  fn _uninstall_app_ui(&mut self, app_id: String, ui_name: &String) -> Result<(), String> {
+  /// Directories of the previous revisions of an app UI, oldest first
+  fn list_ui_revision_dirs(&self, app_id: &String, ui_name: &String) -> Vec<PathBuf> {
+    let mut revisions: Vec<(u128, PathBuf)> = match fs::read_dir(app_ui_revisions_dir(&self.environment_path, app_id, ui_name)) {
+      Ok(entries) => entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+          let timestamp = entry.file_name().to_str()?.parse::<u128>().ok()?;
+          Some((timestamp, entry.path()))
+        })
+        .collect(),
+      Err(_) => vec![],
+    };
+
+    revisions.sort_by_key(|(timestamp, _)| *timestamp);
+    revisions.into_iter().map(|(_, path)| path).collect()
+  }
+
+  /// Number of previous revisions of an app UI that it can be rolled back to
+  pub fn count_ui_revisions(&self, app_id: &String, ui_name: &String) -> usize {
+    self.list_ui_revision_dirs(app_id, ui_name).len()
+  }
 
+  /// Restores the assets, their manifest and the GUI release info of the most recent previous
+  /// revision of an app UI, if its assets still match the manifest recorded with them. The
+  /// currently installed assets are discarded.
+  pub fn rollback_app_ui(&mut self, app_id: String, ui_name: &String) -> Result<(), String> {
+    let revision_dir = self
+      .list_ui_revision_dirs(&app_id, ui_name)
+      .pop()
+      .ok_or(format!("There is no previous revision of the UI '{}' of app '{}' to roll back to", ui_name, app_id))?;
+
+    let revision_manifest_path = revision_dir.join(".assets-manifest.yaml");
+    match AssetsManifest::verify_at(&revision_manifest_path, &revision_dir.join("assets"))? {
+      AssetsVerification::Valid => (),
+      AssetsVerification::NoManifest => {
+        return Err(format!(
+          "No assets manifest has been recorded for the previous revision of the UI '{}' of app '{}'",
+          ui_name, app_id
+        ))
+      }
+      AssetsVerification::Tampered(files) => {
+        return Err(format!(
+          "The assets of the previous revision of the UI '{}' of app '{}' have been tampered with: {}",
+          ui_name,
+          app_id,
+          files.join(", ")
+        ))
+      }
+    }
+
+    let ui_dir = app_ui_dir(&self.environment_path, &app_id, ui_name);
     let ui_folder_path = app_assets_dir(&self.environment_path, &app_id, ui_name);
-    let local_storage_path = app_local_storage_dir(&self.environment_path, &app_id, ui_name);
+    let temp_current_ui_path = ui_dir.join("assets_temp_backup");
 
-    if Path::new(&ui_folder_path).exists() {
-      fs::remove_dir_all(ui_folder_path).or(Err("Failed to remove UI folder"))?;
+    fs::rename(ui_folder_path.clone(), temp_current_ui_path.clone())
+      .map_err(|e| format!("Failed to move currently installed UI assets to temporary backup location: {:?}", e))?;
+
+    if let Err(e) = fs::rename(revision_dir.join("assets"), ui_folder_path.clone()) {
+      fs::rename(temp_current_ui_path, ui_folder_path)
+        .map_err(|e| format!("Failed to restore UI assets after failed rollback: {:?}", e))?;
+      return Err(format!("Failed to restore assets of previous UI revision: {:?}", e));
+    }
+
+    let revision_release_info = read_yaml::<ReleaseInfo>(&revision_dir.join(".guirelease"))?;
+    self.update_metadata(&app_id, |metadata| {
+      metadata.uis.entry(ui_name.clone()).or_default().gui_release_info = revision_release_info;
+    })?;
+
+    self.invalidate_installed_apps_cache();
+    fs::rename(
+      &revision_manifest_path,
+      app_ui_assets_manifest_path(&self.environment_path, &app_id, ui_name),
+    )
+    .map_err(|e| format!("Failed to restore assets manifest of previous UI revision: {:?}", e))?;
+
+    fs::remove_dir_all(temp_current_ui_path)
+      .map_err(|e| format!("Failed to remove rolled back UI assets: {:?}", e))?;
+    fs::remove_dir_all(revision_dir)
+      .map_err(|e| format!("Failed to remove restored UI revision directory: {:?}", e))?;
+
+    self.emit_app_event(AppLifecycleEvent::UiUpdated { app_id, ui_name: ui_name.clone() });
+
+    Ok(())
+  }
+
+  /// Hashes the current assets of an app UI and records them as the trusted state of this UI
+  pub fn record_ui_assets_manifest(&mut self, app_id: &String, ui_name: &String) -> Result<AssetsManifest, String> {
+    self.invalidate_installed_apps_cache();
+    let manifest = AssetsManifest::compute(&app_assets_dir(&self.environment_path, app_id, ui_name))?;
+    manifest.write(&app_ui_assets_manifest_path(&self.environment_path, app_id, ui_name))?;
+    Ok(manifest)
+  }
+
+  /// Path of the manifest with the hashes recorded when the UI got installed, to verify its assets
+  /// against with AssetsManifest::verify_at
+  pub fn get_ui_assets_manifest_path(&self, app_id: &String, ui_name: &String) -> PathBuf {
+    app_ui_assets_manifest_path(&self.environment_path, app_id, ui_name)
+  }
+
+  /// Records the assets manifests of the UIs installed before manifests got recorded on installation.
+  /// Only runs once, so that a manifest removed afterwards is reported instead of silently recreated.
+  fn record_missing_ui_assets_manifests(&mut self) -> Result<(), String> {
+    if self.metadata.ui_assets_manifests_recorded() {
+      return Ok(());
+    }
+
+    let app_ids: Vec<String> = match fs::read_dir(apps_data_dir(&self.environment_path)) {
+      Ok(entries) => entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect(),
+      Err(_) => vec![],
+    };
+
+    for app_id in app_ids {
+      for ui_name in self.list_app_ui_names(&app_id) {
+        if !app_ui_assets_manifest_path(&self.environment_path, &app_id, &ui_name).exists() {
+          log::info!("Recording the assets manifest of UI '{}' of app '{}' installed by an older version of the launcher", ui_name, app_id);
+          self.record_ui_assets_manifest(&app_id, &ui_name)?;
+        }
+      }
+    }
+
+    self.metadata.set_ui_assets_manifests_recorded()
+  }
+
+  /// Installs an additional named UI next to the existing UI(s) of an installed app,
+  /// e.g. a community-built UI
+  pub async fn add_app_ui(
+    &mut self,
+    app_id: String,
+    web_ui_zip_bytes: ResourceBytes,
+    ui_name: &String,
+    gui_release_info: Option<ReleaseInfo>,
+  ) -> Result<(), String> {
+    validate_ui_name(ui_name)?;
+
+    let installed_apps = self.holochain_manager.list_apps().await?;
+    if !installed_apps.iter().any(|app| app.installed_app_id == app_id) {
+      return Err(format!("There is no app installed with the id '{}'", app_id));
+    }
+    if app_assets_dir(&self.environment_path, &app_id, ui_name).exists() {
+      return Err(format!("The app '{}' already has a UI with the name '{}'", app_id, ui_name));
+    }
+
+    if let Err(e) = self.install_app_ui(app_id.clone(), web_ui_zip_bytes, ui_name, gui_release_info) {
+      // don't leave a half-installed UI behind
+      self.uninstall_app_ui(app_id, ui_name)?;
+      return Err(e);
+    }
+
+    self.on_running_apps_changed(vec![AppLifecycleEvent::UiUpdated { app_id, ui_name: ui_name.clone() }]).await?;
+
+    Ok(())
+  }
+
+  /// Removes a UI that has been added next to the default UI of an app
+  pub async fn remove_app_ui(&mut self, app_id: String, ui_name: &String) -> Result<(), String> {
+    if ui_name == DEFAULT_UI_NAME {
+      return Err(String::from("The default UI of an app can only be removed by uninstalling the app."));
+    }
+    if !app_ui_dir(&self.environment_path, &app_id, ui_name).exists() {
+      return Err(format!("The app '{}' has no UI with the name '{}'", app_id, ui_name));
+    }
+
+    self.uninstall_app_ui(app_id.clone(), ui_name)?;
+
+    self.on_running_apps_changed(vec![AppLifecycleEvent::UiUpdated { app_id, ui_name: ui_name.clone() }]).await?;
+
+    Ok(())
+  }
+
+  /// Uninstalls the UI assets, release info and tauri's localStorage associated to the given app UI
+  fn uninstall_app_ui(&mut self, app_id: String, ui_name: &String) -> Result<(), String> {
+    self.invalidate_installed_apps_cache();
+
+    let ui_dir = app_ui_dir(&self.environment_path, &app_id, ui_name);
+
+    if Path::new(&ui_dir).exists() {
+      fs::remove_dir_all(ui_dir).or(Err("Failed to remove UI folder"))?;
     }
 
-    if Path::new(&local_storage_path).exists() {
-      fs::remove_dir_all(local_storage_path).or(Err("Failed to remove app's localStorage folder"))?;
+    if self.metadata.get(&app_id).map_or(false, |metadata| metadata.uis.contains_key(ui_name)) {
+      self.update_metadata(&app_id, |metadata| {
+        metadata.uis.remove(ui_name);
+      })?;
     }
 
     Ok(())
   }
 
+  /// Names of the UIs installed for the given app, with the default UI first
+  pub fn list_app_ui_names(&self, app_id: &String) -> Vec<String> {
+    let uis_dir = app_data_dir(&self.environment_path, app_id).join("uis");
+
+    let mut ui_names: Vec<String> = match fs::read_dir(uis_dir) {
+      Ok(entries) => entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("assets").exists())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect(),
+      Err(_) => vec![],
+    };
+
+    ui_names.sort_by(|a, b| (b == DEFAULT_UI_NAME).cmp(&(a == DEFAULT_UI_NAME)).then(a.cmp(b)));
+    ui_names
+  }
+
 
   /// Uninstalls the data of all UI's of this app as well as tauri's localStorage associated to those UI's
   fn uninstall_app_data(&mut self, app_id: String) -> Result<(), String> {
@@ -263,19 +663,52 @@ impl WebAppManager {
       fs::remove_dir_all(ui_folder_path).or(Err("Failed to remove app's data dir"))?;
     }
 
+    self.remove_metadata(&app_id)?;
+
     Ok(())
   }
 
 
-  async fn on_running_apps_changed(&mut self) -> Result<(), String> {
+  /// Refreshes the cached app list, the window label to agent public key mapping and the allocated
+  /// ports, then notifies subscribers and the frontend about the given events
+  async fn on_running_apps_changed(&mut self, events: Vec<AppLifecycleEvent>) -> Result<(), String> {
+    self.invalidate_installed_apps_cache();
     let _installed_apps = self.list_apps().await?;
+
+    for event in events {
+      self.emit_app_event(event);
+    }
+
     Ok(())
   }
 
+  /// Subscribes to the lifecycle events of the apps of this conductor
+  pub fn subscribe_app_events(&mut self) -> UnboundedReceiver<AppLifecycleEvent> {
+    self.app_events.subscribe()
+  }
+
+  fn emit_app_event(&mut self, event: AppLifecycleEvent) {
+    self.app_events.emit(&event);
+
+    let payload = AppLifecycleEventPayload {
+      holochain_version: self.holochain_manager.version,
+      event,
+    };
+    if let Err(e) = self.app_handle.emit_all(APP_LIFECYCLE_EVENT, payload) {
+      log::error!("Failed to emit app lifecycle event: {}", e);
+    }
+  }
+
   fn get_web_ui_info(&self, app_id: String, ui_name: &String) -> Result<WebUiInfo, String> {
     let ui_folder_path = app_assets_dir(&self.environment_path, &app_id, ui_name);
 
     let gui_release_info = self.get_gui_release_info(&app_id, ui_name);
+    let assets_hash = AssetsManifest::read(&app_ui_assets_manifest_path(&self.environment_path, &app_id, ui_name))
+      .unwrap_or_else(|e| {
+        log::error!("{}", e);
+        None
+      })
+      .map(|manifest| manifest.hash);
 
     match self.is_web_app(app_id.clone()) {
       true => Ok(WebUiInfo::WebApp {
@@ -289,6 +722,7 @@ impl WebAppManager {
           ))?
           .clone(),
           gui_release_info,
+          assets_hash,
       }),
       false => Ok(WebUiInfo::Headless),
     }
@@ -303,7 +737,7 @@ impl WebAppManager {
   }
 
 
-  pub fn kill(self) -> Result<(), String> {
+  pub fn kill(&mut self) -> Result<(), String> {
     self.holochain_manager.kill()
   }
 
@@ -319,6 +753,11 @@ impl WebAppManager {
     happ_release_info: Option<ReleaseInfo>,
   ) -> Result<(), String> {
 
+    let install_source = match happ_release_info.is_some() {
+      true => InstallSource::AppLibrary,
+      false => InstallSource::Filesystem,
+    };
+
     // Try to write hashes first so if that fails, don't even install the app
     // Note: a hApp release hash will only be passed if the hApp is installed
     // from the AppLibrary
@@ -326,17 +765,25 @@ impl WebAppManager {
       self.store_happ_release_info(info, &app_id)?;
     }
 
+    if let Err(e) = self.store_happ_bundle(&app_id, &app_bundle).await {
+      log::warn!("Failed to store hApp bundle, upgrades of this app won't be revertable: {}", e);
+    }
+
     // Install app in conductor manager
-    self
+    if let Err(err) = self
       .holochain_manager
-      .install_app(app_id, app_bundle, network_seed, membrane_proofs, agent_pub_key)
+      .install_app(app_id.clone(), app_bundle, network_seed.clone(), membrane_proofs, agent_pub_key)
       .await
-      .map_err(|err| {
-        log::error!("Error installing hApp in the conductor: {}", err);
-        err
-      })?;
+    {
+      log::error!("Error installing hApp in the conductor: {}", err);
+      self.uninstall_app_data(app_id)?;
+
+      return Err(err);
+    }
+
+    self.record_installation(&app_id, install_source, network_seed)?;
 
-    self.on_running_apps_changed().await?;
+    self.on_running_apps_changed(vec![AppLifecycleEvent::AppInstalled { app_id }]).await?;
 
     Ok(())
   }
@@ -344,6 +791,8 @@ impl WebAppManager {
   /// This uninstalls the happ from the conductor as well as all UI's and
   /// localStorage related to that happ from the filesystem
   pub async fn uninstall_app(&mut self, app_id: String) -> Result<(), String> {
+    self.revoke_app_windows(&app_id).await;
+
     self
       .holochain_manager
       .uninstall_app(app_id.clone())
@@ -353,9 +802,9 @@ impl WebAppManager {
         err
       })?;
 
-    self.uninstall_app_data(app_id)?;
+    self.uninstall_app_data(app_id.clone())?;
 
-    self.on_running_apps_changed().await?;
+    self.on_running_apps_changed(vec![AppLifecycleEvent::AppUninstalled { app_id }]).await?;
 
     Ok(())
   }
@@ -371,61 +820,119 @@ impl WebAppManager {
   pub async fn enable_app(&mut self, app_id: String) -> Result<(), String> {
     self.holochain_manager.enable_app(app_id.clone()).await?;
 
-    self.on_running_apps_changed().await?;
+    self.on_running_apps_changed(vec![AppLifecycleEvent::AppEnabled { app_id }]).await?;
 
     Ok(())
   }
 
   pub async fn disable_app(&mut self, app_id: String) -> Result<(), String> {
+    self.revoke_app_windows(&app_id).await;
     self.holochain_manager.disable_app(app_id.clone()).await?;
 
-    self.on_running_apps_changed().await?;
+    self.on_running_apps_changed(vec![AppLifecycleEvent::AppDisabled { app_id }]).await?;
 
     Ok(())
   }
 
   pub async fn delete_clone(&mut self, app_id: String, cell_id: CellId) -> Result<(), String> {
-    self.holochain_manager.delete_clone(app_id, cell_id).await?;
+    self.holochain_manager.delete_clone(app_id.clone(), cell_id.clone()).await?;
 
-    self.on_running_apps_changed().await?;
+    self.on_running_apps_changed(vec![AppLifecycleEvent::CloneDeleted { app_id, cell_id }]).await?;
 
     Ok(())
   }
 
+  fn update_metadata<F: FnOnce(&mut AppMetadata)>(&mut self, app_id: &String, f: F) -> Result<(), String> {
+    self.invalidate_installed_apps_cache();
+    self.metadata.update(app_id, f)
+  }
+
+  fn remove_metadata(&mut self, app_id: &String) -> Result<(), String> {
+    self.invalidate_installed_apps_cache();
+    self.metadata.remove(app_id)
+  }
+
+  fn invalidate_installed_apps_cache(&mut self) {
+    self.installed_apps_cache = None;
+  }
+
+  /// Lists the installed apps, served from the cache unless it has been invalidated or is outdated
   pub async fn list_apps(&mut self) -> Result<Vec<InstalledWebAppInfo>, String> {
+    if let Some(cache) = &self.installed_apps_cache {
+      if cache.fetched_at.elapsed() < INSTALLED_APPS_CACHE_MAX_AGE {
+        return Ok(cache.apps.clone());
+      }
+    }
+
+    let installed_web_apps = self.fetch_installed_apps().await?;
+    self.installed_apps_cache = Some(InstalledAppsCache {
+      apps: installed_web_apps.clone(),
+      fetched_at: Instant::now(),
+    });
+    *self
+      .snapshot
+      .installed_apps
+      .write()
+      .unwrap_or_else(|e| e.into_inner()) = Some(installed_web_apps.clone());
+
+    Ok(installed_web_apps)
+  }
+
+  /// Fetches the installed apps from the conductor and updates the window label to agent public key
+  /// mapping and the allocated ports accordingly
+  async fn fetch_installed_apps(&mut self) -> Result<Vec<InstalledWebAppInfo>, String> {
     let installed_apps = self.holochain_manager.list_apps().await?;
 
-    let mut updated_pubkey_map: HashMap<String, AgentPubKey> = HashMap::new();
-    // update agent public key to tauri window label mapping
-    for app_info in installed_apps.clone() {
-      let window_label = derive_window_label(&app_info.installed_app_id);
-      updated_pubkey_map.insert(window_label, app_info.agent_pub_key);
+    // update the identities of the windows of this conductor. Every UI of an app gets its own window.
+    // Windows of apps that are not running are not allowed to sign zome calls.
+    let running_apps: Vec<AppInfo> = installed_apps
+      .iter()
+      .filter(|app_info| matches!(app_info.status, AppInfoStatus::Running))
+      .cloned()
+      .collect();
+    let mut windows = Vec::new();
+    for app_info in running_apps.iter() {
+      let zome_call_policy = self
+        .metadata
+        .get(&app_info.installed_app_id)
+        .and_then(|metadata| metadata.zome_call_policy.clone());
+      for ui_name in self.list_app_ui_names(&app_info.installed_app_id) {
+        windows.push(WindowIdentity::new(self.holochain_manager.version, app_info, &ui_name, zome_call_policy.clone()));
+      }
     }
 
-    *self.app_handle.state::<Arc<Mutex<HashMap<String, AgentPubKey>>>>().lock().await = updated_pubkey_map;
+    self
+      .app_handle
+      .state::<WindowRegistryState>()
+      .lock()
+      .await
+      .set_conductor_windows(self.holochain_manager.version, windows, &running_apps);
 
     self.allocate_necessary_ports(&installed_apps);
 
-    // Assuming only one single default UI per app at the moment.
-    let default_ui_name = String::from("default");
-
     let installed_web_apps = installed_apps
       .into_iter()
       .map(|installed_app| {
-        let web_ui_info = self.get_web_ui_info(installed_app.installed_app_id.clone(), &default_ui_name)?;
-        // Currently only 1 UI supported
+        let app_id = installed_app.installed_app_id.clone();
         let mut web_uis = HashMap::new();
-        web_uis.insert(default_ui_name.clone(), web_ui_info);
-
-        let happ_release_info = self.get_happ_release_info(&installed_app.installed_app_id);
+        for ui_name in self.list_app_ui_names(&app_id) {
+          let web_ui_info = self.get_web_ui_info(app_id.clone(), &ui_name)?;
+          web_uis.insert(ui_name, web_ui_info);
+        }
+        // headless apps are listed with a headless default UI
+        if web_uis.is_empty() {
+          web_uis.insert(String::from(DEFAULT_UI_NAME), WebUiInfo::Headless);
+        }
 
-        let icon_src = self.get_app_icon_src(&installed_app.installed_app_id);
+        let metadata = self.metadata.get(&app_id).cloned().unwrap_or_default();
 
         Ok(InstalledWebAppInfo {
           installed_app_info: installed_app,
-          happ_release_info,
+          happ_release_info: metadata.happ_release_info,
           web_uis,
-          icon_src,
+          icon_src: metadata.icon_src,
+          label: metadata.label,
+          installed_at: metadata.installed_at,
         })
       })
       .collect::<Result<Vec<InstalledWebAppInfo>, String>>()?;
@@ -433,12 +940,19 @@ impl WebAppManager {
     Ok(installed_web_apps)
   }
 
-  fn is_web_app(&self, app_id: String) -> bool {
-    // Assuming only one single default UI per app at the moment.
-    let default_ui_name = String::from("default");
-    let ui_folder_path = app_assets_dir(&self.environment_path, &app_id, &default_ui_name);
+  /// Removes the windows of the given app from the window registry, so that they can't sign zome calls
+  /// anymore before the app is being disabled or uninstalled
+  async fn revoke_app_windows(&self, app_id: &String) {
+    self
+      .app_handle
+      .state::<WindowRegistryState>()
+      .lock()
+      .await
+      .remove_app_windows(&self.holochain_manager.version, app_id);
+  }
 
-    Path::new(&ui_folder_path).exists()
+  fn is_web_app(&self, app_id: String) -> bool {
+    !self.list_app_ui_names(&app_id).is_empty()
   }
 
   fn allocate_necessary_ports(&mut self, installed_apps: &Vec<AppInfo>) -> () {
@@ -472,6 +986,10 @@ impl WebAppManager {
     }
   }
 
+  pub fn snapshot(&self) -> WebAppManagerSnapshot {
+    self.snapshot.clone()
+  }
+
   pub fn admin_interface_port(&self) -> u16 {
     self.holochain_manager.admin_interface_port()
   }
@@ -481,158 +999,539 @@ impl WebAppManager {
   }
 
   pub fn get_storage_info(&self) -> Result<StorageInfo, String> {
-    let ui_path = apps_data_dir(&self.environment_path);
-    let conductor_path = conductor_dir(&self.environment_path);
-    let uis_size = fs_extra::dir::get_size(ui_path)
-      .map_err(|e| format!("Failed to get UI directory size: {:?}", e))?;
-    let authored_size = fs_extra::dir::get_size(conductor_path.join("authored"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-    let cache_size = fs_extra::dir::get_size(conductor_path.join("cache"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-    let conductor_size = fs_extra::dir::get_size(conductor_path.join("conductor"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-    let dht_size = fs_extra::dir::get_size(conductor_path.join("dht"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-    let p2p_size = fs_extra::dir::get_size(conductor_path.join("p2p"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-    let wasm_size = fs_extra::dir::get_size(conductor_path.join("wasm"))
-      .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
-
-    Ok(
-      StorageInfo {
-        uis: uis_size,
-        authored: authored_size,
-        cache: cache_size,
-        conductor: conductor_size,
-        dht: dht_size,
-        p2p: p2p_size,
-        wasm: wasm_size,
+    compute_storage_info(&apps_data_dir(&self.environment_path), &conductor_dir(&self.environment_path))
+  }
+
+  /// Collects what is needed to calculate the storage used by each app, see StorageScan::compute
+  pub async fn storage_scan(&mut self) -> Result<StorageScan, String> {
+    Ok(StorageScan {
+      apps_data_dir: apps_data_dir(&self.environment_path),
+      conductor_dir: conductor_dir(&self.environment_path),
+      installed_apps: self.holochain_manager.list_apps().await?,
+    })
+  }
+
+  /// Stores the release info of the hApp of the given app. The info it replaces is kept
+  /// as the previous release info in order to be able to revert upgrades.
+  pub fn store_happ_release_info(&mut self, info: ReleaseInfo, app_id: &String) -> Result<(), String> {
+    self.set_happ_release_info(app_id, Some(info))
+  }
+
+  fn set_happ_release_info(&mut self, app_id: &String, info: Option<ReleaseInfo>) -> Result<(), String> {
+    self.update_metadata(app_id, |metadata| {
+      if metadata.happ_release_info.is_some() {
+        metadata.previous_happ_release_info = metadata.happ_release_info.take();
       }
-    )
+      metadata.happ_release_info = info;
+    })
+  }
+
+  /// Stores the release info of the given app UI. The info it replaces is kept
+  /// as the previous release info in order to be able to revert updates.
+  pub fn store_gui_release_info(&mut self, info: ReleaseInfo, app_id: &String, ui_name: &String) -> Result<(), String> {
+    self.update_metadata(app_id, |metadata| {
+      let ui_metadata = metadata.uis.entry(ui_name.clone()).or_default();
+      if ui_metadata.gui_release_info.is_some() {
+        ui_metadata.previous_gui_release_info = ui_metadata.gui_release_info.take();
+      }
+      ui_metadata.gui_release_info = Some(info);
+    })
+  }
+
+  /// Reads the happ release info of an app
+  pub fn get_happ_release_info(&self, app_id: &String) -> Option<ReleaseInfo> {
+    self.metadata.get(app_id)?.happ_release_info.clone()
+  }
+
+  /// Reads the gui release info of an app UI
+  pub fn get_gui_release_info(&self, app_id: &String, ui_name: &String) -> Option<ReleaseInfo> {
+    self.metadata.get(app_id)?.uis.get(ui_name)?.gui_release_info.clone()
+  }
+
+  /// Stores the app icon src
+  /// The icon is expected to be a base64 string of the format 'data:image/png;base64,[...blabla...]'
+  pub fn store_app_icon_src(&mut self, icon_src: String, app_id: &String) -> Result<(), String> {
+    self.update_metadata(app_id, |metadata| metadata.icon_src = Some(icon_src))
+  }
 
+  /// Reads the app icon src
+  /// The icon is expected to be a base64 string of the format 'data:image/png;base64,[...blabla...]'
+  pub fn get_app_icon_src(&self, app_id: &String) -> Option<String> {
+    self.metadata.get(app_id)?.icon_src.clone()
   }
 
+  pub fn get_app_metadata(&self, app_id: &String) -> Option<AppMetadata> {
+    self.metadata.get(app_id).cloned()
+  }
 
-  /// Stores the hash of a happ release of the given hApp to the filesystem in a yaml file
-  /// called .happrelease
-  /// ActionHash must be passed as a base64 string
-  pub fn store_happ_release_info(&self, info: ReleaseInfo, app_id: &String) -> Result<(), String> {
+  /// Sets the name given to an app by the user, None removes it
+  pub fn set_app_label(&mut self, app_id: &String, label: Option<String>) -> Result<(), String> {
+    self.update_metadata(app_id, |metadata| metadata.label = label)
+  }
 
-    let app_data_dir = app_data_dir(&self.environment_path, app_id);
+  /// Overrides the zome call policy of an app, None allows calls to any zome of its own cells
+  pub async fn set_zome_call_policy(&mut self, app_id: &String, zome_call_policy: Option<ZomeCallPolicy>) -> Result<(), String> {
+    self.update_metadata(app_id, |metadata| metadata.zome_call_policy = zome_call_policy)?;
 
-    create_dir_if_necessary(&app_data_dir)
-      .map_err(|e| format!("Failed to create app's data directory before storing happ release hash: {:?}", e))?;
+    // update the windows of the app in the window registry
+    self.list_apps().await?;
 
-    let dot_happrelease_path = app_data_dir.join(".happrelease");
-    // if there is already a .happrelease file, store its contents to a .happrelease.previous file in order to be able
-    // to revert upgrades if necessary
-    if dot_happrelease_path.exists() {
-      let dot_happrelease_dot_previous_path = app_data_dir.join(".happrelease.previous");
-      std::fs::rename(dot_happrelease_path.clone(), dot_happrelease_dot_previous_path)
-        .map_err(|e| format!("Failed to rename .happrelease file to .happrelease.previous file: {:?}", e))?;
-    }
+    Ok(())
+  }
 
-    // println!("Storing happ release hash to the following path: {:?}", dot_happrelease_path);
-    let info_value = serde_yaml::to_value(info)
-      .map_err(|e| format!("Failed to convert info of happ release to serde_yaml Value: {}", e))?;
+  /// Refetches the installed apps from the conductor, e.g. to pick up clone cells created by an app
+  pub async fn refresh_installed_apps(&mut self) -> Result<(), String> {
+    self.invalidate_installed_apps_cache();
+    self.list_apps().await?;
+    Ok(())
+  }
+
+  fn record_installation(
+    &mut self,
+    app_id: &String,
+    install_source: InstallSource,
+    network_seed: Option<String>,
+  ) -> Result<(), String> {
+    let installed_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_err(|e| format!("Failed to get current time: {}", e))?
+      .as_millis() as u64;
+
+    self.update_metadata(app_id, |metadata| {
+      metadata.install_source = Some(install_source);
+      metadata.installed_at = Some(installed_at);
+      metadata.network_seed = network_seed;
+    })
+  }
+
+
+  /// Upgrades an installed app to a new release of its hApp. If only the coordinator zomes changed they get
+  /// replaced in place. Otherwise the new release gets installed as a separate app with the same agent key,
+  /// using the given membrane proofs, the UIs get copied over and the previous app gets disabled. The data
+  /// of the changed roles is not carried over, it stays with the previous app until that gets uninstalled.
+  pub async fn upgrade_app(
+    &mut self,
+    app_id: String,
+    app_bundle: AppBundle,
+    membrane_proofs: HashMap<String, MembraneProof>,
+    happ_release_info: Option<ReleaseInfo>,
+  ) -> Result<AppUpgrade, String> {
+    self.emit_upgrade_progress(&app_id, "Checking which zomes changed");
+
+    let upgrade = match self.holochain_manager.update_coordinators(&app_id, &app_bundle).await? {
+      CoordinatorUpdate::Updated => {
+        self.emit_upgrade_progress(&app_id, "Updated coordinator zomes");
+
+        if let Err(e) = self.store_happ_bundle(&app_id, &app_bundle).await {
+          log::warn!("Failed to store hApp bundle, this upgrade won't be revertable: {}", e);
+        }
+        self.set_happ_release_info(&app_id, happ_release_info)?;
+
+        AppUpgrade::CoordinatorsUpdated
+      }
+      CoordinatorUpdate::IntegrityChanged(roles) => {
+        self.emit_upgrade_progress(
+          &app_id,
+          format!("Integrity zomes of roles {} changed, installing the new release next to the current one", roles.join(", ")).as_str(),
+        );
+
+        let new_app_id = self.migrate_app(&app_id, app_bundle, membrane_proofs, happ_release_info).await?;
+
+        self.emit_upgrade_progress(
+          &app_id,
+          format!(
+            "The data of roles {} is not carried over to the new release, it stays with the disabled '{}'",
+            roles.join(", "),
+            app_id,
+          )
+          .as_str(),
+        );
+
+        AppUpgrade::Migrated {
+          previous_app_id: app_id.clone(),
+          new_app_id,
+          changed_roles: roles,
+        }
+      }
+    };
+
+    let record_app_id = match &upgrade {
+      AppUpgrade::CoordinatorsUpdated => app_id.clone(),
+      AppUpgrade::Migrated { new_app_id, .. } => new_app_id.clone(),
+    };
+    let last_upgrade = upgrade.clone();
+    self.update_metadata(&record_app_id, |metadata| metadata.last_upgrade = Some(last_upgrade))?;
+
+    let events = match &upgrade {
+      AppUpgrade::CoordinatorsUpdated => vec![AppLifecycleEvent::AppUpgraded { app_id: app_id.clone() }],
+      AppUpgrade::Migrated { new_app_id, .. } => vec![
+        AppLifecycleEvent::AppInstalled { app_id: new_app_id.clone() },
+        AppLifecycleEvent::AppDisabled { app_id: app_id.clone() },
+      ],
+    };
 
-    let info_string = serde_yaml::to_string(&info_value)
-      .map_err(|e| format!("Failed to convert info of happ release from serde_yaml Value to string: {}", e))?;
+    self.emit_upgrade_progress(&app_id, "Upgrade finished");
+    self.on_running_apps_changed(events).await?;
 
-    std::fs::write(dot_happrelease_path, info_string)
-      .map_err(|e| format!("Failed to write happ release info to .happrelease file: {:?}", e))
+    Ok(upgrade)
   }
 
-  /// Stores the hash of a gui release of the given hApp to the filesystem
-  /// ActionHash must be passed as a base64 string
-  pub fn store_gui_release_info(&self, info: ReleaseInfo, app_id: &String, ui_name: &String) -> Result<(), String> {
+  /// Installs the new release as a separate app with the same agent key and network seed, copies over
+  /// all UIs and the icon of the previous app, and disables the previous app
+  async fn migrate_app(
+    &mut self,
+    app_id: &String,
+    app_bundle: AppBundle,
+    membrane_proofs: HashMap<String, MembraneProof>,
+    happ_release_info: Option<ReleaseInfo>,
+  ) -> Result<String, String> {
+    let app_info = self
+      .holochain_manager
+      .list_apps()
+      .await?
+      .into_iter()
+      .find(|app_info| &app_info.installed_app_id == app_id)
+      .ok_or(format!("There is no app installed with the id '{}'", app_id))?;
+
+    let network_seed = app_info
+      .cell_info
+      .values()
+      .flatten()
+      .find_map(|cell| match cell {
+        CellInfo::Provisioned(cell) => Some(cell.dna_modifiers.network_seed.clone()),
+        _ => None,
+      })
+      .filter(|seed| !seed.is_empty());
+
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_err(|e| format!("Failed to get current time: {}", e))?
+      .as_secs();
+    let new_app_id = match happ_release_info.as_ref().and_then(|info| info.version.clone()) {
+      Some(version) => format!("{}-{}", app_id, version),
+      None => format!("{}-{}", app_id, now),
+    };
+    if app_data_dir(&self.environment_path, &new_app_id).exists() {
+      return Err(format!("Can't migrate to '{}': an app with this id already exists", new_app_id));
+    }
+
+    self.emit_upgrade_progress(app_id, format!("Installing new release as '{}'", new_app_id).as_str());
 
-    let app_gui_dir = app_ui_dir(&self.environment_path, app_id, ui_name);
-    create_dir_if_necessary(&app_gui_dir)
-      .map_err(|e| format!("Failed to create app's data directory before storing gui release hash: {:?}", e))?;
+    let old_app_dir = app_data_dir(&self.environment_path, app_id);
+    let new_app_dir = app_data_dir(&self.environment_path, &new_app_id);
+    create_dir_if_necessary(&new_app_dir)
+      .map_err(|e| format!("Failed to create data directory of the new app: {:?}", e))?;
 
-    let dot_guirelease_path = app_gui_dir.join(".guirelease");
-    // if there is already a .guirelease file, store its contents to a .guirelease.previous file in order to be able
-    // to revert upgrades if necessary
-    if dot_guirelease_path.exists() {
-      let dot_guirelease_dot_previous_path = app_gui_dir.join(".guirelease.previous");
+    if old_app_dir.join("uis").exists() {
+      fs_extra::dir::copy(old_app_dir.join("uis"), &new_app_dir, &fs_extra::dir::CopyOptions::new())
+        .map_err(|e| format!("Failed to copy UIs to the new app: {}", e))?;
+    }
+    let previous_metadata = self.metadata.get(app_id).cloned().unwrap_or_default();
+    self.update_metadata(&new_app_id, |metadata| {
+      metadata.uis = previous_metadata.uis;
+      metadata.icon_src = previous_metadata.icon_src;
+      metadata.label = previous_metadata.label;
+      metadata.happ_release_info = happ_release_info;
+    })?;
+    if let Err(e) = self.store_happ_bundle(&new_app_id, &app_bundle).await {
+      log::warn!("Failed to store hApp bundle of the new app: {}", e);
+    }
 
-      std::fs::rename(dot_guirelease_path.clone(), dot_guirelease_dot_previous_path)
-        .map_err(|e| format!("Failed to rename .guirelease file to .guirelease.previous file: {:?}", e))?;
+    if let Err(e) = self
+      .holochain_manager
+      .install_app(
+        new_app_id.clone(),
+        app_bundle,
+        network_seed.clone(),
+        membrane_proofs,
+        Some(app_info.agent_pub_key),
+      )
+      .await
+    {
+      self.uninstall_app_data(new_app_id)?;
+      return Err(e);
     }
 
-    // println!("Storing GUI release hash to the following path: {:?}", dot_guirelease_path);
-    let info_value = serde_yaml::to_value(info)
-      .map_err(|e| format!("Failed to convert ResourceLocator of GUI release info to serde_yaml Value: {}", e))?;
+    self.record_installation(
+      &new_app_id,
+      InstallSource::Upgrade { previous_app_id: app_id.clone() },
+      network_seed,
+    )?;
 
-    let info_string = serde_yaml::to_string(&info_value)
-      .map_err(|e| format!("Failed to convert info of GUI release from serde_yaml Value to string: {}", e))?;
+    self.emit_upgrade_progress(app_id, "Disabling the previous release");
+    self.holochain_manager.disable_app(app_id.clone()).await?;
 
-    std::fs::write(dot_guirelease_path, info_string)
-      .map_err(|e| format!("Failed to write GUI release info to .guirelease file: {:?}", e))
+    Ok(new_app_id)
   }
 
-  /// Reads the happ release hash of an app
-  pub fn get_happ_release_info(&self, app_id: &String) -> Option<ReleaseInfo> {
-    match fs::read_to_string(app_data_dir(&self.environment_path, app_id).join(".happrelease")) {
-      Ok(s) => {
-        let happ_release_info = serde_yaml::from_str::<ReleaseInfo>(s.as_str());
-        match happ_release_info {
-          Ok(info) => Some(info),
-          Err(e) => {
-            log::error!("Failed to read happ release info from .happrelease file: {}", e);
-            None
+  /// Reverts the last upgrade of the given app. For migrated apps, app_id is the id of the new app.
+  pub async fn rollback_app_upgrade(&mut self, app_id: String) -> Result<(), String> {
+    let upgrade = self
+      .get_app_upgrade(&app_id)
+      .ok_or(format!("There is no upgrade to roll back for app '{}'", app_id))?;
+
+    let events = match upgrade {
+      AppUpgrade::CoordinatorsUpdated => {
+        let happ_dir = app_happ_dir(&self.environment_path, &app_id);
+        let previous_bundle_path = happ_dir.join("previous.happ");
+        let bytes = fs::read(&previous_bundle_path)
+          .map_err(|e| format!("The previous hApp bundle of this app is not available: {}", e))?;
+        let previous_bundle = AppBundle::decode(&bytes)
+          .map_err(|e| format!("Failed to decode the previous hApp bundle: {}", e))?;
+
+        self.emit_upgrade_progress(&app_id, "Restoring previous coordinator zomes");
+        match self.holochain_manager.update_coordinators(&app_id, &previous_bundle).await? {
+          CoordinatorUpdate::Updated => (),
+          CoordinatorUpdate::IntegrityChanged(roles) => {
+            return Err(format!("The previous hApp bundle doesn't match the integrity zomes of roles {}", roles.join(", ")))
           }
         }
+
+        fs::rename(previous_bundle_path, happ_dir.join("current.happ"))
+          .map_err(|e| format!("Failed to restore previous hApp bundle: {}", e))?;
+
+        self.update_metadata(&app_id, |metadata| {
+          metadata.happ_release_info = metadata.previous_happ_release_info.take();
+          metadata.last_upgrade = None;
+        })?;
+
+        vec![AppLifecycleEvent::AppUpgraded { app_id }]
+      }
+      AppUpgrade::Migrated { previous_app_id, new_app_id, .. } => {
+        self.emit_upgrade_progress(&new_app_id, format!("Re-enabling '{}'", previous_app_id).as_str());
+        self.holochain_manager.enable_app(previous_app_id.clone()).await?;
+
+        self.emit_upgrade_progress(&new_app_id, "Uninstalling the new release");
+        self.holochain_manager.uninstall_app(new_app_id.clone()).await?;
+        self.uninstall_app_data(new_app_id.clone())?;
+
+        vec![
+          AppLifecycleEvent::AppEnabled { app_id: previous_app_id },
+          AppLifecycleEvent::AppUninstalled { app_id: new_app_id },
+        ]
+      }
+    };
+
+    self.on_running_apps_changed(events).await?;
+
+    Ok(())
+  }
+
+  /// Reads the record of the last upgrade of an app
+  pub fn get_app_upgrade(&self, app_id: &String) -> Option<AppUpgrade> {
+    self.metadata.get(app_id)?.last_upgrade.clone()
+  }
+
+  /// Stores the hApp bundle an app is running as current.happ, keeping the one it replaces as previous.happ
+  async fn store_happ_bundle(&self, app_id: &String, app_bundle: &AppBundle) -> Result<(), String> {
+    let happ_dir = app_happ_dir(&self.environment_path, app_id);
+    create_dir_if_necessary(&happ_dir)
+      .map_err(|e| format!("Failed to create hApp bundle directory: {:?}", e))?;
+
+    let current_path = happ_dir.join("current.happ");
+    if current_path.exists() {
+      fs::rename(&current_path, happ_dir.join("previous.happ"))
+        .map_err(|e| format!("Failed to keep previous hApp bundle: {}", e))?;
+    }
+
+    app_bundle
+      .write_to_file(&current_path)
+      .await
+      .map_err(|e| format!("Failed to write hApp bundle: {}", e))
+  }
+
+  /// Reassembles a .webhapp from the hApp bundle an app is running and the assets of one of its UIs
+  /// and writes it to the given path. The hApp bundle is rebuilt from the conductor as installed,
+  /// falling back to the stored one if that fails. The release info of both is included as an
+  /// extra resource.
+  pub async fn export_web_app(&mut self, app_id: &String, ui_name: &String, out_path: &PathBuf) -> Result<(), String> {
+    let stored_happ_bytes = fs::read(app_happ_dir(&self.environment_path, app_id).join("current.happ"))
+      .map_err(|_| format!("The hApp bundle of app '{}' is not available. Only apps installed or upgraded by this version of the launcher can be exported.", app_id))?;
+    let stored_bundle = AppBundle::decode(&stored_happ_bytes)
+      .map_err(|e| format!("Failed to decode the stored hApp bundle of app '{}': {}", app_id, e))?;
+
+    let happ_bytes = match self.holochain_manager.rebuild_app_bundle(app_id, &stored_bundle).await {
+      Ok(happ_bytes) => happ_bytes,
+      Err(e) => {
+        log::warn!("Could not rebuild the hApp bundle of app '{}' from the conductor, exporting the stored one instead: {}", app_id, e);
+        self.with_network_seed(app_id, stored_happ_bytes, stored_bundle)?
+      }
+    };
+
+    let assets_dir = app_assets_dir(&self.environment_path, app_id, ui_name);
+    if !assets_dir.exists() {
+      return Err(format!("The app '{}' has no UI with the name '{}'", app_id, ui_name));
+    }
+    let ui_zip_bytes = zip_dir(&assets_dir)?;
+
+    let release_info = ExportedReleaseInfo {
+      happ_release_info: self.get_happ_release_info(app_id),
+      gui_release_info: self.get_gui_release_info(app_id, ui_name),
+    };
+    let release_info_bytes = serde_yaml::to_string(&release_info)
+      .map_err(|e| format!("Failed to serialize release info: {}", e))?
+      .into_bytes();
+
+    let happ_path = PathBuf::from("app.happ");
+    let ui_path = PathBuf::from("ui.zip");
+    let manifest = WebAppManifest::V1(WebAppManifestV1 {
+      name: app_id.clone(),
+      ui: WebUI {
+        location: Location::Bundled(ui_path.clone()),
+      },
+      happ_manifest: AppManifestLocation {
+        location: Location::Bundled(happ_path.clone()),
       },
-      Err(_) => None,
+    });
+
+    let mut resources = vec![
+      (happ_path, ResourceBytes::from(happ_bytes)),
+      (ui_path, ResourceBytes::from(ui_zip_bytes)),
+      (PathBuf::from(EXPORTED_RELEASE_INFO_RESOURCE), ResourceBytes::from(release_info_bytes)),
+    ];
+    if let Some(zome_call_policy) = self.metadata.get(app_id).and_then(|metadata| metadata.zome_call_policy.clone()) {
+      let zome_call_policy_bytes = serde_yaml::to_string(&zome_call_policy)
+        .map_err(|e| format!("Failed to serialize zome call policy: {}", e))?
+        .into_bytes();
+      resources.push((PathBuf::from(ZOME_CALL_POLICY_RESOURCE), ResourceBytes::from(zome_call_policy_bytes)));
     }
+
+    let bundle_bytes = Bundle::new_unchecked(manifest, resources)
+    .and_then(|bundle| bundle.encode())
+    .map_err(|e| format!("Failed to assemble .webhapp bundle: {}", e))?;
+
+    // make sure what gets written can be installed again
+    WebAppBundle::decode(&bundle_bytes)
+      .map_err(|e| format!("Failed to assemble a valid .webhapp bundle: {}", e))?;
+
+    fs::write(out_path, bundle_bytes)
+      .map_err(|e| format!("Failed to write .webhapp file to {:?}: {}", out_path, e))
   }
 
-  /// Reads the gui release hash of an app UI
-  pub fn get_gui_release_info(&self, app_id: &String, ui_name: &String) -> Option<ReleaseInfo> {
-    match fs::read_to_string(app_ui_dir(&self.environment_path, app_id, ui_name).join(".guirelease")) {
-      Ok(s) => {
-        let gui_release_info = serde_yaml::from_str::<ReleaseInfo>(s.as_str());
-        match gui_release_info {
-          Ok(info) => Some(info),
-          Err(e) => {
-            log::error!("Failed to read GUI release ResourceLocator from .guirelease file: {}", e);
-            None
-          }
+  /// Sets the network seed an app has been installed with on all roles of its stored hApp bundle
+  fn with_network_seed(&self, app_id: &String, happ_bytes: Vec<u8>, app_bundle: AppBundle) -> Result<Vec<u8>, String> {
+    let network_seed = match self.metadata.get(app_id).and_then(|metadata| metadata.network_seed.clone()) {
+      Some(network_seed) => network_seed,
+      None => return Ok(happ_bytes),
+    };
+
+    let mut manifest = app_bundle.manifest().clone();
+    match &mut manifest {
+      AppManifest::V1(manifest) => {
+        for role in manifest.roles.iter_mut() {
+          role.dna.modifiers.network_seed = Some(network_seed.clone());
         }
-      },
-      Err(_) => None,
+      }
     }
+
+    Bundle::new_unchecked(manifest, app_bundle.bundled_resources().clone())
+      .and_then(|bundle| bundle.encode())
+      .map_err(|e| format!("Failed to assemble the hApp bundle: {}", e))
   }
 
-  /// Stores the app icon src
-  /// The icon is expected to be a base64 string of the format 'data:image/png;base64,[...blabla...]'
-  pub fn store_app_icon_src(&self, icon_src: String, app_id: &String) -> Result<(), String> {
+  /// Compares the installed apps of the conductor with the apps directory, the app metadata and the
+  /// temporary files of installations to find data that isn't used anymore
+  pub async fn find_orphaned_data(&mut self) -> Result<Vec<OrphanedData>, String> {
+    let installed_app_ids: HashSet<String> = self
+      .holochain_manager
+      .list_apps()
+      .await?
+      .into_iter()
+      .map(|app_info| app_info.installed_app_id)
+      .collect();
 
-    let app_data_dir = app_data_dir(&self.environment_path, app_id);
+    let mut orphans = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(apps_data_dir(&self.environment_path)) {
+      for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let size = fs_extra::dir::get_size(&path).unwrap_or(0);
+
+        if !path.is_dir() {
+          // e.g. UI zip files of installations that failed before they got removed again
+          orphans.push(OrphanedData::LeftoverFile { path, size });
+        } else if !installed_app_ids.contains(&name) {
+          orphans.push(OrphanedData::AppDataDir { app_id: name, path, size });
+        } else {
+          for ui_name in fs::read_dir(path.join("uis")).into_iter().flatten().filter_map(|e| e.ok()) {
+            for leftover in ["assets_temp_backup", "assets.unzipping"] {
+              let leftover_path = ui_name.path().join(leftover);
+              if leftover_path.exists() {
+                let size = fs_extra::dir::get_size(&leftover_path).unwrap_or(0);
+                orphans.push(OrphanedData::LeftoverFile { path: leftover_path, size });
+              }
+            }
+          }
+        }
+      }
+    }
 
-    create_dir_if_necessary(&app_data_dir)
-      .map_err(|e| format!("Failed to create app's data directory before storing app icon src: {:?}", e))?;
+    for app_id in self.metadata.app_ids() {
+      if !installed_app_ids.contains(&app_id) {
+        orphans.push(OrphanedData::AppMetadata { app_id });
+      }
+    }
 
-    let icon_path = app_data_dir.join(".icon");
+    if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
+      for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_old_enough = entry
+          .metadata()
+          .and_then(|m| m.modified())
+          .ok()
+          .and_then(|modified| modified.elapsed().ok())
+          .map_or(false, |age| age > TEMP_BUNDLE_MIN_AGE);
+
+        if name.starts_with("app_to_install") && name.ends_with(".webhapp") && is_old_enough {
+          let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+          orphans.push(OrphanedData::LeftoverFile { path: entry.path(), size });
+        }
+      }
+    }
 
-    std::fs::write(icon_path, icon_src)
-      .map_err(|e| format!("Failed to write icon src to .icon file: {:?}", e))
+    Ok(orphans)
   }
 
-  /// Reads the app icon src
-  /// The icon is expected to be a base64 string of the format 'data:image/png;base64,[...blabla...]'
-  pub fn get_app_icon_src(&self, app_id: &String) -> Option<String> {
-    match fs::read_to_string(app_data_dir(&self.environment_path, app_id).join(".icon")) {
-      Ok(s) => Some(s),
-      Err(_) => {
-        log::error!("Failed to load icon src for app with id {}", app_id);
-        None
-      },
+  /// Removes the given orphaned data after checking again that it is still orphaned
+  pub async fn remove_orphaned_data(&mut self, confirmed: Vec<OrphanedData>) -> Result<Vec<OrphanedData>, String> {
+    let orphans: Vec<OrphanedData> = self
+      .find_orphaned_data()
+      .await?
+      .into_iter()
+      .filter(|orphan| confirmed.iter().any(|c| c.is_same_as(orphan)))
+      .collect();
+
+    for orphan in orphans.iter() {
+      match orphan {
+        OrphanedData::AppDataDir { path, .. } => fs::remove_dir_all(path)
+          .map_err(|e| format!("Failed to remove orphaned app data {:?}: {}", path, e))?,
+        OrphanedData::AppMetadata { app_id } => self.remove_metadata(app_id)?,
+        OrphanedData::LeftoverFile { path, .. } => match path.is_dir() {
+          true => fs::remove_dir_all(path),
+          false => fs::remove_file(path),
+        }
+        .map_err(|e| format!("Failed to remove leftover file {:?}: {}", path, e))?,
+      }
+      log::info!("Removed orphaned data: {:?}", orphan);
     }
+
+    Ok(orphans)
   }
 
+  fn emit_upgrade_progress(&self, app_id: &String, message: &str) {
+    log::info!("[UPGRADE {}] {}", app_id, message);
+    if let Err(e) = self.app_handle.emit_to(
+      "admin",
+      "app-upgrade-progress",
+      AppUpgradeProgress {
+        app_id: app_id.clone(),
+        message: String::from(message),
+      },
+    ) {
+      log::warn!("Failed to emit upgrade progress: {}", e);
+    }
+  }
 
   pub async fn dump_network_stats(&mut self) -> Result<String, String> {
     self.holochain_manager.dump_network_stats().await
@@ -642,14 +1541,26 @@ impl WebAppManager {
 }
 
 
-/// Derives the window label from the app id and the holochain id
-/// The window label will be of the format [holochain version]#[app id with some special characters removed]
-pub fn derive_window_label(app_id: &String) -> String {
-  // !! it is important to have the window label not be uniquely defined by the app id to ensure
-  // it's possible to unambiguously differentiate this window from the admin window !!
-  let mut window_label = app_id.clone().replace("-", "--").replace(" ", "-").replace(".", "_");
-  window_label.push_str("--EXTERNAL");
-  window_label
+/// UI names are used as directory names and as part of window labels
+fn validate_ui_name(ui_name: &String) -> Result<(), String> {
+  let valid = !ui_name.is_empty()
+    && ui_name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' ');
+
+  match valid {
+    true => Ok(()),
+    false => Err(format!(
+      "Invalid UI name '{}'. Only letters, numbers, spaces, '-' and '_' are allowed.",
+      ui_name
+    )),
+  }
+}
+
+/// Path to the metadata of all installed apps relative to a root directory
+/// (normally relative to the holochain version's "data directory")
+fn app_metadata_path(root_path: &PathBuf) -> PathBuf {
+  root_path.join("app-metadata.yaml")
 }
 
 /// Path to the apps folder relative to a root directory
@@ -665,12 +1576,30 @@ fn app_data_dir(root_path: &PathBuf, app_id: &String) -> PathBuf {
   apps_data_dir(root_path).join(app_id)
 }
 
+/// Path where the current and previous hApp bundles of the given app are stored, relative
+/// to a root directory (normally relative to the holochain version's "data directory")
+fn app_happ_dir(root_path: &PathBuf, app_id: &String) -> PathBuf {
+  app_data_dir(root_path, app_id).join("happ")
+}
+
 /// Path where things related to a specific UI of the given app are stored, relative
 /// to a root directory (normally relative to the holochain version's "data directory")
 fn app_ui_dir(root_path: &PathBuf, app_id: &String, ui_name: &String) -> PathBuf {
   apps_data_dir(root_path).join(app_id).join("uis").join(ui_name)
 }
 
+/// Path where previous revisions of an app UI are kept to be able to roll back updates,
+/// relative to a root directory (normally relative to the holochain version's "data directory")
+fn app_ui_revisions_dir(root_path: &PathBuf, app_id: &String, ui_name: &String) -> PathBuf {
+  app_ui_dir(root_path, app_id, ui_name).join("revisions")
+}
+
+/// Path of the manifest with the hashes of the UI assets of the given app, relative
+/// to a root directory (normally relative to the holochain version's "data directory")
+fn app_ui_assets_manifest_path(root_path: &PathBuf, app_id: &String, ui_name: &String) -> PathBuf {
+  app_ui_dir(root_path, app_id, ui_name).join(".assets-manifest.yaml")
+}
+
 /// Path where UI assets of the given app are stored, relative
 /// to a root directory (normally relative to the holochain version's "data directory")
 fn app_assets_dir(root_path: &PathBuf, app_id: &String, ui_name: &String) -> PathBuf {
@@ -692,8 +1621,51 @@ fn conductor_dir(root_path: &PathBuf) -> PathBuf {
 }
 
 
+fn read_yaml<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Result<Option<T>, String> {
+  match fs::read_to_string(path) {
+    Ok(s) => serde_yaml::from_str::<T>(s.as_str())
+      .map(Some)
+      .map_err(|e| format!("Failed to parse {:?}: {}", path, e)),
+    Err(_) => Ok(None),
+  }
+}
+
+fn write_yaml<T: Serialize>(path: &PathBuf, value: &T) -> Result<(), String> {
+  let s = serde_yaml::to_string(value)
+    .map_err(|e| format!("Failed to serialize {:?}: {}", path, e))?;
+  fs::write(path, s).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+pub(crate) fn compute_storage_info(ui_path: &PathBuf, conductor_path: &PathBuf) -> Result<StorageInfo, String> {
+  let uis_size = fs_extra::dir::get_size(ui_path)
+    .map_err(|e| format!("Failed to get UI directory size: {:?}", e))?;
+  let authored_size = fs_extra::dir::get_size(conductor_path.join("authored"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+  let cache_size = fs_extra::dir::get_size(conductor_path.join("cache"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+  let conductor_size = fs_extra::dir::get_size(conductor_path.join("conductor"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+  let dht_size = fs_extra::dir::get_size(conductor_path.join("dht"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+  let p2p_size = fs_extra::dir::get_size(conductor_path.join("p2p"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+  let wasm_size = fs_extra::dir::get_size(conductor_path.join("wasm"))
+    .map_err(|e| format!("Failed to get conductor directory size: {:?}", e))?;
+
+  Ok(
+    StorageInfo {
+      uis: uis_size,
+      authored: authored_size,
+      cache: cache_size,
+      conductor: conductor_size,
+      dht: dht_size,
+      p2p: p2p_size,
+      wasm: wasm_size,
+    }
+  )
+}
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StorageInfo {
   uis: u64,
   authored: u64,
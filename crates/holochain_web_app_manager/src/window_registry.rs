@@ -0,0 +1,350 @@
+use futures::lock::Mutex;
+use holochain_manager::versions::{
+  holochain_conductor_api_latest::{AppInfo, CellInfo},
+  holochain_types_latest::prelude::{AgentPubKey, CellId},
+  HolochainVersion,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+use thiserror::Error;
+
+use crate::zome_call_policy::ZomeCallPolicy;
+
+/// The tauri state holding the WindowRegistry
+pub type WindowRegistryState = Arc<Mutex<WindowRegistry>>;
+
+/// What an app window is and what it is authorized to do
+#[derive(Clone, Debug)]
+pub struct WindowIdentity {
+  pub holochain_version: HolochainVersion,
+  pub app_id: String,
+  pub ui_name: String,
+  /// The only agent key the window may sign with
+  pub agent_pub_key: AgentPubKey,
+  /// The provisioned and cloned cells of the app, the only cells the window may call
+  pub cells: Vec<CellId>,
+  pub zome_call_policy: Option<ZomeCallPolicy>,
+}
+
+impl WindowIdentity {
+  pub fn new(
+    holochain_version: HolochainVersion,
+    app_info: &AppInfo,
+    ui_name: &String,
+    zome_call_policy: Option<ZomeCallPolicy>,
+  ) -> Self {
+    WindowIdentity {
+      holochain_version,
+      app_id: app_info.installed_app_id.clone(),
+      ui_name: ui_name.clone(),
+      agent_pub_key: app_info.agent_pub_key.clone(),
+      cells: app_cells(app_info),
+      zome_call_policy,
+    }
+  }
+}
+
+#[derive(Error, Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum ZomeCallAuthorizationError {
+  #[error("No authorized public key found for this window.")]
+  UnknownWindow,
+  #[error("The provided public key in the provenance field is not authorized to make a zome call to the requested cell.")]
+  ProvenanceNotAuthorized,
+  #[error("This window is not authorized to make zome calls to the requested cell.")]
+  CellNotAuthorized,
+  #[error("The zome call policy of this app doesn't allow calls to function '{fn_name}' of zome '{zome_name}'.")]
+  ZomeNotAllowed { zome_name: String, fn_name: String },
+}
+
+impl From<ZomeCallAuthorizationError> for String {
+  fn from(e: ZomeCallAuthorizationError) -> Self {
+    e.to_string()
+  }
+}
+
+/// Maps the labels of the windows of all running apps of all conductors to their identity.
+/// Windows without an entry, like the admin window, have no app identity.
+pub struct WindowRegistry {
+  windows: HashMap<String, WindowIdentity>,
+  /// Apps whose cells the admin window may call, e.g. the AppStore
+  admin_app_ids: HashSet<String>,
+  /// Cells of the running admin apps per conductor
+  admin_cells: HashMap<HolochainVersion, Vec<CellId>>,
+}
+
+impl WindowRegistry {
+  pub fn new(admin_app_ids: HashSet<String>) -> Self {
+    WindowRegistry {
+      windows: HashMap::new(),
+      admin_app_ids,
+      admin_cells: HashMap::new(),
+    }
+  }
+
+  pub fn get(&self, window_label: &str) -> Option<&WindowIdentity> {
+    self.windows.get(window_label)
+  }
+
+  /// Checks whether the window with the given label may sign a zome call with the given provenance
+  /// to the given cell, zome and function
+  pub fn authorize_zome_call(
+    &self,
+    window_label: &str,
+    provenance: &AgentPubKey,
+    cell_id: &CellId,
+    zome_name: &str,
+    fn_name: &str,
+  ) -> Result<(), ZomeCallAuthorizationError> {
+    if window_label == "admin" {
+      let is_admin_cell = self.admin_cells.values().flatten().any(|admin_cell| admin_cell == cell_id);
+      if !is_admin_cell {
+        return Err(ZomeCallAuthorizationError::CellNotAuthorized);
+      }
+      // the admin window may only sign with the agent key of the admin app owning the cell
+      if cell_id.agent_pubkey() != provenance {
+        return Err(ZomeCallAuthorizationError::ProvenanceNotAuthorized);
+      }
+      return Ok(());
+    }
+
+    let identity = self
+      .windows
+      .get(window_label)
+      .ok_or(ZomeCallAuthorizationError::UnknownWindow)?;
+
+    if &identity.agent_pub_key != provenance {
+      return Err(ZomeCallAuthorizationError::ProvenanceNotAuthorized);
+    }
+    if !identity.cells.contains(cell_id) {
+      return Err(ZomeCallAuthorizationError::CellNotAuthorized);
+    }
+    if let Some(policy) = &identity.zome_call_policy {
+      if !policy.allows(zome_name, fn_name) {
+        return Err(ZomeCallAuthorizationError::ZomeNotAllowed {
+          zome_name: zome_name.to_string(),
+          fn_name: fn_name.to_string(),
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Labels and identities of the windows of the given app in any conductor
+  pub fn windows_of_app<'a>(&'a self, app_id: &'a String) -> impl Iterator<Item = (&'a String, &'a WindowIdentity)> {
+    self
+      .windows
+      .iter()
+      .filter(move |(_, identity)| &identity.app_id == app_id)
+  }
+
+  /// Replaces the windows of the given conductor and the cells the admin window may call in it
+  pub fn set_conductor_windows(
+    &mut self,
+    holochain_version: HolochainVersion,
+    windows: Vec<WindowIdentity>,
+    running_apps: &Vec<AppInfo>,
+  ) {
+    self.remove_conductor_windows(&holochain_version);
+
+    let admin_cells = running_apps
+      .iter()
+      .filter(|app_info| self.admin_app_ids.contains(&app_info.installed_app_id))
+      .flat_map(app_cells)
+      .collect();
+    self.admin_cells.insert(holochain_version, admin_cells);
+
+    for identity in windows {
+      let window_label = derive_window_label(&identity.holochain_version, &identity.app_id, &identity.ui_name);
+      self.windows.insert(window_label, identity);
+    }
+  }
+
+  pub fn remove_conductor_windows(&mut self, holochain_version: &HolochainVersion) {
+    self
+      .windows
+      .retain(|_, identity| &identity.holochain_version != holochain_version);
+    self.admin_cells.remove(holochain_version);
+  }
+
+  pub fn remove_app_windows(&mut self, holochain_version: &HolochainVersion, app_id: &String) {
+    self.windows.retain(|_, identity| {
+      &identity.holochain_version != holochain_version || &identity.app_id != app_id
+    });
+  }
+}
+
+/// The provisioned and cloned cells of an app
+pub fn app_cells(app_info: &AppInfo) -> Vec<CellId> {
+  app_info
+    .cell_info
+    .values()
+    .flatten()
+    .filter_map(|cell_info| match cell_info {
+      CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+      CellInfo::Cloned(cell) => Some(cell.cell_id.clone()),
+      CellInfo::Stem(_) => None,
+    })
+    .collect()
+}
+
+/// Derives the label of the window of a UI of an app. Labels are unique across conductors and UIs.
+pub fn derive_window_label(holochain_version: &HolochainVersion, app_id: &String, ui_name: &String) -> String {
+  // !! it is important to have the window label not be uniquely defined by the app id to ensure
+  // it's possible to unambiguously differentiate this window from the admin window !!
+  format!(
+    "{}:{}:{}--EXTERNAL",
+    escape_window_label_part(&holochain_version.to_string()),
+    escape_window_label_part(app_id),
+    escape_window_label_part(ui_name)
+  )
+}
+
+/// Tauri only allows alphanumeric characters, '-', '/', ':' and '_' in window labels. Any other
+/// character as well as '-', ':' and '_' get encoded as '_' followed by their hex code, which keeps
+/// the labels of different app ids distinct.
+fn escape_window_label_part(part: &String) -> String {
+  let mut escaped = String::new();
+  for byte in part.bytes() {
+    match byte.is_ascii_alphanumeric() {
+      true => escaped.push(byte as char),
+      false => escaped.push_str(format!("_{:02x}", byte).as_str()),
+    }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::zome_call_policy::AllowedZome;
+  use holochain_manager::versions::holochain_types_latest::prelude::DnaHash;
+
+  fn agent_pub_key(byte: u8) -> AgentPubKey {
+    AgentPubKey::from_raw_36(vec![byte; 36])
+  }
+
+  fn cell_id(byte: u8, agent_pub_key: &AgentPubKey) -> CellId {
+    CellId::new(DnaHash::from_raw_36(vec![byte; 36]), agent_pub_key.clone())
+  }
+
+  /// A registry with the window of a single app, whose only cell belongs to agent 1
+  fn registry_with_app_window(zome_call_policy: Option<ZomeCallPolicy>) -> (WindowRegistry, String, CellId) {
+    let agent = agent_pub_key(1);
+    let cell = cell_id(1, &agent);
+    let identity = WindowIdentity {
+      holochain_version: HolochainVersion::V0_2_7,
+      app_id: String::from("app"),
+      ui_name: String::from("default"),
+      agent_pub_key: agent,
+      cells: vec![cell.clone()],
+      zome_call_policy,
+    };
+    let window_label = derive_window_label(&identity.holochain_version, &identity.app_id, &identity.ui_name);
+
+    let mut registry = WindowRegistry::new(HashSet::from([String::from("appstore")]));
+    registry.windows.insert(window_label.clone(), identity);
+
+    (registry, window_label, cell)
+  }
+
+  fn label(app_id: &str, ui_name: &str) -> String {
+    derive_window_label(&HolochainVersion::V0_2_7, &app_id.to_string(), &ui_name.to_string())
+  }
+
+  #[test]
+  fn escaped_label_parts_only_contain_alphanumeric_characters_and_underscores() {
+    let escaped = escape_window_label_part(&String::from("my app:v1-2_3/é"));
+
+    assert!(escaped.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    assert_eq!(escape_window_label_part(&String::from("app")), "app");
+  }
+
+  #[test]
+  fn window_labels_are_unique() {
+    // would collide if the separator or the escape character were not escaped
+    assert_ne!(label("a:b", "c"), label("a", "b:c"));
+    assert_ne!(label("a_3ab", "c"), label("a:b", "c"));
+    assert_ne!(label("a-b", "c"), label("a_2db", "c"));
+
+    assert_ne!(
+      derive_window_label(&HolochainVersion::V0_2_7, &String::from("app"), &String::from("default")),
+      derive_window_label(&HolochainVersion::CustomBinary, &String::from("app"), &String::from("default")),
+    );
+    assert_ne!(label("admin", ""), "admin");
+  }
+
+  #[test]
+  fn authorizes_calls_of_the_window_to_its_own_cells() {
+    let (registry, window_label, cell) = registry_with_app_window(None);
+
+    assert!(registry
+      .authorize_zome_call(&window_label, &agent_pub_key(1), &cell, "zome", "fn")
+      .is_ok());
+  }
+
+  #[test]
+  fn rejects_unauthorized_calls() {
+    let (registry, window_label, cell) = registry_with_app_window(None);
+
+    assert!(matches!(
+      registry.authorize_zome_call("unknown", &agent_pub_key(1), &cell, "zome", "fn"),
+      Err(ZomeCallAuthorizationError::UnknownWindow)
+    ));
+    assert!(matches!(
+      registry.authorize_zome_call(&window_label, &agent_pub_key(2), &cell, "zome", "fn"),
+      Err(ZomeCallAuthorizationError::ProvenanceNotAuthorized)
+    ));
+    assert!(matches!(
+      registry.authorize_zome_call(&window_label, &agent_pub_key(1), &cell_id(2, &agent_pub_key(1)), "zome", "fn"),
+      Err(ZomeCallAuthorizationError::CellNotAuthorized)
+    ));
+  }
+
+  #[test]
+  fn enforces_the_zome_call_policy() {
+    let policy = ZomeCallPolicy {
+      allowed: vec![AllowedZome {
+        zome_name: String::from("posts"),
+        fn_names: Some(vec![String::from("get_posts")]),
+      }],
+    };
+    let (registry, window_label, cell) = registry_with_app_window(Some(policy));
+
+    assert!(registry
+      .authorize_zome_call(&window_label, &agent_pub_key(1), &cell, "posts", "get_posts")
+      .is_ok());
+    assert!(matches!(
+      registry.authorize_zome_call(&window_label, &agent_pub_key(1), &cell, "posts", "delete_post"),
+      Err(ZomeCallAuthorizationError::ZomeNotAllowed { .. })
+    ));
+  }
+
+  #[test]
+  fn admin_window_may_only_call_admin_app_cells() {
+    let (mut registry, _, app_cell) = registry_with_app_window(None);
+    let admin_cell = cell_id(3, &agent_pub_key(3));
+    registry.admin_cells.insert(HolochainVersion::V0_2_7, vec![admin_cell.clone()]);
+
+    assert!(registry
+      .authorize_zome_call("admin", &agent_pub_key(3), &admin_cell, "zome", "fn")
+      .is_ok());
+    assert!(matches!(
+      registry.authorize_zome_call("admin", &agent_pub_key(1), &app_cell, "zome", "fn"),
+      Err(ZomeCallAuthorizationError::CellNotAuthorized)
+    ));
+    assert!(matches!(
+      registry.authorize_zome_call("admin", &agent_pub_key(1), &admin_cell, "zome", "fn"),
+      Err(ZomeCallAuthorizationError::ProvenanceNotAuthorized)
+    ));
+
+    registry.remove_conductor_windows(&HolochainVersion::V0_2_7);
+    assert!(registry
+      .authorize_zome_call("admin", &agent_pub_key(3), &admin_cell, "zome", "fn")
+      .is_err());
+  }
+}
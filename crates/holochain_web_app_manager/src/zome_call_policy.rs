@@ -0,0 +1,91 @@
+use holochain_manager::versions::holochain_types_latest::web_app::WebAppBundle;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Name of the optional resource of a .webhapp declaring the ZomeCallPolicy of the app
+pub const ZOME_CALL_POLICY_RESOURCE: &str = "zome-call-policy.yaml";
+
+/// Zomes and functions of its own cells the UIs of an app are allowed to call.
+/// Apps without a policy may call any zome of their own cells.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ZomeCallPolicy {
+  pub allowed: Vec<AllowedZome>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AllowedZome {
+  pub zome_name: String,
+  /// All functions of the zome are allowed if not specified
+  #[serde(default)]
+  pub fn_names: Option<Vec<String>>,
+}
+
+impl ZomeCallPolicy {
+  pub fn allows(&self, zome_name: &str, fn_name: &str) -> bool {
+    self.allowed.iter().any(|allowed| {
+      allowed.zome_name == zome_name
+        && allowed
+          .fn_names
+          .as_ref()
+          .map_or(true, |fn_names| fn_names.iter().any(|name| name == fn_name))
+    })
+  }
+
+  /// Reads the policy declared by the app in its .webhapp, if any
+  pub fn from_web_app_bundle(web_app_bundle: &WebAppBundle) -> Result<Option<ZomeCallPolicy>, String> {
+    match web_app_bundle
+      .bundled_resources()
+      .get(&PathBuf::from(ZOME_CALL_POLICY_RESOURCE))
+    {
+      Some(bytes) => serde_yaml::from_slice(&bytes.clone().into_inner())
+        .map(Some)
+        .map_err(|e| format!("Failed to parse the zome call policy of the app: {}", e)),
+      None => Ok(None),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn policy() -> ZomeCallPolicy {
+    ZomeCallPolicy {
+      allowed: vec![
+        AllowedZome {
+          zome_name: String::from("profiles"),
+          fn_names: None,
+        },
+        AllowedZome {
+          zome_name: String::from("posts"),
+          fn_names: Some(vec![String::from("get_posts"), String::from("create_post")]),
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn allows_all_functions_of_zomes_without_fn_names() {
+    assert!(policy().allows("profiles", "get_profile"));
+    assert!(policy().allows("profiles", "update_profile"));
+  }
+
+  #[test]
+  fn allows_only_the_listed_functions() {
+    assert!(policy().allows("posts", "create_post"));
+    assert!(!policy().allows("posts", "delete_post"));
+  }
+
+  #[test]
+  fn rejects_zomes_not_listed() {
+    assert!(!policy().allows("admin", "get_profile"));
+    assert!(!ZomeCallPolicy { allowed: vec![] }.allows("profiles", "get_profile"));
+  }
+
+  #[test]
+  fn fn_names_default_to_all_functions() {
+    let policy: ZomeCallPolicy = serde_yaml::from_str("allowed:\n  - zome_name: profiles\n").unwrap();
+
+    assert!(policy.allows("profiles", "get_profile"));
+  }
+}
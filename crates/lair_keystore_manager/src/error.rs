@@ -1,3 +1,4 @@
+use holochain_launcher_utils::zome_call_signing::ZomeCallSigningError;
 use std::{fmt, io};
 use thiserror::Error;
 
@@ -23,7 +24,11 @@ pub enum LairKeystoreError {
   #[error("Lair Keystore Error: `{0}`")]
   OtherError(String),
   #[error("Failed to sign zome call: `{0}`")]
-  SignZomeCallError(String),
+  SignZomeCallError(#[from] ZomeCallSigningError),
+  #[error("The keystore is locked")]
+  KeystoreLocked,
+  #[error("Failed to sign data: `{0}`")]
+  SignError(String),
 }
 
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
@@ -0,0 +1,52 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::PathBuf,
+};
+
+use holochain_types::prelude::AgentPubKey;
+use serde::{Deserialize, Serialize};
+
+use crate::error::LairKeystoreError;
+
+const KEY_INVENTORY_FILE: &str = "key-inventory.yaml";
+
+/// A seed stored in lair-keystore, as generated for agent keys
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentKeyEntry {
+  pub tag: String,
+  pub agent_pub_key: AgentPubKey,
+  pub label: Option<String>,
+}
+
+/// Launcher-side bookkeeping about the seeds in lair-keystore, stored next to the keystore.
+/// Keys are base64 encoded agent public keys.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct KeyInventory {
+  #[serde(default)]
+  pub labels: HashMap<String, String>,
+  /// Keys that no app used anymore and that the user hid from the inventory. lair-keystore 0.4
+  /// can't delete entries, so these stay in the keystore.
+  #[serde(default, alias = "retired")]
+  pub hidden: HashSet<String>,
+}
+
+impl KeyInventory {
+  pub fn read(keystore_path: &PathBuf) -> Self {
+    match fs::read_to_string(keystore_path.join(KEY_INVENTORY_FILE)) {
+      Ok(str) => serde_yaml::from_str(str.as_str()).unwrap_or_else(|e| {
+        log::error!("Failed to parse key inventory, starting with an empty one: {}", e);
+        KeyInventory::default()
+      }),
+      Err(_) => KeyInventory::default(),
+    }
+  }
+
+  pub fn write(&self, keystore_path: &PathBuf) -> Result<(), LairKeystoreError> {
+    let serialized = serde_yaml::to_string(&self)
+      .map_err(|e| LairKeystoreError::OtherError(format!("Failed to serialize key inventory: {}", e)))?;
+
+    fs::write(keystore_path.join(KEY_INVENTORY_FILE), serialized)
+      .map_err(|e| LairKeystoreError::OtherError(format!("Failed to write key inventory: {}", e)))
+  }
+}
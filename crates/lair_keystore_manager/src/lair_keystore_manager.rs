@@ -1,11 +1,36 @@
 use std::path::PathBuf;
 
-use crate::{error::LairKeystoreError, versions::LairKeystoreVersion};
+use crate::{
+  error::LairKeystoreError, key_inventory::AgentKeyEntry, sodoken::BufRead,
+  versions::LairKeystoreVersion,
+};
 use async_trait::async_trait;
+use futures::channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
 use url2::Url2;
-use holochain_zome_types::ZomeCallUnsigned;
+use holochain_zome_types::{Signature, ZomeCallUnsigned};
+use holochain_types::prelude::AgentPubKey;
 pub use holochain_conductor_api::ZomeCall;
 
+/// Health of the supervised lair-keystore process
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum KeystoreHealthEvent {
+  /// The lair-keystore process exited unexpectedly. The keystore is locked until it gets
+  /// unlocked again, which restarts the process.
+  ProcessExited { code: Option<i32> },
+  /// A restart of the lair-keystore process is being attempted
+  Restarting { attempt: u32 },
+  /// lair-keystore is running again and the LairClient has been reconnected.
+  /// If the connection url changed, conductors need to be pointed to the new url.
+  Restarted {
+    connection_url: String,
+    connection_url_changed: bool,
+  },
+  /// lair-keystore could not be restarted and is not running anymore
+  RestartFailed(String),
+}
+
 #[async_trait]
 pub trait LairKeystoreManager: Send + Sync {
   fn lair_keystore_version() -> LairKeystoreVersion
@@ -16,14 +41,14 @@ pub trait LairKeystoreManager: Send + Sync {
   where
     Self: Sized;
 
-  async fn initialize(keystore_path: PathBuf, password: String) -> Result<(), LairKeystoreError>
+  async fn initialize(keystore_path: PathBuf, password: BufRead) -> Result<(), LairKeystoreError>
   where
     Self: Sized;
 
   async fn launch(
     log_level: log::Level,
     keystore_path: PathBuf,
-    password: String,
+    password: BufRead,
   ) -> Result<Self, LairKeystoreError>
   where
     Self: Sized;
@@ -32,5 +57,48 @@ pub trait LairKeystoreManager: Send + Sync {
 
   async fn sign_zome_call(&self, zome_call: ZomeCallUnsigned) -> Result<ZomeCall, LairKeystoreError>;
 
-  fn password(&self) -> String;
+  /// Signs the zome calls concurrently, returning one result per zome call in the same order
+  async fn sign_zome_calls(
+    &self,
+    zome_calls: Vec<ZomeCallUnsigned>,
+  ) -> Result<Vec<Result<ZomeCall, LairKeystoreError>>, LairKeystoreError>;
+
+  /// Signs arbitrary bytes with the private key of the given agent
+  async fn sign_by_pub_key(
+    &self,
+    agent_pub_key: AgentPubKey,
+    data: Vec<u8>,
+  ) -> Result<Signature, LairKeystoreError>;
+
+  /// Stops the launcher from signing until the keystore gets unlocked again. This is no lock of
+  /// lair-keystore itself: the process keeps running unlocked and conductors keep signing with it.
+  fn lock(&self);
+
+  /// Verifies the passphrase against the running lair-keystore process and resumes signing.
+  /// If the process exited meanwhile, it gets restarted with the passphrase instead.
+  async fn unlock(&self, password: BufRead) -> Result<(), LairKeystoreError>;
+
+  fn is_locked(&self) -> bool;
+
+  /// Generates a new seed in lair-keystore and returns its public key as an agent key
+  async fn generate_agent_key(&self) -> Result<AgentPubKey, LairKeystoreError>;
+
+  /// Whether lair-keystore holds the seed of the given agent key, hidden or not
+  async fn contains_agent_key(&self, agent_pub_key: &AgentPubKey) -> Result<bool, LairKeystoreError>;
+
+  /// Lists the seeds stored in lair-keystore together with their labels, leaving out hidden ones
+  async fn list_agent_keys(&self) -> Result<Vec<AgentKeyEntry>, LairKeystoreError>;
+
+  fn set_agent_key_label(
+    &self,
+    agent_pub_key: AgentPubKey,
+    label: Option<String>,
+  ) -> Result<(), LairKeystoreError>;
+
+  /// Hides a key that no app uses anymore from the inventory. lair-keystore 0.4 has no API to
+  /// delete entries, so the seed itself remains in the keystore.
+  fn hide_agent_key(&self, agent_pub_key: AgentPubKey) -> Result<(), LairKeystoreError>;
+
+  /// Subscribes to health events of the supervised lair-keystore process
+  fn subscribe_health_events(&self) -> UnboundedReceiver<KeystoreHealthEvent>;
 }
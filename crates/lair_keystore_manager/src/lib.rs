@@ -1,6 +1,8 @@
 pub mod error;
+pub mod key_inventory;
 mod lair_keystore_manager;
 pub mod versions;
 pub mod utils;
 
 pub use lair_keystore_manager::*;
+pub use lair_keystore_api::dependencies::sodoken;
@@ -3,7 +3,15 @@ use std::{
   path::{Path, PathBuf},
 };
 
-use crate::error::FileSystemError;
+use holochain_zome_types::Signature;
+use holochain_types::prelude::AgentPubKey;
+use lair_keystore_api::prelude::BinDataSized;
+use zeroize::Zeroize;
+
+use crate::{
+  error::{FileSystemError, LairKeystoreError},
+  sodoken::{BufRead, BufWrite},
+};
 
 pub fn create_dir_if_necessary(path: &PathBuf) -> Result<(), FileSystemError> {
   if !path_exists(path) {
@@ -16,3 +24,91 @@ pub fn create_dir_if_necessary(path: &PathBuf) -> Result<(), FileSystemError> {
 pub fn path_exists(path: &PathBuf) -> bool {
   Path::new(path).exists()
 }
+
+/// Moves the passphrase into a memory-locked buffer that gets zeroized once it is dropped
+/// and zeroizes the given String
+pub fn secure_passphrase(mut password: String) -> Result<BufRead, LairKeystoreError> {
+  let buffer = BufWrite::new_mem_locked(password.len());
+  let result = buffer
+    .map(|buffer| {
+      buffer.write_lock().copy_from_slice(password.as_bytes());
+      buffer.to_read()
+    })
+    .map_err(|e| LairKeystoreError::OtherError(format!("Failed to allocate secure memory for the passphrase: {:?}", e)));
+
+  password.zeroize();
+
+  result
+}
+
+/// Prepended to the data signed on behalf of happ windows, so that the signature can never be
+/// valid for a zome call or anything else signed with the same agent key
+pub const SIGN_BYTES_DOMAIN_TAG: &[u8] = b"holochain-launcher/sign_bytes/v1\0";
+
+/// The message that actually gets signed when a happ window asks to sign the given data
+pub fn sign_bytes_message(data: &[u8]) -> Vec<u8> {
+  [SIGN_BYTES_DOMAIN_TAG, data].concat()
+}
+
+/// Verifies a signature made over the given data by signing it on behalf of a happ window
+pub async fn verify_sign_bytes_signature(
+  agent_pub_key: AgentPubKey,
+  data: Vec<u8>,
+  signature: Signature,
+) -> Result<bool, LairKeystoreError> {
+  verify_signature(agent_pub_key, sign_bytes_message(&data), signature).await
+}
+
+/// Verifies an ed25519 signature of the given agent over the given data
+pub async fn verify_signature(
+  agent_pub_key: AgentPubKey,
+  data: Vec<u8>,
+  signature: Signature,
+) -> Result<bool, LairKeystoreError> {
+  let mut pub_key = [0; 32];
+  pub_key.copy_from_slice(agent_pub_key.get_raw_32());
+
+  BinDataSized::<32>::from(pub_key)
+    .verify_detached(signature.0.into(), data)
+    .await
+    .map_err(|e| LairKeystoreError::OtherError(format!("Failed to verify signature: {:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sodoken::{self, BufWriteSized};
+
+  async fn sign_detached(data: Vec<u8>) -> (AgentPubKey, Signature) {
+    let pub_key = BufWriteSized::<32>::new_no_lock();
+    let sec_key = BufWriteSized::<64>::new_mem_locked().unwrap();
+    sodoken::sign::keypair(pub_key.clone(), sec_key.clone()).await.unwrap();
+
+    let signature = BufWriteSized::<64>::new_no_lock();
+    sodoken::sign::detached(signature.clone(), data, sec_key.to_read_sized()).await.unwrap();
+
+    let agent_pub_key = AgentPubKey::from_raw_32(pub_key.read_lock().to_vec());
+    let signature = Signature(*signature.read_lock());
+    (agent_pub_key, signature)
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn sign_bytes_signature_is_not_a_zome_call_signature() {
+    // stands in for the hash returned by ZomeCallUnsigned::data_to_sign()
+    let zome_call_hash = vec![7u8; 32];
+
+    let (agent_pub_key, signature) = sign_detached(sign_bytes_message(&zome_call_hash)).await;
+
+    assert!(verify_sign_bytes_signature(agent_pub_key.clone(), zome_call_hash.clone(), signature.clone()).await.unwrap());
+    // zome call signatures are verified over the hash itself
+    assert!(!verify_signature(agent_pub_key, zome_call_hash, signature).await.unwrap());
+  }
+
+  #[test]
+  fn sign_bytes_message_is_prefixed() {
+    let message = sign_bytes_message(b"data");
+
+    assert!(message.starts_with(SIGN_BYTES_DOMAIN_TAG));
+    assert!(message.ends_with(b"data"));
+  }
+}
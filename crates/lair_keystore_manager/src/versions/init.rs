@@ -5,7 +5,10 @@ use std::{
 
 use tauri::api::process::{Command, CommandEvent};
 
-use crate::error::{LairKeystoreError, LaunchChildError};
+use crate::{
+  error::{LairKeystoreError, LaunchChildError},
+  sodoken::BufRead,
+};
 
 pub fn is_initialized(keystore_path: PathBuf) -> bool {
   Path::new(&keystore_path)
@@ -13,7 +16,7 @@ pub fn is_initialized(keystore_path: PathBuf) -> bool {
     .exists()
 }
 
-pub async fn initialize(keystore_path: PathBuf, password: String) -> Result<(), LairKeystoreError> {
+pub async fn initialize(keystore_path: PathBuf, password: BufRead) -> Result<(), LairKeystoreError> {
   // NEW_VERSION Check whether lair-keystore version needs to get updated
   let (mut lair_rx, mut command_child) = Command::new_sidecar("lair-keystore-v0.4.2")
     .or(Err(LairKeystoreError::LaunchChildError(
@@ -27,7 +30,7 @@ pub async fn initialize(keystore_path: PathBuf, password: String) -> Result<(),
   tauri::async_runtime::spawn(async move {
     std::thread::sleep(Duration::from_millis(10));
     command_child
-      .write(password.as_bytes())
+      .write(&*password.read_lock())
       .expect("Could not write password");
   });
 
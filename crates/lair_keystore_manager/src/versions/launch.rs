@@ -3,17 +3,36 @@ use std::{collections::HashMap, time::Duration};
 use url2::Url2;
 
 use tauri::api::process::{Command, CommandEvent};
-
-use crate::error::{LairKeystoreError, LaunchChildError};
+use tauri::async_runtime::Receiver;
+
+use crate::{
+  error::{LairKeystoreError, LaunchChildError},
+  sodoken::BufRead,
+};
+
+/// A running lair-keystore process
+pub struct LairKeystoreProcess {
+  /// Directory the process has been started in. On unix this is a symlink to the
+  /// keystore data directory, see `prepare_keystore_path()`
+  pub keystore_path: PathBuf,
+  pub connection_url: Url2,
+  /// Events of the process after it reported to be running. Yields `CommandEvent::Terminated`
+  /// once the process exits.
+  pub events: Receiver<CommandEvent>,
+}
 
 pub async fn launch_lair_keystore_process(
   log_level: log::Level,
   keystore_data_dir: PathBuf,
-  password: String,
-) -> Result<Url2, LairKeystoreError> {
-  let mut envs = HashMap::new();
-  envs.insert(String::from("RUST_LOG"), String::from(log_level.as_str()));
+  password: BufRead,
+) -> Result<LairKeystoreProcess, LairKeystoreError> {
+  let keystore_path = prepare_keystore_path(keystore_data_dir)?;
+
+  spawn_lair_keystore_process(log_level, keystore_path, password).await
+}
 
+/// Returns the path from which the lair-keystore process should be run.
+pub fn prepare_keystore_path(keystore_data_dir: PathBuf) -> Result<PathBuf, LairKeystoreError> {
   let mut keystore_path = keystore_data_dir.clone();
 
   // On Unix systems, there is a limit to the path length of a domain socket. Create a symlink to the lair directory from the tempdir
@@ -75,6 +94,20 @@ pub async fn launch_lair_keystore_process(
     .map_err(|e| LairKeystoreError::ErrorWritingLairConfig(e.to_string()))?;
   }
 
+  Ok(keystore_path)
+}
+
+/// Spawns lair-keystore in the given keystore path and waits until it is running.
+///
+/// Restarting lair in the same keystore path keeps the connection url stable.
+pub async fn spawn_lair_keystore_process(
+  log_level: log::Level,
+  keystore_path: PathBuf,
+  password: BufRead,
+) -> Result<LairKeystoreProcess, LairKeystoreError> {
+  let mut envs = HashMap::new();
+  envs.insert(String::from("RUST_LOG"), String::from(log_level.as_str()));
+
   // NEW_VERSION Check whether lair-keystore version needs to get updated
   let (mut lair_rx, mut command_child) = Command::new_sidecar("lair-keystore-v0.4.2")
     .or(Err(LairKeystoreError::LaunchChildError(
@@ -91,7 +124,7 @@ pub async fn launch_lair_keystore_process(
   tauri::async_runtime::spawn(async move {
     std::thread::sleep(Duration::from_millis(10));
     command_child
-      .write(password.as_bytes())
+      .write(&*password.read_lock())
       .expect("Could not write password");
   });
 
@@ -118,24 +151,13 @@ pub async fn launch_lair_keystore_process(
     }
   }
 
-  tauri::async_runtime::spawn(async move {
-    // read events such as stdout
-    while let Some(event) = lair_rx.recv().await {
-      match event.clone() {
-        CommandEvent::Stdout(line) => log::info!("[LAIR] {}", line),
-        CommandEvent::Stderr(line) => log::error!("[LAIR] {}", line),
-        _ => log::info!("[LAIR] {:?}", event),
-      }
-    }
-  });
-
   // NEW_VERSION Check whether lair-keystore version needs to get updated
   let output = Command::new_sidecar("lair-keystore-v0.4.2")
     .or(Err(LairKeystoreError::LaunchChildError(
       LaunchChildError::BinaryNotFound,
     )))?
     .args(&["url"])
-    .current_dir(keystore_path)
+    .current_dir(keystore_path.clone())
     .envs(envs.clone())
     .output()
     .map_err(|err| {
@@ -148,11 +170,15 @@ pub async fn launch_lair_keystore_process(
     ));
   }
 
-  let url = Url2::parse(output.stdout);
+  let connection_url = Url2::parse(output.stdout);
 
   log::info!("Launched lair-keystore");
 
-  Ok(url)
+  Ok(LairKeystoreProcess {
+    keystore_path,
+    connection_url,
+    events: lair_rx,
+  })
 }
 
 /// Iterator yielding every line in a string. The line includes newline character(s).
@@ -2,8 +2,11 @@ use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 
 mod init;
 mod launch;
+mod supervisor;
 pub mod v0_3;
 
+pub use supervisor::stop_supervision;
+
 #[derive(Copy, Clone, Debug, PartialEq, Hash, Eq, Deserialize_enum_str, Serialize_enum_str)]
 pub enum LairKeystoreVersion {
   #[serde(rename = "0.3")]
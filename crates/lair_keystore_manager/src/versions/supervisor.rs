@@ -0,0 +1,211 @@
+use std::{
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+  },
+  time::Duration,
+};
+
+use futures::{
+  channel::{
+    mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    oneshot,
+  },
+  lock::Mutex,
+  StreamExt,
+};
+use lair_keystore_api::{ipc_keystore::*, prelude::*};
+use tauri::{api::process::CommandEvent, async_runtime::Receiver};
+use url::Url;
+use url2::Url2;
+
+use super::launch::{prepare_keystore_path, spawn_lair_keystore_process, LairKeystoreProcess};
+use crate::{error::LairKeystoreError, sodoken::BufRead, KeystoreHealthEvent};
+
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+
+/// Set once the launcher intentionally kills its child processes
+static SUPERVISION_STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Stops restarting lair-keystore processes when they exit. Needs to be called before
+/// intentionally killing the sidecar binaries, e.g. when quitting or restarting the launcher.
+pub fn stop_supervision() {
+  SUPERVISION_STOPPED.store(true, Ordering::SeqCst);
+}
+
+/// Subscribers to the health events of a lair-keystore process
+#[derive(Clone, Default)]
+pub struct HealthEventSenders(Arc<std::sync::Mutex<Vec<UnboundedSender<KeystoreHealthEvent>>>>);
+
+impl HealthEventSenders {
+  pub fn subscribe(&self) -> UnboundedReceiver<KeystoreHealthEvent> {
+    let (sender, receiver) = unbounded();
+    self
+      .0
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .push(sender);
+    receiver
+  }
+
+  pub fn emit(&self, event: KeystoreHealthEvent) {
+    // drop the subscribers that went away
+    self
+      .0
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .retain(|sender| sender.unbounded_send(event.clone()).is_ok());
+  }
+}
+
+/// A passphrase to restart lair-keystore with, and where to report whether the restart succeeded
+pub type RestartRequest = (BufRead, oneshot::Sender<Result<(), LairKeystoreError>>);
+
+/// Restarts the lair-keystore process whenever it exits and reconnects the shared LairClient.
+/// The passphrase is not kept around, so the keystore is locked until it gets unlocked with the
+/// passphrase again, which restarts the process.
+pub struct LairSupervisor {
+  pub log_level: log::Level,
+  pub keystore_data_dir: PathBuf,
+  pub restart_requests: UnboundedReceiver<RestartRequest>,
+  /// Set while lair-keystore is not running and waits for the passphrase to be restarted
+  pub awaiting_password: Arc<AtomicBool>,
+  pub locked: Arc<AtomicBool>,
+  pub client: Arc<Mutex<LairClient>>,
+  pub connection_url: Arc<RwLock<Url2>>,
+  pub health_events: HealthEventSenders,
+}
+
+impl LairSupervisor {
+  pub async fn supervise(mut self, process: LairKeystoreProcess) {
+    let mut keystore_path = process.keystore_path;
+    let mut events = process.events;
+
+    loop {
+      let code = wait_for_exit(&mut events).await;
+
+      if SUPERVISION_STOPPED.load(Ordering::SeqCst) {
+        return;
+      }
+
+      log::error!("[LAIR] lair-keystore exited unexpectedly with code {:?}", code);
+      self.locked.store(true, Ordering::SeqCst);
+      self.awaiting_password.store(true, Ordering::SeqCst);
+      self
+        .health_events
+        .emit(KeystoreHealthEvent::ProcessExited { code });
+
+      let process = loop {
+        let (password, response) = match self.restart_requests.next().await {
+          Some(request) => request,
+          // the LairKeystoreManager has been dropped
+          None => return,
+        };
+
+        match self.restart(&keystore_path, password).await {
+          Ok(process) => {
+            let _ = response.send(Ok(()));
+            break process;
+          }
+          Err(e) => {
+            log::error!("[LAIR] Failed to restart lair-keystore: {}", e);
+            self
+              .health_events
+              .emit(KeystoreHealthEvent::RestartFailed(e.to_string()));
+            let _ = response.send(Err(e));
+          }
+        }
+      };
+
+      self.awaiting_password.store(false, Ordering::SeqCst);
+      self.locked.store(false, Ordering::SeqCst);
+      keystore_path = process.keystore_path;
+      events = process.events;
+    }
+  }
+
+  async fn restart(
+    &self,
+    keystore_path: &PathBuf,
+    password: BufRead,
+  ) -> Result<LairKeystoreProcess, LairKeystoreError> {
+    let mut last_error = LairKeystoreError::OtherError(String::from("lair-keystore was not restarted"));
+
+    for attempt in 1..=MAX_RESTART_ATTEMPTS {
+      self
+        .health_events
+        .emit(KeystoreHealthEvent::Restarting { attempt });
+
+      // back off a little more with every attempt
+      tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
+
+      if SUPERVISION_STOPPED.load(Ordering::SeqCst) {
+        return Err(LairKeystoreError::OtherError(String::from("Supervision of lair-keystore has been stopped")));
+      }
+
+      match self.try_restart(keystore_path, password.clone()).await {
+        Ok(process) => return Ok(process),
+        // retrying won't help, the admin window needs to prompt for the passphrase again
+        Err(LairKeystoreError::IncorrectPassword) => return Err(LairKeystoreError::IncorrectPassword),
+        Err(e) => {
+          log::error!("[LAIR] Restart attempt {} of lair-keystore failed: {}", attempt, e);
+          last_error = e;
+        }
+      }
+    }
+
+    Err(last_error)
+  }
+
+  async fn try_restart(
+    &self,
+    keystore_path: &PathBuf,
+    password: BufRead,
+  ) -> Result<LairKeystoreProcess, LairKeystoreError> {
+    // Restarting in the same path as before keeps the connection url stable such that running
+    // conductors can reconnect to it
+    let keystore_path = match keystore_path.exists() {
+      true => keystore_path.clone(),
+      false => prepare_keystore_path(self.keystore_data_dir.clone())?,
+    };
+
+    let process =
+      spawn_lair_keystore_process(self.log_level, keystore_path, password.clone()).await?;
+
+    let client = ipc_keystore_connect(Url::from(process.connection_url.clone()), password)
+    .await
+    .map_err(|e| LairKeystoreError::ErrorCreatingLairClient(format!("Failed to create LairClient: {:?}", e)))?;
+
+    *self.client.lock().await = client;
+
+    let connection_url_changed = {
+      let mut connection_url = self.connection_url.write().unwrap_or_else(|e| e.into_inner());
+      let changed = connection_url.to_string() != process.connection_url.to_string();
+      *connection_url = process.connection_url.clone();
+      changed
+    };
+
+    log::info!("[LAIR] Restarted lair-keystore");
+
+    self.health_events.emit(KeystoreHealthEvent::Restarted {
+      connection_url: process.connection_url.to_string(),
+      connection_url_changed,
+    });
+
+    Ok(process)
+  }
+}
+
+/// Logs the events of the lair-keystore process until it exits and returns its exit code
+async fn wait_for_exit(events: &mut Receiver<CommandEvent>) -> Option<i32> {
+  while let Some(event) = events.recv().await {
+    match event {
+      CommandEvent::Stdout(line) => log::info!("[LAIR] {}", line),
+      CommandEvent::Stderr(line) => log::error!("[LAIR] {}", line),
+      CommandEvent::Terminated(payload) => return payload.code,
+      event => log::info!("[LAIR] {:?}", event),
+    }
+  }
+  None
+}
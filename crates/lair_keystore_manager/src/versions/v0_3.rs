@@ -1,9 +1,24 @@
-use std::path::PathBuf;
+use std::{
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+  },
+};
+use futures::{
+  channel::{
+    mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    oneshot,
+  },
+  lock::Mutex,
+};
 use lair_keystore_api::prelude::*;
 use lair_keystore_api::ipc_keystore::*;
-use holochain_types::prelude::ZomeCallUnsigned;
+use holochain_types::prelude::{AgentPubKey, Signature, ZomeCallUnsigned};
 use holochain_conductor_api::ZomeCall;
-use holochain_launcher_utils::zome_call_signing::sign_zome_call_with_client;
+use holochain_launcher_utils::zome_call_signing::{
+  sign_zome_call_with_client, sign_zome_calls_with_client, ZomeCallValidator,
+};
 
 use async_trait::async_trait;
 use url2::Url2;
@@ -12,15 +27,33 @@ use url::Url;
 use super::{
   init::{initialize, is_initialized},
   launch::launch_lair_keystore_process,
+  supervisor::{HealthEventSenders, LairSupervisor, RestartRequest},
   LairKeystoreVersion,
 };
-use crate::{error::LairKeystoreError, utils::create_dir_if_necessary, LairKeystoreManager};
+use crate::{
+  error::LairKeystoreError,
+  key_inventory::{AgentKeyEntry, KeyInventory},
+  sodoken::BufRead,
+  utils::create_dir_if_necessary,
+  KeystoreHealthEvent, LairKeystoreManager,
+};
 
 pub struct LairKeystoreManagerV0_3 {
-  _keystore_path: PathBuf,
-  connection_url: Url2,
-  password: String,
-  client: LairClient,
+  keystore_path: PathBuf,
+  connection_url: Arc<RwLock<Url2>>,
+  // Replaced by the supervisor whenever lair-keystore gets restarted
+  client: Arc<Mutex<LairClient>>,
+  health_events: HealthEventSenders,
+  restart_requests: UnboundedSender<RestartRequest>,
+  awaiting_password: Arc<AtomicBool>,
+  locked: Arc<AtomicBool>,
+  zome_call_validator: ZomeCallValidator,
+}
+
+impl LairKeystoreManagerV0_3 {
+  async fn client(&self) -> LairClient {
+    self.client.lock().await.clone()
+  }
 }
 
 #[async_trait]
@@ -33,7 +66,7 @@ impl LairKeystoreManager for LairKeystoreManagerV0_3 {
     is_initialized(keystore_path)
   }
 
-  async fn initialize(keystore_path: PathBuf, password: String) -> Result<(), LairKeystoreError> {
+  async fn initialize(keystore_path: PathBuf, password: BufRead) -> Result<(), LairKeystoreError> {
     create_dir_if_necessary(&keystore_path)?;
     initialize(keystore_path, password).await
   }
@@ -41,38 +74,243 @@ impl LairKeystoreManager for LairKeystoreManagerV0_3 {
   async fn launch(
     log_level: log::Level,
     keystore_path: PathBuf,
-    password: String,
+    password: BufRead,
   ) -> Result<Self, LairKeystoreError> {
-    let connection_url =
+    let process =
       launch_lair_keystore_process(log_level, keystore_path.clone(), password.clone()).await?;
 
-    let client = ipc_keystore_connect(Url::from(connection_url.clone()), password.clone().into_bytes())
+    let client = ipc_keystore_connect(Url::from(process.connection_url.clone()), password.clone())
       .await
       .map_err(|e| LairKeystoreError::ErrorCreatingLairClient(format!("Failed to create LairClient: {:?}", e)))?;
 
+    let client = Arc::new(Mutex::new(client));
+    let connection_url = Arc::new(RwLock::new(process.connection_url.clone()));
+    let health_events = HealthEventSenders::default();
+    let (restart_requests, restart_requests_receiver) = unbounded();
+    let awaiting_password = Arc::new(AtomicBool::new(false));
+    let locked = Arc::new(AtomicBool::new(false));
+
+    let supervisor = LairSupervisor {
+      log_level,
+      keystore_data_dir: keystore_path.clone(),
+      restart_requests: restart_requests_receiver,
+      awaiting_password: awaiting_password.clone(),
+      locked: locked.clone(),
+      client: client.clone(),
+      connection_url: connection_url.clone(),
+      health_events: health_events.clone(),
+    };
+    tauri::async_runtime::spawn(supervisor.supervise(process));
 
     Ok(LairKeystoreManagerV0_3 {
-      password,
       connection_url,
-      _keystore_path: keystore_path,
+      keystore_path,
       client,
+      health_events,
+      restart_requests,
+      awaiting_password,
+      locked,
+      zome_call_validator: ZomeCallValidator::default(),
     })
   }
 
   fn connection_url(&self) -> Url2 {
-    self.connection_url.clone()
+    self
+      .connection_url
+      .read()
+      .unwrap_or_else(|e| e.into_inner())
+      .clone()
   }
 
   async fn sign_zome_call(&self, unsigned_zome_call: ZomeCallUnsigned) -> Result<ZomeCall, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
     sign_zome_call_with_client(
       unsigned_zome_call,
-      &self.client,
+      &self.client().await,
+      &self.zome_call_validator,
     )
     .await
-    .map_err(|e| LairKeystoreError::SignZomeCallError(e))
+    .map_err(LairKeystoreError::from)
+  }
+
+  async fn sign_zome_calls(
+    &self,
+    unsigned_zome_calls: Vec<ZomeCallUnsigned>,
+  ) -> Result<Vec<Result<ZomeCall, LairKeystoreError>>, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
+    let results = sign_zome_calls_with_client(
+      unsigned_zome_calls,
+      &self.client().await,
+      &self.zome_call_validator,
+    )
+    .await;
+
+    Ok(
+      results
+        .into_iter()
+        .map(|result| result.map_err(LairKeystoreError::from))
+        .collect(),
+    )
+  }
+
+  async fn sign_by_pub_key(
+    &self,
+    agent_pub_key: AgentPubKey,
+    data: Vec<u8>,
+  ) -> Result<Signature, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
+    let mut pub_key = [0; 32];
+    pub_key.copy_from_slice(agent_pub_key.get_raw_32());
+
+    let signature = self
+      .client()
+      .await
+      .sign_by_pub_key(pub_key.into(), None, data.into())
+      .await
+      .map_err(|e| LairKeystoreError::SignError(e.str_kind().to_string()))?;
+
+    Ok(Signature(*signature.0))
+  }
+
+  fn lock(&self) {
+    self.locked.store(true, Ordering::SeqCst);
+  }
+
+  async fn unlock(&self, password: BufRead) -> Result<(), LairKeystoreError> {
+    // lair-keystore exited and waits for the passphrase to be restarted
+    if self.awaiting_password.load(Ordering::SeqCst) {
+      let (response, result) = oneshot::channel();
+      self
+        .restart_requests
+        .unbounded_send((password, response))
+        .map_err(|_| LairKeystoreError::OtherError(String::from("lair-keystore is not supervised anymore")))?;
+
+      return result
+        .await
+        .map_err(|_| LairKeystoreError::OtherError(String::from("lair-keystore is not supervised anymore")))?;
+    }
+
+    // lair-keystore refuses the connection if the passphrase is wrong
+    let client = ipc_keystore_connect(Url::from(self.connection_url()), password)
+      .await
+      .map_err(|e| {
+        log::warn!("Failed to unlock the keystore: {:?}", e);
+        LairKeystoreError::IncorrectPassword
+      })?;
+
+    *self.client.lock().await = client;
+    self.locked.store(false, Ordering::SeqCst);
+
+    Ok(())
+  }
+
+  fn is_locked(&self) -> bool {
+    self.locked.load(Ordering::SeqCst)
+  }
+
+  async fn generate_agent_key(&self) -> Result<AgentPubKey, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
+    // tagged the same way as the seeds the conductors generate
+    let seed_info = self
+      .client()
+      .await
+      .new_seed(nanoid::nanoid!().into(), None, false)
+      .await
+      .map_err(|e| LairKeystoreError::OtherError(format!("Failed to generate agent key: {:?}", e)))?;
+
+    Ok(AgentPubKey::from_raw_32(seed_info.ed25519_pub_key.to_vec()))
+  }
+
+  async fn contains_agent_key(&self, agent_pub_key: &AgentPubKey) -> Result<bool, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
+    let entries = self
+      .client()
+      .await
+      .list_entries()
+      .await
+      .map_err(|e| LairKeystoreError::OtherError(format!("Failed to list lair entries: {:?}", e)))?;
+
+    Ok(entries.into_iter().any(|entry| match entry {
+      LairEntryInfo::Seed { seed_info, .. } | LairEntryInfo::DeepLockedSeed { seed_info, .. } => {
+        seed_info.ed25519_pub_key.to_vec() == agent_pub_key.get_raw_32()
+      }
+      _ => false,
+    }))
+  }
+
+  async fn list_agent_keys(&self) -> Result<Vec<AgentKeyEntry>, LairKeystoreError> {
+    if self.is_locked() {
+      return Err(LairKeystoreError::KeystoreLocked);
+    }
+
+    let entries = self
+      .client()
+      .await
+      .list_entries()
+      .await
+      .map_err(|e| LairKeystoreError::OtherError(format!("Failed to list lair entries: {:?}", e)))?;
+
+    let inventory = KeyInventory::read(&self.keystore_path);
+
+    let agent_keys = entries
+      .into_iter()
+      .filter_map(|entry| match entry {
+        LairEntryInfo::Seed { tag, seed_info } | LairEntryInfo::DeepLockedSeed { tag, seed_info } => {
+          Some((tag, seed_info))
+        }
+        _ => None,
+      })
+      .map(|(tag, seed_info)| {
+        let agent_pub_key = AgentPubKey::from_raw_32(seed_info.ed25519_pub_key.to_vec());
+        let label = inventory.labels.get(&agent_pub_key.to_string()).cloned();
+        AgentKeyEntry {
+          tag: tag.to_string(),
+          agent_pub_key,
+          label,
+        }
+      })
+      .filter(|entry| !inventory.hidden.contains(&entry.agent_pub_key.to_string()))
+      .collect();
+
+    Ok(agent_keys)
+  }
+
+  fn set_agent_key_label(
+    &self,
+    agent_pub_key: AgentPubKey,
+    label: Option<String>,
+  ) -> Result<(), LairKeystoreError> {
+    let mut inventory = KeyInventory::read(&self.keystore_path);
+    match label {
+      Some(label) => inventory.labels.insert(agent_pub_key.to_string(), label),
+      None => inventory.labels.remove(&agent_pub_key.to_string()),
+    };
+    inventory.write(&self.keystore_path)
+  }
+
+  fn hide_agent_key(&self, agent_pub_key: AgentPubKey) -> Result<(), LairKeystoreError> {
+    let mut inventory = KeyInventory::read(&self.keystore_path);
+    inventory.labels.remove(&agent_pub_key.to_string());
+    inventory.hidden.insert(agent_pub_key.to_string());
+    inventory.write(&self.keystore_path)
   }
 
-  fn password(&self) -> String {
-    self.password.clone()
+  fn subscribe_health_events(&self) -> UnboundedReceiver<KeystoreHealthEvent> {
+    self.health_events.subscribe()
   }
 }
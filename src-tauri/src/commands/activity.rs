@@ -0,0 +1,9 @@
+use crate::launcher::idle_lock::UserActivity;
+
+/// Resets the idle-lock timer upon user input in any window. It carries no data, so happ windows
+/// may report their input too.
+#[tauri::command]
+pub fn report_user_activity(activity: tauri::State<'_, UserActivity>) -> Result<(), String> {
+  activity.touch();
+  Ok(())
+}
@@ -0,0 +1,49 @@
+use hdk::prelude::AgentPubKey;
+
+use crate::launcher::state::{AgentKeyInventory, LauncherState};
+
+#[tauri::command]
+pub async fn get_agent_key_inventory(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+) -> Result<AgentKeyInventory, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'get_agent_key_inventory' which is not allowed in this window."))
+  }
+
+  let agent_keys = (*state).lock().await.get_running()?.agent_keys()?;
+
+  agent_keys.inventory().await
+}
+
+#[tauri::command]
+pub async fn set_agent_key_label(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  agent_pub_key: AgentPubKey,
+  label: Option<String>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'set_agent_key_label' which is not allowed in this window."))
+  }
+
+  let agent_keys = (*state).lock().await.get_running()?.agent_keys()?;
+
+  agent_keys.set_label(agent_pub_key, label)
+}
+
+/// Hides an agent key no installed app uses anymore from the inventory. It can't be deleted from lair-keystore.
+#[tauri::command]
+pub async fn hide_unused_agent_key(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  agent_pub_key: AgentPubKey,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'hide_unused_agent_key' which is not allowed in this window."))
+  }
+
+  let agent_keys = (*state).lock().await.get_running()?.agent_keys()?;
+
+  agent_keys.hide_unused(agent_pub_key).await
+}
@@ -0,0 +1,76 @@
+use holochain_web_app_manager::{app_metadata::AppMetadata, zome_call_policy::ZomeCallPolicy};
+
+use tauri::Manager;
+
+use crate::launcher::{apps_refresher::AppsRefresher, state::LauncherState, manager::HolochainId};
+
+/// Gets everything the launcher knows about an installed app besides what the conductor reports
+#[tauri::command]
+pub async fn get_app_metadata(
+  state: tauri::State<'_, LauncherState>,
+  window: tauri::window::Window,
+  holochain_id: HolochainId,
+  app_id: String,
+) -> Result<Option<AppMetadata>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'get_app_metadata' which is not allowed in this window."))
+  }
+
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+
+  let app_metadata = web_app_manager.lock().await.get_app_metadata(&app_id);
+  Ok(app_metadata)
+}
+
+/// Sets the name given to an app by the user. An empty label removes it.
+#[tauri::command]
+pub async fn set_app_label(
+  state: tauri::State<'_, LauncherState>,
+  window: tauri::window::Window,
+  holochain_id: HolochainId,
+  app_id: String,
+  label: Option<String>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'set_app_label' which is not allowed in this window."))
+  }
+
+  let label = label
+    .map(|label| label.trim().to_string())
+    .filter(|label| !label.is_empty());
+
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+
+  web_app_manager
+    .lock()
+    .await
+    .set_app_label(&app_id, label)?;
+
+  window.app_handle().state::<AppsRefresher>().request();
+
+  Ok(())
+}
+
+/// Overrides the zomes and functions the UIs of an app may call. None allows calls to any zome of the app's own cells.
+#[tauri::command]
+pub async fn set_zome_call_policy(
+  state: tauri::State<'_, LauncherState>,
+  window: tauri::window::Window,
+  holochain_id: HolochainId,
+  app_id: String,
+  zome_call_policy: Option<ZomeCallPolicy>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'set_zome_call_policy' which is not allowed in this window."))
+  }
+
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+
+  web_app_manager
+    .lock()
+    .await
+    .set_zome_call_policy(&app_id, zome_call_policy)
+    .await?;
+
+  Ok(())
+}
@@ -0,0 +1,190 @@
+use holochain_web_app_manager::{window_registry::derive_window_label, ReleaseInfo, DEFAULT_UI_NAME};
+use mr_bundle::ResourceBytes;
+use tauri::Manager;
+
+use crate::{launcher::{apps_refresher::AppsRefresher, state::LauncherState, manager::HolochainId}};
+
+/// Installs an additional named UI for an installed app
+#[tauri::command]
+pub async fn add_app_ui(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: String,
+  ui_zip_bytes: Vec<u8>,
+  gui_release_info: Option<ReleaseInfo>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'add_app_ui' which is not allowed in this window."))
+  }
+
+  log::info!("Installing: UI '{}' for app '{}'", ui_name, app_id);
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  web_app_manager
+    .lock()
+    .await
+    .add_app_ui(app_id.clone(), ResourceBytes::from(ui_zip_bytes), &ui_name, gui_release_info)
+    .await?;
+
+  log::info!("Installed UI '{}' for app '{}'", ui_name, app_id);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn update_app_ui(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: String,
+  ui_zip_bytes: Vec<u8>,
+  gui_release_info: Option<ReleaseInfo>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'update_app_ui' which is not allowed in this window."))
+  }
+
+  log::info!("Installing: New version of UI '{}' for app '{}'", ui_name, app_id);
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  web_app_manager
+    .lock()
+    .await
+    .update_app_ui(app_id.clone(), ResourceBytes::from(ui_zip_bytes), &ui_name, gui_release_info)?;
+
+  log::info!("Installed new version of UI '{}' for app '{}'", ui_name, app_id);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_app_ui(
+  window: tauri::Window,
+  app_handle: tauri::AppHandle,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'remove_app_ui' which is not allowed in this window."))
+  }
+
+  let holochain_version = holochain_id.version();
+
+  // close the window of this UI first since its assets are about to be removed
+  if let Some(w) = app_handle.get_window(derive_window_label(&holochain_version, &app_id, &ui_name).as_str()) {
+    if let Err(err) = w.close() {
+      log::error!("Error closing window {:?}", err);
+    }
+  }
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  web_app_manager
+    .lock()
+    .await
+    .remove_app_ui(app_id.clone(), &ui_name)
+    .await?;
+
+  log::info!("Removed UI '{}' of app '{}'", ui_name, app_id);
+
+  Ok(())
+}
+
+/// Restores the previous revision of an app UI
+#[tauri::command]
+pub async fn rollback_app_ui(
+  window: tauri::Window,
+  app_handle: tauri::AppHandle,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: Option<String>,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'rollback_app_ui' which is not allowed in this window."))
+  }
+
+  let holochain_version = holochain_id.version();
+
+  let ui_name = ui_name.unwrap_or(String::from(DEFAULT_UI_NAME));
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  web_app_manager
+    .lock()
+    .await
+    .rollback_app_ui(app_id.clone(), &ui_name)?;
+
+  log::info!("Rolled back UI '{}' of app '{}' to its previous revision", ui_name, app_id);
+
+  // reload the window of this UI if it is open to show the restored assets
+  if let Some(w) = app_handle.get_window(derive_window_label(&holochain_version, &app_id, &ui_name).as_str()) {
+    if let Err(err) = w.eval("window.location.reload()") {
+      log::error!("Error reloading window {:?}", err);
+    }
+  }
+
+  Ok(())
+}
+
+/// Records the current assets of an app UI as trusted, e.g. after the user confirmed that
+/// modifications reported by the integrity check were intended
+#[tauri::command]
+pub async fn trust_app_ui_assets(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: Option<String>,
+) -> Result<String, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'trust_app_ui_assets' which is not allowed in this window."))
+  }
+
+  let ui_name = ui_name.unwrap_or(String::from(DEFAULT_UI_NAME));
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  let assets_manifest = web_app_manager
+    .lock()
+    .await
+    .record_ui_assets_manifest(&app_id, &ui_name)?;
+
+  log::warn!("Trusting the current assets of UI '{}' of app '{}' with hash {}", ui_name, app_id, assets_manifest.hash);
+
+  window.app_handle().state::<AppsRefresher>().request();
+
+  Ok(assets_manifest.hash)
+}
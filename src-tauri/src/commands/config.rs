@@ -1,4 +1,5 @@
 use holochain_manager::versions::common::{bootstrap_service, signaling_server};
+use lair_keystore_manager::versions::stop_supervision;
 use tauri::Manager;
 use crate::launcher::{config::LauncherConfig, error::LauncherError};
 use tauri::api::process;
@@ -27,6 +28,7 @@ pub async fn write_config(
     }
   }
 
+  stop_supervision();
   process::kill_children();
   app_handle.restart();
 
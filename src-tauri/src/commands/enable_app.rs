@@ -13,18 +13,16 @@ pub async fn enable_app(
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (D)"))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
 
-  manager
-    .get_web_happ_manager(holochain_id)?
+  web_app_manager
+    .lock()
+    .await
     .enable_app(app_id.clone())
     .await?;
 
   log::info!("Enabled app: app_id = {}", app_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
 
@@ -39,18 +37,16 @@ pub async fn disable_app(
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command."))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
 
-  manager
-    .get_web_happ_manager(holochain_id)?
+  web_app_manager
+    .lock()
+    .await
     .disable_app(app_id.clone())
     .await?;
 
   log::info!("Disabled app: app_id = {}", app_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
 
@@ -66,17 +62,15 @@ pub async fn delete_clone(
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command."))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
 
-  manager
-    .get_web_happ_manager(holochain_id)?
+  web_app_manager
+    .lock()
+    .await
     .delete_clone(app_id.clone(), cell_id.clone())
     .await?;
 
   log::info!("Deleted cloned cell: app_id = {}, cell_id = {:?}", app_id, cell_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use holochain_web_app_manager::DEFAULT_UI_NAME;
+use std::path::PathBuf;
+
+use crate::launcher::{state::LauncherState, manager::HolochainId};
+
+/// Exports an installed app together with one of its UIs as a .webhapp file to the given path
+#[tauri::command]
+pub async fn export_web_app(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: Option<String>,
+  path: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'export_web_app' which is not allowed in this window."))
+  }
+
+  let ui_name = ui_name.unwrap_or(String::from(DEFAULT_UI_NAME));
+
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+
+  web_app_manager
+    .lock()
+    .await
+    .export_web_app(&app_id, &ui_name, &PathBuf::from(&path))
+    .await?;
+
+  log::info!("Exported app '{}' with UI '{}' to {}", app_id, ui_name, path);
+
+  Ok(())
+}
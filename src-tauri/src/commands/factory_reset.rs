@@ -1,4 +1,5 @@
 use std::{fs, io, path::PathBuf};
+use lair_keystore_manager::versions::stop_supervision;
 
 use tauri::{api::process::kill_children, Manager};
 
@@ -38,6 +39,7 @@ pub async fn execute_factory_reset(
   }
 
   // Kill all the children processes to avoid messing up with the filesystem
+  stop_supervision();
   kill_children();
   log::info!("Stopped children processes");
 
@@ -1,8 +1,6 @@
-use async_recursion::async_recursion;
 use std::collections::HashMap;
 
 use holochain_manager::versions::{HolochainVersion, version_manager::VersionManager};
-use holochain_web_app_manager::WebAppManager;
 
 use crate::{
   launcher::{
@@ -37,6 +35,8 @@ pub async fn get_state_info(
   })
 }
 
+/// Served from the snapshots of the WebAppManagers, such that the admin window doesn't wait for
+/// operations running on the conductors
 async fn inner_get_state_info(
   state: tauri::State<'_, LauncherState>,
 ) -> Result<
@@ -47,65 +47,60 @@ async fn inner_get_state_info(
 
   let state: &mut RunningState<LauncherManager, LauncherError> = &mut *mutex;
 
-  match state {
-    RunningState::Running(manager) => {
-      if let RunningState::Error(err) = &manager.lair_keystore_manager {
-        return Ok(RunningState::Running(RunningState::Error(err.clone())));
-      }
-
-      let mut holochain_manager_states: HashMap<HolochainVersion, HolochainState> = HashMap::new();
-
-      let versions: Vec<HolochainVersion> = manager.holochain_managers.keys().cloned().collect();
-
-      for holochain_version in versions {
-        match manager.get_web_happ_manager(HolochainId::HolochainVersion(holochain_version.clone()))
-        {
-          Ok(holochain_manager) => {
-            let running_state = get_holochain_state(holochain_manager, 2).await;
-            holochain_manager_states.insert(holochain_version.clone(), running_state);
-          }
-          Err(err) => {
-            holochain_manager_states
-              .insert(holochain_version.clone(), RunningState::Error(err.clone()));
-          }
-        }
-      }
-
-      let custom_binary = match &mut manager.custom_binary_manager {
-        Some(RunningState::Running(m)) => Some(get_holochain_state(m, 2).await),
-        Some(RunningState::Error(err)) => Some(RunningState::Error(format!(
-          "There was an error launching the custom Holochain binary: {:?}",
-          err
-        ))),
-        None => None,
-      };
-
-      Ok(RunningState::Running(RunningState::Running(
-        RunningHolochainsStateInfo {
-          versions: holochain_manager_states,
-          custom_binary,
-        },
-      )))
-    }
-    RunningState::Error(err) => Ok(RunningState::Error(err.clone())),
+  let manager = match state {
+    RunningState::Running(manager) => manager,
+    RunningState::Error(err) => return Ok(RunningState::Error(err.clone())),
+  };
+
+  if let RunningState::Error(err) = &manager.lair_keystore_manager {
+    return Ok(RunningState::Running(RunningState::Error(err.clone())));
+  }
+  if manager.is_keystore_locked() {
+    return Ok(RunningState::Running(RunningState::Error(KeystoreStatus::PasswordNecessary)));
+  }
+
+  let mut holochain_manager_states: HashMap<HolochainVersion, HolochainState> = HashMap::new();
+
+  for holochain_version in manager.holochain_managers.keys() {
+    let holochain_id = HolochainId::HolochainVersion(holochain_version.clone());
+    holochain_manager_states.insert(holochain_version.clone(), get_holochain_state(manager, holochain_id));
   }
+
+  let custom_binary = match &manager.custom_binary_manager {
+    Some(RunningState::Running(_)) => Some(get_holochain_state(manager, HolochainId::CustomBinary)),
+    Some(RunningState::Error(err)) => Some(RunningState::Error(format!(
+      "There was an error launching the custom Holochain binary: {:?}",
+      err
+    ))),
+    None => None,
+  };
+
+  Ok(RunningState::Running(RunningState::Running(
+    RunningHolochainsStateInfo {
+      versions: holochain_manager_states,
+      custom_binary,
+    },
+  )))
 }
 
-#[async_recursion]
-async fn get_holochain_state(web_app_manager: &mut WebAppManager, retries: usize) -> HolochainState {
-  match web_app_manager.list_apps().await {
-    Ok(installed_apps) => RunningState::Running(HolochainInfo {
+fn get_holochain_state(manager: &LauncherManager, holochain_id: HolochainId) -> HolochainState {
+  if let Err(err) = manager.get_web_happ_manager(holochain_id.clone()) {
+    return RunningState::Error(err);
+  }
+
+  let snapshot = match manager.get_snapshot(&holochain_id) {
+    Some(snapshot) => snapshot,
+    None => return RunningState::Error(String::from("Could not fetch installed apps")),
+  };
+
+  match snapshot.installed_apps() {
+    Some(installed_apps) => RunningState::Running(HolochainInfo {
       installed_apps,
-      app_interface_port: web_app_manager.app_interface_port(),
-      admin_interface_port: web_app_manager.admin_interface_port(),
-      hdi_version: web_app_manager.holochain_manager.version.manager().hdi_version(),
-      hdk_version: web_app_manager.holochain_manager.version.manager().hdk_version(),
+      app_interface_port: snapshot.app_interface_port,
+      admin_interface_port: snapshot.admin_interface_port,
+      hdi_version: snapshot.version.manager().hdi_version(),
+      hdk_version: snapshot.version.manager().hdk_version(),
     }),
-    Err(err) => {
-      match retries {
-        0 => RunningState::Error(format!("Could not fetch installed apps: {}", err)),
-        _ => get_holochain_state(web_app_manager, retries - 1).await,
-      }
-    }
+    None => RunningState::Error(String::from("The installed apps have not been fetched yet")),
   }
 }
@@ -14,10 +14,8 @@ pub async fn store_icon_src(
     return Err(String::from("Unauthorized: Attempted to call tauri command 'store_icon_src' which is not allowed in this window."))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-
-  let webapp_manager = manager.get_web_happ_manager(holochain_id)?;
+  let webapp_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+  let mut webapp_manager = webapp_manager.lock().await;
 
   webapp_manager.store_app_icon_src(icon_src, &app_id)
 
@@ -35,10 +33,8 @@ pub async fn get_icon_src(
     return Err(String::from("Unauthorized: Attempted to call tauri command 'get_icon_src' which is not allowed in this window."))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-
-  let webapp_manager = manager.get_web_happ_manager(holochain_id)?;
+  let webapp_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+  let webapp_manager = webapp_manager.lock().await;
 
   Ok(webapp_manager.get_app_icon_src(&app_id))
 
@@ -0,0 +1,75 @@
+use hdk::prelude::AgentPubKey;
+
+use crate::{
+  file_system::Profile,
+  launcher::{
+    identities::{IdentitiesState, IdentityInfo},
+    state::LauncherState,
+  },
+};
+
+#[tauri::command]
+pub async fn list_identities(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  identities: tauri::State<'_, IdentitiesState>,
+) -> Result<Vec<IdentityInfo>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'list_identities' which is not allowed in this window."))
+  }
+
+  let agent_keys = (*state).lock().await.get_running()?.agent_keys()?;
+
+  agent_keys.list_identities(&identities).await
+}
+
+/// Creates a named identity. Generates a new agent key if no existing one is given.
+#[tauri::command]
+pub async fn create_identity(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  identities: tauri::State<'_, IdentitiesState>,
+  name: String,
+  agent_pub_key: Option<AgentPubKey>,
+) -> Result<AgentPubKey, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'create_identity' which is not allowed in this window."))
+  }
+
+  let agent_keys = (*state).lock().await.get_running()?.agent_keys()?;
+
+  agent_keys.create_identity(&identities, name, agent_pub_key).await
+}
+
+#[tauri::command]
+pub async fn rename_identity(
+  window: tauri::Window,
+  profile: tauri::State<'_, Profile>,
+  identities: tauri::State<'_, IdentitiesState>,
+  name: String,
+  new_name: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'rename_identity' which is not allowed in this window."))
+  }
+
+  identities
+    .lock()
+    .await
+    .rename(&name, new_name, profile.inner().clone())
+}
+
+/// Removes the identity's name. The agent key stays in the keystore and the apps using it are not affected.
+#[tauri::command]
+pub async fn remove_identity(
+  window: tauri::Window,
+  profile: tauri::State<'_, Profile>,
+  identities: tauri::State<'_, IdentitiesState>,
+  name: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'remove_identity' which is not allowed in this window."))
+  }
+
+  identities.lock().await.remove(&name, profile.inner().clone())
+}
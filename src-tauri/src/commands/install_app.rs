@@ -7,19 +7,20 @@ use holochain_manager::versions::{
 use holochain_web_app_manager::ReleaseInfo;
 use std::{collections::HashMap, fs, sync::Arc};
 
-use crate::{launcher::{state::LauncherState, manager::HolochainId}, file_system::Profile};
+use crate::launcher::{state::LauncherState, manager::HolochainId, identities::IdentitiesState};
 
 #[tauri::command]
 pub async fn install_app(
   window: tauri::Window,
   state: tauri::State<'_, LauncherState>,
-  profile: tauri::State<'_, Profile>,
+  identities: tauri::State<'_, IdentitiesState>,
   holochain_id: HolochainId,
   app_id: String,
   app_bundle_path: String,
   network_seed: Option<String>,
   membrane_proofs: HashMap<String, Vec<u8>>,
   reuse_agent_pub_key: Option<AgentPubKey>,
+  identity: Option<String>,
   happ_release_info: Option<ReleaseInfo>,
   gui_release_info: Option<ReleaseInfo>,
 ) -> Result<(), String> {
@@ -29,6 +30,21 @@ pub async fn install_app(
 
   log::info!("Installing: web_app_bundle = {}", app_bundle_path);
 
+  // a named identity is resolved to the agent key it stands for
+  let reuse_agent_pub_key = match identity {
+    Some(name) => {
+      if reuse_agent_pub_key.is_some() {
+        return Err(String::from("Either an identity or a public key can be reused, not both."));
+      }
+      let identities = identities.lock().await;
+      let identity = identities
+        .get(&name)
+        .ok_or(format!("There is no identity with the name '{}'", name))?;
+      Some(AgentPubKey::from(identity.agent_pub_key.clone()))
+    }
+    None => reuse_agent_pub_key,
+  };
+
   let mut converted_membrane_proofs: HashMap<String, MembraneProof> = HashMap::new();
   for (dna_slot, proof) in membrane_proofs.iter() {
     converted_membrane_proofs.insert(
@@ -37,19 +53,20 @@ pub async fn install_app(
     );
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-
   let bytes = fs::read(&app_bundle_path).or(Err("Failed to read Web hApp bundle file"))?;
 
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
 
   match WebAppBundle::decode(&bytes) {
     Ok(web_app_bundle) => {
-      manager
-        .get_or_launch_holochain(
-          holochain_id,
-          profile.inner().clone(),
-        ).await?
+      web_app_manager
+        .lock()
+        .await
         .install_web_app(
           app_id.clone(),
           web_app_bundle,
@@ -63,11 +80,9 @@ pub async fn install_app(
     }
     Err(_) => {
       let app_bundle = AppBundle::decode(&bytes).or(Err("Failed to decode Web hApp of hApp bundle file"))?;
-      manager
-        .get_or_launch_holochain(
-          holochain_id,
-          profile.inner().clone(),
-        ).await?
+      web_app_manager
+        .lock()
+        .await
         .install_app(
           app_id.clone(),
           app_bundle,
@@ -82,7 +97,5 @@ pub async fn install_app(
 
   log::info!("Installed hApp {}", app_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
@@ -19,10 +19,12 @@ pub async fn install_devhub(
 
   log::info!("Requested to install DevHub");
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-
-  let webapp_manager = manager.get_web_happ_manager(HolochainId::HolochainVersion(HolochainVersion::default()))?;
+  let webapp_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_web_happ_manager(HolochainId::HolochainVersion(HolochainVersion::default()))?;
+  let mut webapp_manager = webapp_manager.lock().await;
 
   let apps = webapp_manager.list_apps().await?;
 
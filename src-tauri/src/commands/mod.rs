@@ -1,21 +1,31 @@
+pub mod activity;
+pub mod agent_keys;
+pub mod app_metadata;
+pub mod app_uis;
 pub mod config;
 pub mod choose_version;
 pub mod enable_app;
+pub mod export_app;
 pub mod factory_reset;
 pub mod get_app_info;
 pub mod get_state_info;
 pub mod icon_src;
+pub mod identities;
 pub mod install_app;
 pub mod install_devhub;
 pub mod network_stats;
 pub mod notifications;
 pub mod open_app;
+pub mod orphaned_data;
 pub mod password;
 pub mod quit;
 pub mod restart;
 pub mod save_app;
+pub mod sign_bytes;
 pub mod sign_zome_call;
 // pub mod start_app;
 pub mod storage;
 pub mod uninstall_app;
 pub mod update_default_ui;
+pub mod upgrade_app;
+pub mod zome_call_audit_log;
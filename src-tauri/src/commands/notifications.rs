@@ -1,6 +1,6 @@
 use futures::lock::Mutex;
 use holochain_client::InstalledAppId;
-use holochain_web_app_manager::derive_window_label;
+use holochain_web_app_manager::window_registry::WindowRegistryState;
 use serde::{Deserialize, Serialize};
 use tauri::{api::notification::Notification, Manager, Icon};
 
@@ -45,12 +45,16 @@ impl SysTrayIconState {
 
 #[tauri::command]
 pub async fn notify_tauri(
-    // window: tauri::Window,
+    window: tauri::Window,
     app_handle: tauri::AppHandle,
+    window_registry: tauri::State<'_, WindowRegistryState>,
     notifications: Vec<HappNotification>,
     app_id: InstalledAppId,
-) -> tauri::Result<()> {
-    // This tauri command is allowed for any window.
+) -> Result<(), String> {
+    // This tauri command is allowed for any window, but app windows may only send notifications for their own app.
+    if window.label() != "admin" && !is_window_of_app(&window_registry, window.label(), &app_id).await {
+        return Err(String::from("Unauthorized: Attempted to send notifications for app that this tauri window is not associated to."))
+    }
 
     // Send notifications to admin window to store to localStorage and check
     // OS notification settings for this app.
@@ -59,6 +63,7 @@ pub async fn notify_tauri(
             notifications,
             app_id,
         })
+        .map_err(|e| format!("Failed to emit event to admin window: {}", e))
     } else {
         // The admin window must always be running invisibly in the background
         // so this case should not occur
@@ -71,6 +76,7 @@ pub async fn notify_tauri(
 pub async fn notify_os(
     window: tauri::Window,
     app_handle: tauri::AppHandle,
+    window_registry: tauri::State<'_, WindowRegistryState>,
     // profile: tauri::State<'_, Profile>,
     notifications: Vec<HappNotification>,
     app_id: InstalledAppId,
@@ -81,12 +87,18 @@ pub async fn notify_os(
         return Err(String::from("Unauthorized: Attempted to call tauri command 'notify_os' which is not allowed in this window."))
     }
 
-    // Only notify the OS if the happ's window is not focused,
-    let happ_window_focused = match app_handle.get_window(derive_window_label(&app_id).as_str()) {
-        Some(happ_window) => happ_window.is_focused()
-            .map_err(|e| format!("Failed to get focus state of happ window: {}", e))?,
-        None => false,
-    };
+    // Only notify the OS if none of the happ's windows is focused,
+    let happ_window_labels: Vec<String> = window_registry.lock().await
+        .windows_of_app(&app_id)
+        .map(|(window_label, _)| window_label.clone())
+        .collect();
+    let mut happ_window_focused = false;
+    for window_label in happ_window_labels {
+        if let Some(happ_window) = app_handle.get_window(window_label.as_str()) {
+            happ_window_focused |= happ_window.is_focused()
+                .map_err(|e| format!("Failed to get focus state of happ window: {}", e))?;
+        }
+    }
     let admin_window_focused = window.is_focused()
         .map_err(|e| format!("Failed to get focus state of admin window: {}", e))?;
 
@@ -123,10 +135,11 @@ pub async fn notify_os(
 pub async fn clear_happ_notifications(
     app_handle: tauri::AppHandle,
     window: tauri::Window,
+    window_registry: tauri::State<'_, WindowRegistryState>,
     app_id: InstalledAppId
 ) -> Result<(), String> {
-    // This tauri command is allowed to be called only by the window of the corresponding app:
-    if window.label() != derive_window_label(&app_id) {
+    // This tauri command is allowed to be called only by the windows of the corresponding app:
+    if !is_window_of_app(&window_registry, window.label(), &app_id).await {
         return Err(String::from("Unauthorized: Attempted to clear notifications for app that this tauri window is not associated to."))
     }
 
@@ -152,11 +165,12 @@ struct ResetHappNotificationPayload {
 pub async fn reset_happ_notification_count(
     app_handle: tauri::AppHandle,
     window: tauri::Window,
+    window_registry: tauri::State<'_, WindowRegistryState>,
     app_id: InstalledAppId,
     notification_ids: Vec<NotificationId>,
 ) -> Result<(), String> {
-    // This tauri command is allowed to be called only by the window of the corresponding app:
-    if window.label() != derive_window_label(&app_id) {
+    // This tauri command is allowed to be called only by the windows of the corresponding app:
+    if !is_window_of_app(&window_registry, window.label(), &app_id).await {
         return Err(String::from("Unauthorized: Attempted to notifications for app that this tauri window is not associated to."))
     }
 
@@ -173,6 +187,13 @@ pub async fn reset_happ_notification_count(
 }
 
 
+async fn is_window_of_app(window_registry: &WindowRegistryState, window_label: &str, app_id: &InstalledAppId) -> bool {
+    window_registry.lock().await
+        .get(window_label)
+        .map_or(false, |identity| &identity.app_id == app_id)
+}
+
+
 async fn change_systray_icon_state(
   app_handle: &tauri::AppHandle,
   urgency: &String,
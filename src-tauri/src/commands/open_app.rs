@@ -1,4 +1,6 @@
-use crate::launcher::{state::LauncherState, manager::HolochainId};
+use holochain_web_app_manager::DEFAULT_UI_NAME;
+
+use crate::launcher::{state::LauncherState, manager::{open_app, HolochainId}};
 
 #[tauri::command]
 pub async fn open_app_ui(
@@ -6,16 +8,16 @@ pub async fn open_app_ui(
   state: tauri::State<'_, LauncherState>,
   holochain_id: HolochainId,
   app_id: String,
+  ui_name: Option<String>,
   visible: bool,
 ) -> Result<(), String> {
   if window.label() != "admin" {
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (J)"))
   }
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let ui_name = ui_name.unwrap_or(String::from(DEFAULT_UI_NAME));
 
-  manager
-    .open_app(holochain_id, &app_id, visible)
+  open_app(&state, holochain_id, &app_id, &ui_name, visible)
+    .await
     .map_err(|err| format!("Error opening app: {}", err))?;
 
   log::info!("Opening app {}", app_id.clone(),);
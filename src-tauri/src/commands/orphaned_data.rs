@@ -0,0 +1,56 @@
+use holochain_web_app_manager::OrphanedData;
+
+use crate::{launcher::{state::LauncherState, manager::HolochainId}};
+
+/// Lists data that isn't used by any installed app anymore, without removing anything
+#[tauri::command]
+pub async fn find_orphaned_data(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+) -> Result<Vec<OrphanedData>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'find_orphaned_data' which is not allowed in this window."))
+  }
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  let orphans = web_app_manager.lock().await.find_orphaned_data().await?;
+
+  Ok(orphans)
+}
+
+/// Removes the orphaned data the user confirmed to remove, as returned by find_orphaned_data
+#[tauri::command]
+pub async fn remove_orphaned_data(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  orphans: Vec<OrphanedData>,
+) -> Result<Vec<OrphanedData>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'remove_orphaned_data' which is not allowed in this window."))
+  }
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  let removed = web_app_manager
+    .lock()
+    .await
+    .remove_orphaned_data(orphans)
+    .await?;
+
+  log::info!("Removed {} orphaned data item(s)", removed.len());
+
+  Ok(removed)
+}
@@ -1,4 +1,9 @@
-use crate::{launcher::state::LauncherState, file_system::Profile};
+use lair_keystore_manager::utils::secure_passphrase;
+
+use crate::{
+  file_system::Profile,
+  launcher::{manager::relaunch_holochain_managers, state::LauncherState},
+};
 
 #[tauri::command]
 pub async fn initialize_keystore(
@@ -10,6 +15,8 @@ pub async fn initialize_keystore(
   if window.label() != "admin" {
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (K)"))
   }
+  let password = secure_passphrase(password).map_err(|e| e.to_string())?;
+
   // emitting signal to the front-end for progress indication
   window.emit("progress-update", String::from("Initializing keystore"))
     .map_err(|e| format!("Failed to send signal to the frontend: {:?}", e))?;
@@ -34,11 +41,51 @@ pub async fn unlock_and_launch(
   if window.label() != "admin" {
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (K)"))
   }
+  let password = secure_passphrase(password).map_err(|e| e.to_string())?;
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let unlocked = {
+    let mut mutex = (*state).lock().await;
+    let manager = mutex.get_running()?;
+
+    if manager.is_keystore_locked() {
+      manager.unlock_keystore(password.clone()).await?;
+      true
+    } else {
+      manager.launch_managers(password.clone(), profile.inner().clone()).await?;
+      false
+    }
+  };
 
-  manager.launch_managers(password, profile.inner().clone()).await?;
+  // conductors can only be launched while the passphrase is at hand, it is not kept afterwards
+  if unlocked {
+    if let Err(e) = relaunch_holochain_managers(&state, password).await {
+      log::error!("Failed to point conductors to the restarted lair-keystore: {}", e);
+    }
+  }
 
   Ok(())
 }
+
+/// Launches the conductors that got requested while the launcher was running. They can only be
+/// launched with the passphrase, which is not kept after launch.
+#[tauri::command]
+pub async fn launch_pending_holochains(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  password: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (K)"))
+  }
+  let password = secure_passphrase(password).map_err(|e| e.to_string())?;
+
+  // verifies the passphrase before launching conductors with it, the running ones keep signing
+  (*state)
+    .lock()
+    .await
+    .get_running()?
+    .unlock_keystore(password.clone())
+    .await?;
+
+  relaunch_holochain_managers(&state, password).await
+}
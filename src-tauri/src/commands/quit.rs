@@ -1,4 +1,5 @@
 use tauri::api::process;
+use lair_keystore_manager::versions::stop_supervision;
 
 // restarts the Holochain Launcher
 #[tauri::command]
@@ -12,6 +13,7 @@ pub fn quit(
   }
 
   log::warn!("Quitting the Launcher has been requested, Quitting...");
+  stop_supervision();
   process::kill_children();
   app_handle.exit(0);
   Ok(())
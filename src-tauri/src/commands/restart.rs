@@ -1,4 +1,5 @@
 use tauri::api::process;
+use lair_keystore_manager::versions::stop_supervision;
 
 
 // restarts the Holochain Launcher
@@ -14,6 +15,7 @@ pub fn restart(
 
   log::warn!("A Restart of the Launcher has been requested, restarting...");
 
+  stop_supervision();
   process::kill_children();
   app_handle.restart();
   Ok(())
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, env::temp_dir, fs, path::PathBuf, time::SystemTime};
+use std::{collections::BTreeMap, env::temp_dir, fs, path::PathBuf, sync::Arc, time::SystemTime};
 
 use devhub_types::{
   encode_bundle, happ_entry_types::GUIReleaseEntry, happ_entry_types::HappManifest, DevHubResponse,
@@ -18,10 +18,7 @@ use mere_memory_types::{MemoryBlockEntry, MemoryEntry};
 // use mere_memory_types::MemoryEntry;
 use serde::de::DeserializeOwned;
 
-use crate::{
-  file_system::Profile,
-  launcher::{manager::HolochainId, state::LauncherState},
-};
+use crate::launcher::{manager::HolochainId, state::LauncherState};
 
 #[tauri::command]
 pub fn save_app(window: tauri::Window, app_bundle_bytes: Vec<u8>) -> Result<PathBuf, String> {
@@ -45,7 +42,6 @@ pub fn save_app(window: tauri::Window, app_bundle_bytes: Vec<u8>) -> Result<Path
 pub async fn fetch_and_save_app(
   window: tauri::Window,
   state: tauri::State<'_, LauncherState>,
-  profile: tauri::State<'_, Profile>,
   holochain_id: HolochainId,
   appstore_app_id: String,
   app_title: String,
@@ -75,13 +71,19 @@ pub async fn fetch_and_save_app(
     })?,
   );
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  // the DevHub is queried without holding the launcher lock
+  let (web_app_manager, lair_keystore_manager) = {
+    let mut mutex = (*state).lock().await;
+    let manager = mutex.get_running()?;
+
+    let web_app_manager = manager
+      .get_or_launch_holochain(holochain_id)
+      .await?;
+    let lair_keystore_manager = manager.get_lair_keystore_manager()?.clone();
+    (web_app_manager, lair_keystore_manager)
+  };
 
-  let app_port = manager
-    .get_or_launch_holochain(holochain_id, profile.inner().clone())
-    .await?
-    .app_interface_port();
+  let app_port = web_app_manager.lock().await.app_interface_port();
 
   let mut ws = AppWebsocket::connect(format!("ws://localhost:{}", app_port))
     .await
@@ -105,7 +107,7 @@ pub async fn fetch_and_save_app(
   // fetch HappReleaseEntry to check whether it's a happ or webhapp
   let happ_release_entry_entity: Entity<HappReleaseEntry> = portal_remote_call(
     &mut ws,
-    manager.get_lair_keystore_manager()?,
+    &lair_keystore_manager,
     &appstore_pub_key,
     host.clone(),
     portal_cell,
@@ -124,7 +126,7 @@ pub async fn fetch_and_save_app(
     Some(_gui_hash) => {
       let bytes = fetch_and_assemble_web_happ(
         &mut ws,
-        manager.get_lair_keystore_manager()?,
+        &lair_keystore_manager,
         &appstore_pub_key,
         host,
         portal_cell,
@@ -144,7 +146,7 @@ pub async fn fetch_and_save_app(
     None => {
       let bytes = fetch_and_assemble_happ(
         &mut ws,
-        manager.get_lair_keystore_manager()?,
+        &lair_keystore_manager,
         &appstore_pub_key,
         host,
         portal_cell,
@@ -234,13 +236,16 @@ pub async fn fetch_gui(
       return Err(format!("No provisioned cell for role portal_api found."));
   };
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-  let lair_keystore_manager = manager.get_lair_keystore_manager()?;
+  let lair_keystore_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_lair_keystore_manager()?
+    .clone();
 
   let gui_release_entry_entity: Entity<GUIReleaseEntry> = portal_remote_call(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -255,7 +260,7 @@ pub async fn fetch_gui(
 
   let web_asset_file: Entity<FileEntry> = portal_remote_call(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -270,7 +275,7 @@ pub async fn fetch_gui(
 
   let ui_bytes = fetch_mere_memory(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -363,7 +368,7 @@ pub struct CoordinatorZomes {
 /// Fetch and assemble a happ from a devhub host
 async fn fetch_and_assemble_web_happ(
   app_websocket: &mut AppWebsocket,
-  lair_keystore_manager: &Box<dyn LairKeystoreManager>,
+  lair_keystore_manager: &Arc<dyn LairKeystoreManager>,
   agent_pub_key: &AgentPubKey,
   host: AgentPubKey,
   portal_cell: &ProvisionedCell,
@@ -457,7 +462,7 @@ async fn fetch_and_assemble_web_happ(
 /// Fetch and assemble a happ from a devhub host
 pub async fn fetch_and_assemble_happ(
   app_websocket: &mut AppWebsocket,
-  lair_keystore_manager: &Box<dyn LairKeystoreManager>,
+  lair_keystore_manager: &Arc<dyn LairKeystoreManager>,
   agent_pub_key: &AgentPubKey,
   host: AgentPubKey,
   portal_cell: &ProvisionedCell,
@@ -595,7 +600,7 @@ pub async fn fetch_and_assemble_happ(
 /// Fetching and combining bytes by mere_memory_address
 pub async fn fetch_mere_memory(
   app_websocket: &mut AppWebsocket,
-  lair_keystore_manager: &Box<dyn LairKeystoreManager>,
+  lair_keystore_manager: &Arc<dyn LairKeystoreManager>,
   agent_pub_key: &AgentPubKey,
   host: AgentPubKey,
   portal_cell: &ProvisionedCell,
@@ -660,7 +665,7 @@ pub async fn portal_remote_call<
   U: Serialize + DeserializeOwned + core::fmt::Debug,
 >(
   app_websocket: &mut AppWebsocket,
-  lair_keystore_manager: &Box<dyn LairKeystoreManager>,
+  lair_keystore_manager: &Arc<dyn LairKeystoreManager>,
   agent_pub_key: &AgentPubKey,
   host: AgentPubKey,
   portal_cell: &ProvisionedCell,
@@ -0,0 +1,186 @@
+use hdk::prelude::{AgentPubKey, Signature};
+use holochain_web_app_manager::window_registry::WindowRegistryState;
+use lair_keystore_manager::utils::{sign_bytes_message, verify_sign_bytes_signature};
+use tauri::api::dialog::blocking::ask;
+
+use crate::{
+  commands::sign_zome_call::report_throttled_window,
+  file_system::Profile,
+  launcher::{
+    manager::HolochainId,
+    signing_consents::{SigningConsent, SigningConsentsState},
+    state::LauncherState,
+    zome_call_audit_log::{ZomeCallAuditEntry, ZomeCallAuditLog, ZomeCallOutcome},
+    zome_call_signer::ZomeCallSigner,
+  },
+};
+
+/// Data up to this length is shown to the user in the consent prompt if it is valid UTF-8
+const MAX_PREVIEW_LENGTH: usize = 500;
+
+/// Signs arbitrary bytes with the agent key the calling window is authorized to use,
+/// after the user consented to it. The bytes are prefixed with a domain tag before signing, so
+/// the signature can't be passed off as a zome call signature.
+#[tauri::command]
+pub async fn sign_bytes(
+  window: tauri::Window,
+  launcher_state: tauri::State<'_, LauncherState>,
+  window_registry: tauri::State<'_, WindowRegistryState>,
+  signing_consents: tauri::State<'_, SigningConsentsState>,
+  profile: tauri::State<'_, Profile>,
+  audit_log: tauri::State<'_, ZomeCallAuditLog>,
+  zome_call_signer: tauri::State<'_, ZomeCallSigner>,
+  data: Vec<u8>,
+) -> Result<Signature, String> {
+
+  // if window.label() != "admin" {
+  //   () // this function is allowed to be called in any happ window
+  // }
+
+  let window_label = window.label().to_string();
+
+  // The same window registry as for zome call signing determines which key a window may sign with.
+  // The admin window has no agent key associated to it.
+  let consent = {
+    let window_registry = &*window_registry.lock().await;
+    match window_registry.get(&window_label) {
+      Some(identity) => SigningConsent {
+        holochain_id: HolochainId::from(identity.holochain_version),
+        app_id: identity.app_id.clone(),
+        agent_pub_key: identity.agent_pub_key.clone(),
+      },
+      None => {
+        log::warn!("[SIGNING] WARNING: A tauri window without an authorized public key attempted to sign data. Window label: '{}'", window_label);
+        return Err(String::from("No authorized public key found for this window."));
+      }
+    }
+  };
+
+  // signing requests count against the same rate limit as zome calls
+  let quota = zome_call_signer.acquire(&window_label, 1);
+  if quota.newly_throttled {
+    report_throttled_window(&window, &window_registry).await;
+  }
+
+  let (result, outcome) = match quota.allowed {
+    0 => (
+      Err(String::from("This window exceeded its rate limit for signing requests. Try again later.")),
+      ZomeCallOutcome::Throttled,
+    ),
+    _ => {
+      let result = sign_with_consent(&window, &launcher_state, &signing_consents, &profile, &consent, &data).await;
+      let outcome = match &result {
+        Ok(_) => ZomeCallOutcome::Signed,
+        Err(e) => ZomeCallOutcome::Rejected(e.clone()),
+      };
+      (result, outcome)
+    }
+  };
+  audit_log.record(ZomeCallAuditEntry::sign_bytes(
+    &window_label,
+    Some(consent.app_id.clone()),
+    &consent.agent_pub_key,
+    data.len(),
+    outcome,
+  ));
+
+  result
+}
+
+/// Asks the user for consent unless it has been given permanently already, then signs the data
+async fn sign_with_consent(
+  window: &tauri::Window,
+  launcher_state: &LauncherState,
+  signing_consents: &SigningConsentsState,
+  profile: &Profile,
+  consent: &SigningConsent,
+  data: &Vec<u8>,
+) -> Result<Signature, String> {
+  let window_label = window.label().to_string();
+
+  // not held while asking the user
+  let consented = signing_consents.lock().await.contains(consent);
+
+  if !consented {
+    let app_name = window.title().unwrap_or(window_label.clone());
+    let preview = match String::from_utf8(data.clone()) {
+      Ok(text) if text.len() <= MAX_PREVIEW_LENGTH => format!("\n\n{}", text),
+      _ => String::new(),
+    };
+    let question = format!(
+      "The app '{}' wants to sign {} bytes of data with your public key.{}\n\nDo you want to allow this?",
+      app_name,
+      data.len(),
+      preview,
+    );
+
+    let parent = window.clone();
+    let (allowed, remember) = tauri::async_runtime::spawn_blocking(move || {
+      if !ask(Some(&parent), "Signing Request", question) {
+        return (false, false);
+      }
+      let remember = ask(
+        Some(&parent),
+        "Signing Request",
+        format!("Always allow '{}' to sign data without asking?", app_name),
+      );
+      (true, remember)
+    })
+    .await
+    .map_err(|e| format!("Failed to ask for consent: {:?}", e))?;
+
+    if !allowed {
+      return Err(String::from("The user declined to sign the data."));
+    }
+
+    if remember {
+      signing_consents
+        .lock()
+        .await
+        .add(consent.clone(), profile.clone())?;
+    }
+  }
+
+  let lair_keystore_manager = launcher_state
+    .lock()
+    .await
+    .get_running()?
+    .get_lair_keystore_manager()?
+    .clone();
+
+  lair_keystore_manager
+    .sign_by_pub_key(consent.agent_pub_key.clone(), sign_bytes_message(data))
+    .await
+    .map_err(|e| format!("Signing data failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn verify_signature(
+  agent_pub_key: AgentPubKey,
+  data: Vec<u8>,
+  signature: Signature,
+) -> Result<bool, String> {
+  // this function is allowed to be called in any window
+  verify_sign_bytes_signature(agent_pub_key, data, signature)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Makes all UIs of the app ask for consent again before signing data
+#[tauri::command]
+pub async fn revoke_signing_consent(
+  window: tauri::Window,
+  signing_consents: tauri::State<'_, SigningConsentsState>,
+  profile: tauri::State<'_, Profile>,
+  holochain_id: HolochainId,
+  app_id: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'revoke_signing_consent' which is not allowed in this window."))
+  }
+
+  signing_consents
+    .lock()
+    .await
+    .remove_app(&holochain_id, &app_id, profile.inner().clone())
+}
@@ -1,19 +1,40 @@
-use std::collections::HashMap;
-use futures::lock::Mutex;
-use std::sync::Arc;
-use crate::launcher::state::LauncherState;
-use hdk::prelude::AgentPubKey;
+
+use crate::launcher::{
+  manager::HolochainId,
+  state::LauncherState,
+  zome_call_audit_log::{ZomeCallAuditEntry, ZomeCallAuditLog, ZomeCallOutcome},
+  zome_call_signer::{ZomeCallSigner, ZomeCallThrottledPayload, ZOME_CALL_THROTTLED_EVENT},
+};
+use holochain_web_app_manager::window_registry::{WindowRegistryState, ZomeCallAuthorizationError};
 use lair_keystore_manager::*;
 use holochain_types::prelude::ZomeCallUnsigned;
 
 use holochain_launcher_utils::zome_call_signing::ZomeCallUnsignedTauri;
 
+/// Why a zome call did not get signed
+#[derive(Clone)]
+enum SigningFailure {
+  /// The window exceeded its zome call rate limit
+  Throttled,
+  Rejected(String),
+}
+
+impl From<SigningFailure> for String {
+  fn from(failure: SigningFailure) -> Self {
+    match failure {
+      SigningFailure::Throttled => String::from("This window exceeded its zome call rate limit. Try again later."),
+      SigningFailure::Rejected(e) => e,
+    }
+  }
+}
 
 #[tauri::command]
 pub async fn sign_zome_call(
   window: tauri::Window,
   launcher_state: tauri::State<'_, LauncherState>,
-  pubkey_map_state: tauri::State<'_, Arc<Mutex<HashMap<String, AgentPubKey>>>>,
+  window_registry: tauri::State<'_, WindowRegistryState>,
+  audit_log: tauri::State<'_, ZomeCallAuditLog>,
+  zome_call_signer: tauri::State<'_, ZomeCallSigner>,
   zome_call_unsigned: ZomeCallUnsignedTauri,
 ) -> Result<ZomeCall, String> {
 
@@ -22,42 +43,220 @@ pub async fn sign_zome_call(
   // }
 
   let window_label = window.label();
+  let zome_calls_unsigned = vec![zome_call_unsigned];
 
-  {
-    // validate that the agent public key added to the ZomeCallUnsigned field is actually
-    // one associated to the UI that's making the call
-    // NOTE: This part is in its own scope such that the pubkey_map_state lock gets released
-    // before the launcher_state lock is acquired. Otherwise, it can lead to a deadlock when
-    // calling install_app() around the same time (https://github.com/holochain/launcher/issues/182)
-    let pubkey_map = &*pubkey_map_state.lock().await;
-    let maybe_authorized_pubkey = pubkey_map.get(window_label);
-
-    if window_label != "admin" {
-      match maybe_authorized_pubkey {
-        Some(pubkey) => {
-          if pubkey != &zome_call_unsigned.provenance {
-            log::warn!("[ZOME CALL SIGNING] WARGNING: A tauri window attempted to make a zome call with a public key that it is not authorized to make zome calls with. Window label: '{}'", window_label);
-            return Err(String::from("The provided public key in the provenance field is not authorized to make a zome call to the requested cell."));
-          }
-        },
-        None => {
-          log::warn!("[ZOME CALL SIGNING] WARGNING: A tauri window attempted to make a zome call with a public key that it is not authorized to make zome calls with. Window label: '{}'", window_label);
-          return Err(String::from("No authorized public key found for this window."));
-        }
-      }
-    }
+  let mut results = authorize_and_sign(&window, &launcher_state, &window_registry, &zome_call_signer, zome_calls_unsigned.clone()).await;
+  record_zome_calls(&audit_log, &window_registry, window_label, &zome_calls_unsigned, &results).await;
+
+  results.remove(0).map_err(String::from)
+}
+
+/// Signs multiple zome calls in one go. Returns one result per zome call, in the same order.
+#[tauri::command]
+pub async fn sign_zome_calls(
+  window: tauri::Window,
+  launcher_state: tauri::State<'_, LauncherState>,
+  window_registry: tauri::State<'_, WindowRegistryState>,
+  audit_log: tauri::State<'_, ZomeCallAuditLog>,
+  zome_call_signer: tauri::State<'_, ZomeCallSigner>,
+  zome_calls_unsigned: Vec<ZomeCallUnsignedTauri>,
+) -> Result<Vec<Result<ZomeCall, String>>, String> {
+  let window_label = window.label();
+
+  let results = authorize_and_sign(&window, &launcher_state, &window_registry, &zome_call_signer, zome_calls_unsigned.clone()).await;
+  record_zome_calls(&audit_log, &window_registry, window_label, &zome_calls_unsigned, &results).await;
+
+  Ok(results.into_iter().map(|result| result.map_err(String::from)).collect())
+}
+
+async fn authorize_and_sign(
+  window: &tauri::Window,
+  launcher_state: &LauncherState,
+  window_registry: &WindowRegistryState,
+  zome_call_signer: &ZomeCallSigner,
+  mut zome_calls_unsigned: Vec<ZomeCallUnsignedTauri>,
+) -> Vec<Result<ZomeCall, SigningFailure>> {
+  let window_label = window.label();
+
+  // zome calls exceeding the rate limit of the window are neither authorized nor signed
+  let quota = zome_call_signer.acquire(window_label, zome_calls_unsigned.len());
+  if quota.newly_throttled {
+    report_throttled_window(window, window_registry).await;
   }
+  let throttled_count = zome_calls_unsigned.len() - quota.allowed;
+  zome_calls_unsigned.truncate(quota.allowed);
+
+  let authorizations =
+    authorize_zome_calls(launcher_state, window_registry, zome_call_signer, window_label, &zome_calls_unsigned).await;
 
-  let zome_call_unsigned_converted: ZomeCallUnsigned = zome_call_unsigned.into();
+  // only the authorized zome calls get signed
+  let authorized_zome_calls: Vec<ZomeCallUnsigned> = authorizations
+    .iter()
+    .zip(zome_calls_unsigned)
+    .filter(|(authorization, _)| authorization.is_ok())
+    .map(|(_, zome_call_unsigned)| zome_call_unsigned.into())
+    .collect();
+  let authorized_count = authorized_zome_calls.len();
 
-  let mut mutex = (*launcher_state).lock().await;
-  let manager = mutex.get_running()?;
+  let mut signed_zome_calls = match sign_authorized_zome_calls(zome_call_signer, authorized_zome_calls).await {
+    Ok(signed_zome_calls) => signed_zome_calls.into_iter(),
+    Err(e) => vec![Err(e); authorized_count].into_iter(),
+  };
 
-  let lair_keystore_manager = manager.get_lair_keystore_manager()?;
-  let signed_zome_call = lair_keystore_manager.sign_zome_call(zome_call_unsigned_converted)
+  authorizations
+    .into_iter()
+    .map(|authorization| match authorization {
+      Ok(()) => signed_zome_calls
+        .next()
+        .unwrap_or(Err(String::from("Signing zome call failed: missing signing result")))
+        .map_err(SigningFailure::Rejected),
+      Err(e) => Err(SigningFailure::Rejected(e.into())),
+    })
+    .chain(std::iter::repeat(Err(SigningFailure::Throttled)).take(throttled_count))
+    .collect()
+}
+
+/// Notifies the admin window that the window started exceeding its zome call rate limit
+pub(crate) async fn report_throttled_window(window: &tauri::Window, window_registry: &WindowRegistryState) {
+  let app_id = window_registry
+    .lock()
     .await
-    .map_err(|e| format!("Signing zome call failed: {}", e))?;
+    .get(window.label())
+    .map(|identity| identity.app_id.clone());
+
+  log::warn!("[ZOME CALL SIGNING] WARNING: Window '{}' exceeded its zome call rate limit and gets throttled.", window.label());
 
-  Ok(signed_zome_call)
+  let payload = ZomeCallThrottledPayload {
+    window_label: window.label().to_string(),
+    app_id,
+  };
+  if let Err(e) = window.emit_to("admin", ZOME_CALL_THROTTLED_EVENT, payload) {
+    log::error!("Failed to notify the admin window about a throttled window: {}", e);
+  }
 }
 
+async fn sign_authorized_zome_calls(
+  zome_call_signer: &ZomeCallSigner,
+  zome_calls_unsigned: Vec<ZomeCallUnsigned>,
+) -> Result<Vec<Result<ZomeCall, String>>, String> {
+  if zome_calls_unsigned.is_empty() {
+    return Ok(vec![]);
+  }
+
+  zome_call_signer.sign_zome_calls(zome_calls_unsigned).await
+}
+
+async fn record_zome_calls(
+  audit_log: &ZomeCallAuditLog,
+  window_registry: &WindowRegistryState,
+  window_label: &str,
+  zome_calls_unsigned: &Vec<ZomeCallUnsignedTauri>,
+  results: &Vec<Result<ZomeCall, SigningFailure>>,
+) {
+  if !audit_log.is_enabled() {
+    return;
+  }
+
+  let app_id = window_registry
+    .lock()
+    .await
+    .get(window_label)
+    .map(|identity| identity.app_id.clone());
+
+  for (zome_call_unsigned, result) in zome_calls_unsigned.iter().zip(results) {
+    let outcome = match result {
+      Ok(_) => ZomeCallOutcome::Signed,
+      Err(SigningFailure::Throttled) => ZomeCallOutcome::Throttled,
+      Err(SigningFailure::Rejected(e)) => ZomeCallOutcome::Rejected(e.clone()),
+    };
+    audit_log.record(ZomeCallAuditEntry::zome_call(window_label, app_id.clone(), zome_call_unsigned, outcome));
+  }
+}
+
+/// Authorizes all zome calls against the identity of the window with a single lookup in the window registry
+async fn authorize_zome_calls(
+  launcher_state: &LauncherState,
+  window_registry: &WindowRegistryState,
+  zome_call_signer: &ZomeCallSigner,
+  window_label: &str,
+  zome_calls_unsigned: &Vec<ZomeCallUnsignedTauri>,
+) -> Vec<Result<(), ZomeCallAuthorizationError>> {
+  // validate that the agent public key and the cell of the zome calls are ones associated to the UI that's making the calls.
+  // NOTE: The window_registry lock gets released before the launcher_state lock is acquired. Otherwise, it can lead to
+  // a deadlock when calling install_app() around the same time (https://github.com/holochain/launcher/issues/182)
+  let authorizations = check_zome_calls(window_registry, window_label, zome_calls_unsigned).await;
+
+  // the cells may be clone cells the app created since the window registry got updated. Clone
+  // cells have a DNA hash of their own, but all cells of the app belong to the app's agent key,
+  // which the provenance has been checked against.
+  let has_unknown_app_cells = authorizations
+    .iter()
+    .zip(zome_calls_unsigned)
+    .any(|(authorization, zome_call_unsigned)| {
+      matches!(authorization, Err(ZomeCallAuthorizationError::CellNotAuthorized))
+        && zome_call_unsigned.cell_id.agent_pubkey() == &zome_call_unsigned.provenance
+    });
+  if !has_unknown_app_cells || !zome_call_signer.try_refresh_cells(window_label) {
+    return authorizations;
+  }
+
+  if let Err(e) = refresh_window_cells(launcher_state, window_registry, window_label).await {
+    log::warn!("Failed to refresh the cells of window '{}': {}", window_label, e);
+    return authorizations;
+  }
+
+  check_zome_calls(window_registry, window_label, zome_calls_unsigned).await
+}
+
+async fn refresh_window_cells(
+  launcher_state: &LauncherState,
+  window_registry: &WindowRegistryState,
+  window_label: &str,
+) -> Result<(), String> {
+  let maybe_holochain_version = window_registry
+    .lock()
+    .await
+    .get(window_label)
+    .map(|identity| identity.holochain_version);
+
+  let holochain_version = match maybe_holochain_version {
+    Some(holochain_version) => holochain_version,
+    None => return Ok(()),
+  };
+
+  let web_app_manager = (*launcher_state)
+    .lock()
+    .await
+    .get_running()?
+    .get_web_happ_manager(HolochainId::from(holochain_version))?;
+
+  web_app_manager.lock().await.refresh_installed_apps().await?;
+
+  Ok(())
+}
+
+async fn check_zome_calls(
+  window_registry: &WindowRegistryState,
+  window_label: &str,
+  zome_calls_unsigned: &Vec<ZomeCallUnsignedTauri>,
+) -> Vec<Result<(), ZomeCallAuthorizationError>> {
+  let window_registry = window_registry.lock().await;
+
+  zome_calls_unsigned
+    .iter()
+    .map(|zome_call_unsigned| {
+      window_registry
+        .authorize_zome_call(
+          window_label,
+          &zome_call_unsigned.provenance,
+          &zome_call_unsigned.cell_id,
+          &zome_call_unsigned.zome_name.to_string(),
+          &zome_call_unsigned.fn_name.to_string(),
+        )
+        .map_err(|e| {
+          log::warn!("[ZOME CALL SIGNING] WARNING: A tauri window attempted to make a zome call it is not authorized to make: {} Window label: '{}'", e, window_label);
+          e
+        })
+    })
+    .collect()
+}
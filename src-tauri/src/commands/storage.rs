@@ -1,4 +1,4 @@
-use holochain_web_app_manager::StorageInfo;
+use holochain_web_app_manager::{app_storage::{AppStorageInfo, StorageReport}, StorageInfo};
 
 use crate::launcher::{state::LauncherState, manager::HolochainId};
 
@@ -11,12 +11,38 @@ pub async fn get_storage_info(
   if window.label() != "admin" {
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (H)"))
   }
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
 
-  let storage_info = manager
-    .get_web_happ_manager(holochain_id)?
-    .get_storage_info()?;
+  let storage_report = compute_storage_report(state, holochain_id).await?;
 
-  Ok(storage_info)
+  Ok(storage_report.totals)
+}
+
+/// Storage used by each installed app, broken down by UIs and cells
+#[tauri::command]
+pub async fn get_app_storage_info(
+  window: tauri::Window,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+) -> Result<Vec<AppStorageInfo>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'get_app_storage_info' which is not allowed in this window."))
+  }
+
+  let storage_report = compute_storage_report(state, holochain_id).await?;
+
+  Ok(storage_report.apps)
+}
+
+/// Only holds the WebAppManager while listing the apps, the directories are walked on a blocking thread
+async fn compute_storage_report(
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+) -> Result<StorageReport, String> {
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id)?;
+
+  let storage_scan = web_app_manager.lock().await.storage_scan().await?;
+
+  tauri::async_runtime::spawn_blocking(move || storage_scan.compute())
+    .await
+    .map_err(|e| format!("Failed to calculate storage info: {}", e))?
 }
@@ -1,12 +1,14 @@
-use crate::launcher::{state::LauncherState, manager::HolochainId};
-use tauri::Manager;
-use holochain_web_app_manager::derive_window_label;
+use crate::{
+  file_system::Profile,
+  launcher::{manager::HolochainId, signing_consents::SigningConsentsState, state::LauncherState},
+};
 
 #[tauri::command]
 pub async fn uninstall_app(
-  app_handle: tauri::AppHandle,
   window: tauri::Window,
   state: tauri::State<'_, LauncherState>,
+  signing_consents: tauri::State<'_, SigningConsentsState>,
+  profile: tauri::State<'_, Profile>,
   app_id: String,
   holochain_id: HolochainId,
 ) -> Result<(), String> {
@@ -14,21 +16,19 @@ pub async fn uninstall_app(
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (P)"))
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let web_app_manager = (*state).lock().await.get_running()?.get_web_happ_manager(holochain_id.clone())?;
 
-  manager
-    .get_web_happ_manager(holochain_id.clone())?
+  web_app_manager
+    .lock()
+    .await
     .uninstall_app(app_id.clone())
     .await?;
 
-  manager.on_apps_changed().await?;
-
-  // close existing window belonging to that app if there is one
-  let window_label = derive_window_label(&app_id);
-  if let Some(w) = app_handle.get_window(window_label.as_str()) {
-    w.close().map_err(|e| format!("Failed to close app window after uninstalling app: {:?}", e))?;
-  }
+  // an app installed later under the same id must ask for consent again
+  signing_consents
+    .lock()
+    .await
+    .remove_app(&holochain_id, &app_id, profile.inner().clone())?;
 
   Ok(())
 }
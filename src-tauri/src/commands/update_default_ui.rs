@@ -1,10 +1,10 @@
-use crate::{launcher::{state::LauncherState, manager::HolochainId}, file_system::Profile, commands::save_app::{portal_remote_call, fetch_mere_memory}};
+use crate::{launcher::{state::LauncherState, manager::HolochainId}, commands::save_app::{portal_remote_call, fetch_mere_memory}};
 use devhub_types::{happ_entry_types::GUIReleaseEntry, Entity, GetEntityInput, FileEntry};
 use hdk::prelude::{AnyDhtHash, ActionHash};
 use holochain::conductor::api::CellInfo;
 use holochain_client::{AgentPubKey, AppWebsocket, AppInfo};
 use holochain_types::prelude::{DnaHash, AgentPubKeyB64};
-use holochain_web_app_manager::ReleaseInfo;
+use holochain_web_app_manager::{ReleaseInfo, DEFAULT_UI_NAME};
 use mr_bundle::ResourceBytes;
 
 
@@ -13,7 +13,6 @@ use mr_bundle::ResourceBytes;
 pub async fn fetch_and_update_default_gui(
   window: tauri::Window,
   state: tauri::State<'_, LauncherState>,
-  profile: tauri::State<'_, Profile>,
   app_port: u16,
   appstore_app_id: String,
   appstore_pub_key: String,
@@ -59,13 +58,16 @@ pub async fn fetch_and_update_default_gui(
       return Err(format!("No provisioned cell for role portal_api found."));
   };
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
-  let lair_keystore_manager = manager.get_lair_keystore_manager()?;
+  let lair_keystore_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_lair_keystore_manager()?
+    .clone();
 
   let gui_release_entry_entity: Entity<GUIReleaseEntry> = portal_remote_call(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -80,7 +82,7 @@ pub async fn fetch_and_update_default_gui(
 
   let web_asset_file: Entity<FileEntry> = portal_remote_call(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -94,7 +96,7 @@ pub async fn fetch_and_update_default_gui(
 
   let ui_bytes = fetch_mere_memory(
     &mut ws,
-    lair_keystore_manager,
+    &lair_keystore_manager,
     &agent_pub_key,
     host.clone(),
     portal_cell,
@@ -103,15 +105,20 @@ pub async fn fetch_and_update_default_gui(
     web_asset_file.content.mere_memory_addr
   ).await?;
 
-  let default_ui_name = String::from("default");
+  let default_ui_name = String::from(DEFAULT_UI_NAME);
 
   log::info!("Installing: New UI for app '{}'", &app_id);
 
-  manager
-    .get_or_launch_holochain(
-      holochain_id,
-      profile.inner().clone(),
-    ).await?
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+
+  web_app_manager
+    .lock()
+    .await
     .update_app_ui(
       app_id.clone(),
       ResourceBytes::from(ui_bytes),
@@ -121,8 +128,6 @@ pub async fn fetch_and_update_default_gui(
 
   log::info!("Installed new UI for app '{}'", app_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
 
@@ -134,7 +139,6 @@ pub async fn fetch_and_update_default_gui(
 pub async fn update_default_ui(
   window: tauri::Window,
   state: tauri::State<'_, LauncherState>,
-  profile: tauri::State<'_, Profile>,
   holochain_id: HolochainId,
   app_id: String,
   ui_zip_bytes: Vec<u8>,
@@ -144,21 +148,23 @@ pub async fn update_default_ui(
     return Err(String::from("Unauthorized: Attempted to call an unauthorized tauri command. (Q)"))
   }
 
-  let default_ui_name = String::from("default");
+  let default_ui_name = String::from(DEFAULT_UI_NAME);
 
   log::info!("Installing: New UI for app '{}'", &app_id);
   if gui_release_info.is_none() {
     log::warn!("WARNING: No GUI release hash passed to update_ui command. Automatically checking for updates will not work for this UI.");
   }
 
-  let mut mutex = (*state).lock().await;
-  let manager = mutex.get_running()?;
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
 
-  manager
-    .get_or_launch_holochain(
-      holochain_id,
-      profile.inner().clone(),
-    ).await?
+  web_app_manager
+    .lock()
+    .await
     .update_app_ui(
       app_id.clone(),
       ResourceBytes::from(ui_zip_bytes),
@@ -168,7 +174,5 @@ pub async fn update_default_ui(
 
   log::info!("Installed new UI for app '{}'", app_id);
 
-  manager.on_apps_changed().await?;
-
   Ok(())
 }
@@ -0,0 +1,119 @@
+use holochain_manager::versions::holochain_types_latest::{
+  prelude::{AppBundle, MembraneProof, SerializedBytes, UnsafeBytes},
+  web_app::WebAppBundle,
+};
+use holochain_web_app_manager::{window_registry::derive_window_label, AppUpgrade, ReleaseInfo};
+use std::{collections::HashMap, fs, sync::Arc};
+use tauri::Manager;
+
+use crate::{launcher::{state::LauncherState, manager::HolochainId}};
+
+/// Upgrades an installed app to the hApp in the given .happ or .webhapp file. Only the hApp part of a
+/// .webhapp is used, UIs are updated separately. The membrane proofs are used if the integrity zomes
+/// changed and the new release gets installed as a separate app. Progress is emitted as
+/// "app-upgrade-progress" events.
+#[tauri::command]
+pub async fn upgrade_app(
+  window: tauri::Window,
+  app_handle: tauri::AppHandle,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+  app_bundle_path: String,
+  membrane_proofs: HashMap<String, Vec<u8>>,
+  happ_release_info: Option<ReleaseInfo>,
+) -> Result<AppUpgrade, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'upgrade_app' which is not allowed in this window."))
+  }
+
+  let holochain_version = holochain_id.version();
+
+  log::info!("Upgrading app '{}' with bundle {}", app_id, app_bundle_path);
+
+  let bytes = fs::read(&app_bundle_path).or(Err("Failed to read hApp bundle file"))?;
+  let app_bundle = match WebAppBundle::decode(&bytes) {
+    Ok(web_app_bundle) => web_app_bundle
+      .happ_bundle()
+      .await
+      .or(Err("Failed to resolve hApp bundle"))?,
+    Err(_) => AppBundle::decode(&bytes).or(Err("Failed to decode hApp bundle"))?,
+  };
+
+  let mut converted_membrane_proofs: HashMap<String, MembraneProof> = HashMap::new();
+  for (dna_slot, proof) in membrane_proofs.iter() {
+    converted_membrane_proofs.insert(
+      dna_slot.clone(),
+      Arc::new(SerializedBytes::from(UnsafeBytes::from(proof.clone()))),
+    );
+  }
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+  let mut web_app_manager = web_app_manager.lock().await;
+
+  let upgrade = web_app_manager
+    .upgrade_app(app_id.clone(), app_bundle, converted_membrane_proofs, happ_release_info)
+    .await?;
+
+  // windows of a migrated app belong to the disabled previous release and get closed,
+  // windows of an app updated in place get reloaded
+  for ui_name in web_app_manager.list_app_ui_names(&app_id) {
+    if let Some(w) = app_handle.get_window(derive_window_label(&holochain_version, &app_id, &ui_name).as_str()) {
+      let result = match upgrade {
+        AppUpgrade::CoordinatorsUpdated => w.eval("window.location.reload()"),
+        AppUpgrade::Migrated { .. } => w.close(),
+      };
+      if let Err(err) = result {
+        log::error!("Error updating window after upgrade {:?}", err);
+      }
+    }
+  }
+
+  log::info!("Upgraded app '{}': {:?}", app_id, upgrade);
+
+  Ok(upgrade)
+}
+
+/// Reverts the last upgrade of an app. For migrated apps, app_id is the id of the new release.
+#[tauri::command]
+pub async fn rollback_app_upgrade(
+  window: tauri::Window,
+  app_handle: tauri::AppHandle,
+  state: tauri::State<'_, LauncherState>,
+  holochain_id: HolochainId,
+  app_id: String,
+) -> Result<(), String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'rollback_app_upgrade' which is not allowed in this window."))
+  }
+
+  let holochain_version = holochain_id.version();
+
+  let web_app_manager = (*state)
+    .lock()
+    .await
+    .get_running()?
+    .get_or_launch_holochain(holochain_id)
+    .await?;
+  let mut web_app_manager = web_app_manager.lock().await;
+
+  // windows of the rolled back release won't be valid anymore
+  for ui_name in web_app_manager.list_app_ui_names(&app_id) {
+    if let Some(w) = app_handle.get_window(derive_window_label(&holochain_version, &app_id, &ui_name).as_str()) {
+      if let Err(err) = w.close() {
+        log::error!("Error closing window {:?}", err);
+      }
+    }
+  }
+
+  web_app_manager.rollback_app_upgrade(app_id.clone()).await?;
+
+  log::info!("Rolled back the last upgrade of app '{}'", app_id);
+
+  Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::launcher::{
+  zome_call_audit_log::{ZomeCallAuditEntry, ZomeCallAuditLog, ZomeCallAuditQuery},
+  zome_call_signer::{ThrottledWindow, ZomeCallSigner},
+};
+use holochain_web_app_manager::window_registry::WindowRegistryState;
+
+/// Reads the recorded signing requests matching the query, newest first
+#[tauri::command]
+pub async fn get_zome_call_audit_log(
+  window: tauri::Window,
+  audit_log: tauri::State<'_, ZomeCallAuditLog>,
+  query: ZomeCallAuditQuery,
+) -> Result<Vec<ZomeCallAuditEntry>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'get_zome_call_audit_log' which is not allowed in this window."))
+  }
+
+  if !audit_log.is_enabled() {
+    return Err(String::from("The zome call audit log is disabled. Enable it by setting 'zome_call_audit_log' in the launcher config."));
+  }
+
+  audit_log.query(&query)
+}
+
+/// Lists the open windows that exceeded their zome call rate limit at least once since they got opened
+#[tauri::command]
+pub async fn get_throttled_windows(
+  window: tauri::Window,
+  zome_call_signer: tauri::State<'_, ZomeCallSigner>,
+  window_registry: tauri::State<'_, WindowRegistryState>,
+) -> Result<Vec<ThrottledWindow>, String> {
+  if window.label() != "admin" {
+    return Err(String::from("Unauthorized: Attempted to call tauri command 'get_throttled_windows' which is not allowed in this window."))
+  }
+
+  let window_registry = window_registry.lock().await;
+  let throttled_windows = zome_call_signer
+    .throttled_windows()
+    .into_iter()
+    .map(|throttled_window| ThrottledWindow {
+      app_id: window_registry
+        .get(&throttled_window.window_label)
+        .map(|identity| identity.app_id.clone()),
+      ..throttled_window
+    })
+    .collect();
+
+  Ok(throttled_windows)
+}
@@ -83,6 +83,29 @@ pub fn launcher_config_path(profile: String) -> Result<PathBuf, LauncherError> {
 }
 
 
+/// Path to `identities.yaml` containing the named agent keys of a profile.
+///
+/// * **Linux:** `$XDG_CONFIG_HOME/${APP_NAME}/profiles/${profile}/launcher/identities.yaml` or `$HOME/.config/${APP_NAME}/profiles/${profile}/launcher/identities.yaml`
+/// * **macOS:** `$HOME/Library/Application Support/${APP_NAME}/profiles/${profile}/config/launcher/identities.yaml`
+/// * **Windows:** `{FOLDERID_RoamingAppData}/${APP_NAME}/profiles/${profile}/config/launcher/identities.yaml`
+///
+pub fn identities_path(profile: String) -> Result<PathBuf, LauncherError> {
+  Ok(launcher_config_dir(profile)?.join("identities.yaml"))
+}
+
+
+/// Path to `signing-consents.yaml` containing the apps that are allowed to sign arbitrary data
+/// without asking the user again.
+///
+/// * **Linux:** `$XDG_CONFIG_HOME/${APP_NAME}/profiles/${profile}/launcher/signing-consents.yaml` or `$HOME/.config/${APP_NAME}/profiles/${profile}/launcher/signing-consents.yaml`
+/// * **macOS:** `$HOME/Library/Application Support/${APP_NAME}/profiles/${profile}/config/launcher/signing-consents.yaml`
+/// * **Windows:** `{FOLDERID_RoamingAppData}/${APP_NAME}/profiles/${profile}/config/launcher/signing-consents.yaml`
+///
+pub fn signing_consents_path(profile: String) -> Result<PathBuf, LauncherError> {
+  Ok(launcher_config_dir(profile)?.join("signing-consents.yaml"))
+}
+
+
 
 
 /** Logs */
@@ -100,6 +123,12 @@ pub fn profile_logs_path(profile: String) -> Result<PathBuf, LauncherError> {
   Ok(profile_logs_dir(profile)?.join("launcher.log"))
 }
 
+/// Path to the audit log of signed zome calls of a given profile, next to the log file.
+/// Rotated files are named `zome-calls.1.jsonl`, `zome-calls.2.jsonl`, etc.
+pub fn zome_call_audit_log_path(profile: String) -> Result<PathBuf, LauncherError> {
+  Ok(profile_logs_dir(profile)?.join("zome-calls.jsonl"))
+}
+
 /// Directory containing logs of a given profile
 ///
 /// **Note:** Log dir is not part of config dir on Linux, such that it can be saved
@@ -233,3 +262,17 @@ pub fn keystore_data_dir(lair_keystore_version: LairKeystoreVersion, profile: St
   Ok(profile_lair_dir(profile)?.join(version))
 }
 
+
+
+/** Writing */
+
+/// Writes to a temporary file next to the given path first and then renames it, so that a crash
+/// while writing can't leave a corrupted file behind
+pub fn write_atomically(path: &PathBuf, contents: String) -> std::io::Result<()> {
+  let mut temp_file_name = path.file_name().unwrap_or_default().to_os_string();
+  temp_file_name.push(".tmp");
+  let temp_path = path.with_file_name(temp_file_name);
+
+  std::fs::write(&temp_path, contents)?;
+  std::fs::rename(&temp_path, path)
+}
@@ -0,0 +1,175 @@
+use hdk::prelude::AgentPubKey;
+use lair_keystore_manager::LairKeystoreManager;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::file_system::Profile;
+
+use super::{
+  identities::{IdentitiesState, IdentityInfo},
+  manager::{HolochainId, WebAppManagerHandle},
+  state::{AgentKeyApp, AgentKeyInfo, AgentKeyInventory},
+};
+
+/// The running conductors and the keystore, taken from the LauncherManager so that operations on
+/// the agent keys of all conductors don't hold the LauncherState lock
+pub struct AgentKeys {
+  pub(crate) web_app_managers: Vec<(HolochainId, WebAppManagerHandle)>,
+  /// Conductors that are not running
+  pub(crate) unavailable: Vec<HolochainId>,
+  pub(crate) lair_keystore_manager: Arc<dyn LairKeystoreManager>,
+  pub(crate) profile: Profile,
+}
+
+/// The agent keys used by the installed apps of the conductors whose apps could be listed
+struct KeysInUse {
+  apps_by_key: HashMap<AgentPubKey, Vec<AgentKeyApp>>,
+  unavailable: Vec<HolochainId>,
+}
+
+impl AgentKeys {
+  /// Maps agent public keys to the installed apps using them across all running conductors
+  async fn keys_in_use(&self) -> KeysInUse {
+    let mut apps_by_key: HashMap<AgentPubKey, Vec<AgentKeyApp>> = HashMap::new();
+    let mut unavailable = self.unavailable.clone();
+
+    for (holochain_id, web_app_manager) in &self.web_app_managers {
+      let agent_keys_in_use = web_app_manager.lock().await.holochain_manager.agent_keys_in_use().await;
+      let agent_keys_in_use = match agent_keys_in_use {
+        Ok(agent_keys_in_use) => agent_keys_in_use,
+        Err(e) => {
+          log::warn!("Failed to list the agent keys used by the apps of {:?}: {}", holochain_id, e);
+          unavailable.push(holochain_id.clone());
+          continue;
+        }
+      };
+
+      for (agent_pub_key, app_ids) in agent_keys_in_use {
+        apps_by_key
+          .entry(agent_pub_key)
+          .or_default()
+          .extend(app_ids.into_iter().map(|app_id| AgentKeyApp {
+            holochain_id: holochain_id.clone(),
+            app_id,
+          }));
+      }
+    }
+
+    KeysInUse { apps_by_key, unavailable }
+  }
+
+  /// Lists the agent keys stored in lair-keystore together with the apps using them. The apps of
+  /// conductors that are not running are missing, which the inventory indicates.
+  pub async fn inventory(&self) -> Result<AgentKeyInventory, String> {
+    let KeysInUse { mut apps_by_key, unavailable } = self.keys_in_use().await;
+
+    let agent_keys = self
+      .lair_keystore_manager
+      .list_agent_keys()
+      .await
+      .map_err(|e| format!("Failed to list agent keys: {}", e))?;
+
+    Ok(AgentKeyInventory {
+      agent_keys: agent_keys
+        .into_iter()
+        .map(|entry| AgentKeyInfo {
+          installed_apps: apps_by_key.remove(&entry.agent_pub_key).unwrap_or_default(),
+          agent_pub_key: entry.agent_pub_key,
+          tag: entry.tag,
+          label: entry.label,
+        })
+        .collect(),
+      unavailable_holochains: unavailable,
+    })
+  }
+
+  pub fn set_label(&self, agent_pub_key: AgentPubKey, label: Option<String>) -> Result<(), String> {
+    self
+      .lair_keystore_manager
+      .set_agent_key_label(agent_pub_key, label)
+      .map_err(|e| format!("Failed to set agent key label: {}", e))
+  }
+
+  /// Hides an agent key from the inventory if no installed app uses it anymore. The key itself
+  /// stays in lair-keystore.
+  pub async fn hide_unused(&self, agent_pub_key: AgentPubKey) -> Result<(), String> {
+    let keys_in_use = self.keys_in_use().await;
+    if let Some(apps) = keys_in_use.apps_by_key.get(&agent_pub_key) {
+      let app_ids: Vec<String> = apps.iter().map(|app| app.app_id.clone()).collect();
+      return Err(format!(
+        "The agent key is still used by the following apps: {}",
+        app_ids.join(", ")
+      ));
+    }
+    if !keys_in_use.unavailable.is_empty() {
+      return Err(String::from(
+        "The agent key can't be hidden while not all Holochain versions are running, since their apps may still use it.",
+      ));
+    }
+
+    self
+      .lair_keystore_manager
+      .hide_agent_key(agent_pub_key)
+      .map_err(|e| format!("Failed to hide agent key: {}", e))
+  }
+
+  /// Lists the named identities of this profile together with the apps using them
+  pub async fn list_identities(&self, identities: &IdentitiesState) -> Result<Vec<IdentityInfo>, String> {
+    let identities = identities.lock().await.identities.clone();
+    let keys_in_use = self.keys_in_use().await.apps_by_key;
+
+    Ok(
+      identities
+        .into_iter()
+        .map(|identity| {
+          let agent_pub_key = AgentPubKey::from(identity.agent_pub_key);
+          IdentityInfo {
+            name: identity.name,
+            installed_apps: keys_in_use
+              .get(&agent_pub_key)
+              .map(|apps| apps.clone())
+              .unwrap_or_default(),
+            agent_pub_key,
+          }
+        })
+        .collect(),
+    )
+  }
+
+  /// Creates a named identity, either for a newly generated agent key or for an existing one. The
+  /// identities stay locked meanwhile, such that concurrent changes can't take the same name.
+  pub async fn create_identity(
+    &self,
+    identities: &IdentitiesState,
+    name: String,
+    agent_pub_key: Option<AgentPubKey>,
+  ) -> Result<AgentPubKey, String> {
+    let mut identities = identities.lock().await;
+    if identities.get(&name).is_some() {
+      return Err(format!("An identity with the name '{}' already exists", name));
+    }
+
+    // agent keys live in the shared lair-keystore, which all conductors sign with
+    let agent_pub_key = match agent_pub_key {
+      Some(agent_pub_key) => {
+        let is_stored = self
+          .lair_keystore_manager
+          .contains_agent_key(&agent_pub_key)
+          .await
+          .map_err(|e| format!("Failed to look up agent key: {}", e))?;
+        if !is_stored {
+          return Err(format!("The agent key {} is not stored in lair-keystore", agent_pub_key));
+        }
+        agent_pub_key
+      }
+      None => self
+        .lair_keystore_manager
+        .generate_agent_key()
+        .await
+        .map_err(|e| format!("Failed to generate agent key: {}", e))?,
+    };
+
+    identities.add(name, agent_pub_key.clone(), self.profile.clone())?;
+
+    Ok(agent_pub_key)
+  }
+}
@@ -0,0 +1,115 @@
+use futures::{
+  channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+  StreamExt,
+};
+use std::{collections::HashMap, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::system_tray::{update_system_tray, AllInstalledApps};
+
+use super::{manager::HolochainId, state::LauncherState};
+
+/// How often the installed apps get fetched from the conductors again, to pick up changes made by
+/// other admin clients of the conductors
+const PERIODIC_REFETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq)]
+enum RefreshRequest {
+  /// Refresh from the installed apps cached by the WebAppManagers
+  Cached,
+  /// Fetch the installed apps from the conductors first
+  Refetch,
+}
+
+/// Updates the system tray and closes the windows of stopped apps whenever the apps of a conductor
+/// changed, and periodically after fetching the installed apps again. Refreshes requested while
+/// one is running get coalesced into a single one.
+#[derive(Clone)]
+pub struct AppsRefresher(UnboundedSender<RefreshRequest>);
+
+impl AppsRefresher {
+  pub fn spawn(app_handle: AppHandle) -> Self {
+    let (sender, receiver) = unbounded();
+    tauri::async_runtime::spawn(refresh_apps_on_request(app_handle, receiver));
+    spawn_periodic_refetch(sender.clone());
+    AppsRefresher(sender)
+  }
+
+  pub fn request(&self) {
+    if let Err(e) = self.0.unbounded_send(RefreshRequest::Cached) {
+      log::error!("Failed to request a refresh of the apps: {:?}", e);
+    }
+  }
+}
+
+fn spawn_periodic_refetch(sender: UnboundedSender<RefreshRequest>) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(PERIODIC_REFETCH_INTERVAL);
+
+    // the refresher went away
+    if sender.unbounded_send(RefreshRequest::Refetch).is_err() {
+      return;
+    }
+  });
+}
+
+async fn refresh_apps_on_request(
+  app_handle: AppHandle,
+  mut requests: UnboundedReceiver<RefreshRequest>,
+) {
+  while let Some(mut request) = requests.next().await {
+    // drop the requests that came in in the meantime, they're covered by this refresh
+    while let Ok(Some(pending)) = requests.try_next() {
+      if pending == RefreshRequest::Refetch {
+        request = RefreshRequest::Refetch;
+      }
+    }
+
+    if let Err(e) = refresh_apps(&app_handle, request == RefreshRequest::Refetch).await {
+      log::error!("Couldn't refresh apps: {:?}", e);
+    }
+  }
+}
+
+/// Lists the apps of all running conductors, fetching them from the conductors again if `refetch`
+/// is set. The LauncherState lock is only held to look up the WebAppManagers and to close windows,
+/// never while waiting for a WebAppManager.
+async fn refresh_apps(app_handle: &AppHandle, refetch: bool) -> Result<(), String> {
+  let launcher_state = match app_handle.try_state::<LauncherState>() {
+    Some(launcher_state) => launcher_state,
+    None => return Ok(()),
+  };
+
+  let web_app_managers = launcher_state.lock().await.get_running()?.web_happ_managers();
+
+  let mut all_installed_apps = AllInstalledApps {
+    by_version: HashMap::new(),
+    custom_binary: None,
+  };
+
+  for (holochain_id, web_app_manager) in web_app_managers {
+    let mut web_app_manager = web_app_manager.lock().await;
+    if refetch {
+      web_app_manager.refresh_installed_apps().await?;
+    }
+    let running_apps = web_app_manager.list_apps().await?;
+    drop(web_app_manager);
+
+    match holochain_id {
+      HolochainId::HolochainVersion(version) => {
+        all_installed_apps.by_version.insert(version, running_apps);
+      }
+      HolochainId::CustomBinary => all_installed_apps.custom_binary = Some(running_apps),
+    }
+  }
+
+  update_system_tray(app_handle, &all_installed_apps);
+
+  launcher_state
+    .lock()
+    .await
+    .get_running()?
+    .close_windows_of_stopped_apps(&all_installed_apps);
+
+  Ok(())
+}
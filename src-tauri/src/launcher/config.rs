@@ -18,6 +18,67 @@ pub struct LauncherConfig {
 
   pub running_versions: HashSet<HolochainVersion>,
   pub profile: Profile,
+  /// Minutes of inactivity after which the keystore gets locked. Never locks if not set.
+  #[serde(default)]
+  pub idle_lock_minutes: Option<u64>,
+  /// Number of previous revisions kept per app UI to be able to roll back UI updates
+  #[serde(default = "default_ui_revisions_to_keep")]
+  pub ui_revisions_to_keep: usize,
+  /// Records every zome call and data signing request if set. Disabled by default.
+  #[serde(default)]
+  pub zome_call_audit_log: Option<ZomeCallAuditLogConfig>,
+  /// Limits how many zome calls each window may get signed
+  #[serde(default)]
+  pub zome_call_rate_limit: ZomeCallRateLimitConfig,
+}
+
+fn default_ui_revisions_to_keep() -> usize {
+  1
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZomeCallAuditLogConfig {
+  /// Size in bytes after which the audit log file gets rotated
+  #[serde(default = "default_audit_log_max_file_size")]
+  pub max_file_size: u64,
+  /// Number of rotated audit log files to keep
+  #[serde(default = "default_audit_log_max_rotated_files")]
+  pub max_rotated_files: usize,
+}
+
+fn default_audit_log_max_file_size() -> u64 {
+  10 * 1024 * 1024
+}
+
+fn default_audit_log_max_rotated_files() -> usize {
+  5
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZomeCallRateLimitConfig {
+  /// Zome calls per second a window may get signed in the long run
+  #[serde(default = "default_rate_limit_calls_per_second")]
+  pub calls_per_second: f64,
+  /// Zome calls a window may get signed at once before the rate limit kicks in
+  #[serde(default = "default_rate_limit_burst")]
+  pub burst: u32,
+}
+
+fn default_rate_limit_calls_per_second() -> f64 {
+  50.0
+}
+
+fn default_rate_limit_burst() -> u32 {
+  200
+}
+
+impl Default for ZomeCallRateLimitConfig {
+  fn default() -> Self {
+    ZomeCallRateLimitConfig {
+      calls_per_second: default_rate_limit_calls_per_second(),
+      burst: default_rate_limit_burst(),
+    }
+  }
 }
 
 impl Default for LauncherConfig {
@@ -29,6 +90,10 @@ impl Default for LauncherConfig {
       profile: String::from("default"),
       bootstrap_server_url: Some(bootstrap_service().to_string()),
       signaling_server_url: Some(signaling_server()),
+      idle_lock_minutes: None,
+      ui_revisions_to_keep: default_ui_revisions_to_keep(),
+      zome_call_audit_log: None,
+      zome_call_rate_limit: ZomeCallRateLimitConfig::default(),
     }
   }
 }
@@ -44,6 +109,10 @@ impl LauncherConfig {
       profile: profile,
       bootstrap_server_url: Some(bootstrap_service().to_string()),
       signaling_server_url: Some(signaling_server()),
+      idle_lock_minutes: None,
+      ui_revisions_to_keep: default_ui_revisions_to_keep(),
+      zome_call_audit_log: None,
+      zome_call_rate_limit: ZomeCallRateLimitConfig::default(),
     }
   }
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use holochain_manager::versions::holochain_types_latest::web_app::WebAppBundle;
-use holochain_web_app_manager::{WebAppManager, ReleaseInfo};
+use holochain_web_app_manager::{WebAppManager, ReleaseInfo, DEFAULT_UI_NAME};
 // use holochain_manager::versions::version_manager::VersionManager;
 
 
@@ -11,7 +11,7 @@ use holochain_web_app_manager::{WebAppManager, ReleaseInfo};
 const APPSTORE_VERSION: &str = "834c3d3cc15d24fb6d598d64f76a69d844cb6e61"; // shasum
 pub const DEVHUB_VERSION: &str = "7455e3fab4a77fcd841d55b60c5bbd182b603ba6"; // shasum
 
-const APPSTORE_APP_ID: &str = "AppStore";
+pub const APPSTORE_APP_ID: &str = "AppStore";
 pub const DEVHUB_APP_ID: &str = "DevHub";
 
 
@@ -66,7 +66,7 @@ pub async fn install_default_apps_if_necessary(manager: &mut WebAppManager, wind
         version: Some(APPSTORE_VERSION.to_string()),
       };
 
-      let current_release_info = manager.get_gui_release_info(&APPSTORE_APP_ID.to_string(), &String::from("default"));
+      let current_release_info = manager.get_gui_release_info(&APPSTORE_APP_ID.to_string(), &String::from(DEFAULT_UI_NAME));
 
       let new_ui_available = match current_release_info {
         None => true,
@@ -84,7 +84,7 @@ pub async fn install_default_apps_if_necessary(manager: &mut WebAppManager, wind
         manager.install_app_ui(
           APPSTORE_APP_ID.to_string(),
           new_ui.into_owned(),
-          &String::from("default"),
+          &String::from(DEFAULT_UI_NAME),
           Some(new_release_info),
         )?;
 
@@ -107,7 +107,7 @@ pub async fn install_default_apps_if_necessary(manager: &mut WebAppManager, wind
       version: Some(DEVHUB_VERSION.to_string()),
     };
 
-    let current_release_info = manager.get_gui_release_info(&DEVHUB_APP_ID.to_string(), &String::from("default"));
+    let current_release_info = manager.get_gui_release_info(&DEVHUB_APP_ID.to_string(), &String::from(DEFAULT_UI_NAME));
 
     let new_ui_available = match current_release_info {
       None => true,
@@ -128,7 +128,7 @@ pub async fn install_default_apps_if_necessary(manager: &mut WebAppManager, wind
       manager.install_app_ui(
         DEVHUB_APP_ID.to_string(),
         new_ui.into_owned(),
-        &String::from("default"),
+        &String::from(DEFAULT_UI_NAME),
         Some(new_release_info),
       )?;
 
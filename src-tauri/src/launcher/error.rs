@@ -18,5 +18,8 @@ pub enum LauncherError {
   #[error("Unauthorized: `{0}`")]
   Unauthorized(String),
   #[error("Failed to get system directory: `{0}`")]
-  SystemDirError(String)
+  SystemDirError(String),
+  /// A conductor that is not running yet is needed, which can only be launched with the passphrase
+  #[error("Holochain {0} is not running yet. Enter your password to launch it, then try again.")]
+  PasswordNeededToLaunch(String),
 }
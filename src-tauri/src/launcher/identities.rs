@@ -0,0 +1,104 @@
+use std::fs;
+
+use futures::lock::Mutex;
+use hdk::prelude::{AgentPubKey, AgentPubKeyB64};
+use serde::{Deserialize, Serialize};
+
+use crate::file_system::{identities_path, write_atomically, Profile};
+
+use super::state::AgentKeyApp;
+
+/// Named identities of the profile, loaded once such that concurrent updates are serialized
+pub type IdentitiesState = Mutex<Identities>;
+
+/// An agent key the user gave a name to, to reuse it across app installs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Identity {
+  pub name: String,
+  pub agent_pub_key: AgentPubKeyB64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IdentityInfo {
+  pub name: String,
+  pub agent_pub_key: AgentPubKey,
+  /// Installed apps using this identity, across all running Holochain versions
+  pub installed_apps: Vec<AgentKeyApp>,
+}
+
+/// The named identities of a profile, stored in `identities.yaml`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Identities {
+  pub identities: Vec<Identity>,
+}
+
+impl Identities {
+  pub fn read(profile: Profile) -> Self {
+    let path = match identities_path(profile) {
+      Ok(path) => path,
+      Err(e) => {
+        log::error!("Failed to get identities path: {}", e);
+        return Identities::default();
+      }
+    };
+
+    match fs::read_to_string(path) {
+      Ok(str) => serde_yaml::from_str(str.as_str()).unwrap_or_else(|e| {
+        log::error!("Failed to parse identities: {}", e);
+        Identities::default()
+      }),
+      Err(_) => Identities::default(),
+    }
+  }
+
+  fn write(&self, profile: Profile) -> Result<(), String> {
+    let path = identities_path(profile).map_err(|e| format!("Failed to get identities path: {}", e))?;
+    let serialized =
+      serde_yaml::to_string(&self).map_err(|e| format!("Failed to serialize identities: {}", e))?;
+
+    write_atomically(&path, serialized).map_err(|e| format!("Failed to write identities: {}", e))
+  }
+
+  pub fn get(&self, name: &String) -> Option<&Identity> {
+    self.identities.iter().find(|identity| &identity.name == name)
+  }
+
+  /// Adds a new identity, making sure that neither its name nor its key are taken yet
+  pub fn add(&mut self, name: String, agent_pub_key: AgentPubKey, profile: Profile) -> Result<(), String> {
+    if self.get(&name).is_some() {
+      return Err(format!("An identity with the name '{}' already exists", name));
+    }
+    let agent_pub_key = AgentPubKeyB64::from(agent_pub_key);
+    if let Some(identity) = self.identities.iter().find(|i| i.agent_pub_key == agent_pub_key) {
+      return Err(format!("This public key is already used by the identity '{}'", identity.name));
+    }
+
+    self.identities.push(Identity { name, agent_pub_key });
+
+    self.write(profile)
+  }
+
+  pub fn rename(&mut self, name: &String, new_name: String, profile: Profile) -> Result<(), String> {
+    if self.get(&new_name).is_some() {
+      return Err(format!("An identity with the name '{}' already exists", new_name));
+    }
+    let identity = self
+      .identities
+      .iter_mut()
+      .find(|identity| &identity.name == name)
+      .ok_or(format!("There is no identity with the name '{}'", name))?;
+    identity.name = new_name;
+
+    self.write(profile)
+  }
+
+  /// Removes the name from the identity. The agent key itself stays in the keystore.
+  pub fn remove(&mut self, name: &String, profile: Profile) -> Result<(), String> {
+    let len = self.identities.len();
+    self.identities.retain(|identity| &identity.name != name);
+    match self.identities.len() < len {
+      true => self.write(profile),
+      false => Err(format!("There is no identity with the name '{}'", name)),
+    }
+  }
+}
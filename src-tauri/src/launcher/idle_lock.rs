@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use super::state::LauncherState;
+
+/// How often the idle time gets compared against the configured idle-lock timeout
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+static WATCH_IDLE_LOCK: Once = Once::new();
+
+/// Time of the last user interaction with any of the launcher's windows
+#[derive(Clone)]
+pub struct UserActivity(Arc<Mutex<Instant>>);
+
+impl UserActivity {
+  pub fn new() -> Self {
+    UserActivity(Arc::new(Mutex::new(Instant::now())))
+  }
+
+  pub fn touch(&self) {
+    *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+  }
+
+  pub fn idle_for(&self) -> Duration {
+    self.0.lock().unwrap_or_else(|e| e.into_inner()).elapsed()
+  }
+}
+
+/// Locks the keystore whenever the user has been inactive for longer than the given timeout.
+/// Only the first call spawns a watcher.
+pub fn watch_idle_lock(app_handle: Arc<AppHandle>, timeout: Duration) {
+  WATCH_IDLE_LOCK.call_once(|| spawn_idle_lock_watcher(app_handle, timeout));
+}
+
+fn spawn_idle_lock_watcher(app_handle: Arc<AppHandle>, timeout: Duration) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(IDLE_CHECK_INTERVAL);
+
+    if app_handle.state::<UserActivity>().idle_for() < timeout {
+      continue;
+    }
+
+    let launcher_state: &LauncherState = &app_handle.state();
+    tauri::async_runtime::block_on(async {
+      let mut mutex = launcher_state.lock().await;
+      if let Ok(manager) = mutex.get_running() {
+        if let Err(e) = manager.lock_keystore() {
+          log::error!("Failed to lock the keystore after inactivity: {}", e);
+        }
+      }
+    });
+  });
+}
@@ -1,32 +1,44 @@
 use holochain_launcher_utils::window_builder::{happ_window_builder, UISource};
 use holochain_manager::config::LaunchHolochainConfig;
 use holochain_manager::errors::{InitializeConductorError, LaunchHolochainError};
-use holochain_web_app_manager::{derive_window_label, error::LaunchWebAppManagerError};
+use holochain_web_app_manager::{
+  app_events::AppLifecycleEvent, assets_manifest::{AssetsManifest, AssetsVerification}, error::LaunchWebAppManagerError,
+  window_registry::derive_window_label, DEFAULT_UI_NAME,
+};
 use lair_keystore_manager::error::{LairKeystoreError, LaunchChildError};
 use lair_keystore_manager::utils::create_dir_if_necessary;
 use lair_keystore_manager::versions::v0_3::LairKeystoreManagerV0_3;
-use lair_keystore_manager::LairKeystoreManager;
+use futures::{channel::mpsc::UnboundedReceiver, lock::Mutex, StreamExt};
+use lair_keystore_manager::{sodoken::BufRead, KeystoreHealthEvent, LairKeystoreManager};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use tauri::api::process::Command;
 use tauri::{AppHandle, Manager, PhysicalSize};
 use tauri::{CustomMenuItem, Menu, Submenu};
+use url2::Url2;
 
-use holochain_manager::versions::HolochainVersion;
-use holochain_web_app_manager::WebAppManager;
+use holochain_manager::versions::{holochain_conductor_api_latest::AppInfoStatus, HolochainVersion};
+use holochain_web_app_manager::{WebAppManager, WebAppManagerSnapshot};
 
 use crate::file_system::{
   conductor_config_dir, holochain_version_data_dir, keystore_data_dir, launcher_config_dir,
   profile_config_dir, profile_holochain_data_dir, profile_lair_dir, Profile,
 };
-use crate::system_tray::AllInstalledApps;
-use crate::{running_state::RunningState, system_tray::update_system_tray, LauncherState};
+use crate::system_tray::{update_system_tray, AllInstalledApps};
+use crate::{running_state::RunningState, LauncherState};
 
+use super::agent_keys::AgentKeys;
+use super::apps_refresher::AppsRefresher;
 use super::config::LauncherConfig;
 use super::default_apps::install_default_apps_if_necessary;
 use super::error::LauncherError;
+use super::idle_lock::{watch_idle_lock, UserActivity};
+use super::zome_call_signer::ZomeCallSigner;
+
+/// Event emitted to the admin window with the HolochainId of a conductor that needs the passphrase to be launched
+pub const PASSWORD_NEEDED_TO_LAUNCH_EVENT: &str = "password-needed-to-launch";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type", content = "content")]
@@ -36,13 +48,41 @@ pub enum KeystoreStatus {
   LaunchKeystoreError(LairKeystoreError),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Payload of the event emitted to the admin window when an app UI is refused to open
+#[derive(Serialize, Debug, Clone)]
+pub struct TamperedUiAssets {
+  pub app_id: String,
+  pub ui_name: String,
+  /// Paths of the changed files, or of the assets manifest itself if it has been removed
+  pub files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(tag = "type", content = "content")]
 pub enum HolochainId {
   HolochainVersion(HolochainVersion),
   CustomBinary,
 }
 
+impl HolochainId {
+  /// The version the WebAppManager of this HolochainId is launched with
+  pub fn version(&self) -> HolochainVersion {
+    match self {
+      HolochainId::HolochainVersion(version) => version.clone(),
+      HolochainId::CustomBinary => HolochainVersion::custom(),
+    }
+  }
+}
+
+impl From<HolochainVersion> for HolochainId {
+  fn from(version: HolochainVersion) -> Self {
+    match version {
+      HolochainVersion::CustomBinary => HolochainId::CustomBinary,
+      version => HolochainId::HolochainVersion(version),
+    }
+  }
+}
+
 impl Into<String> for HolochainId {
   fn into(self) -> String {
     match self {
@@ -52,15 +92,120 @@ impl Into<String> for HolochainId {
   }
 }
 
+/// Shared handle to the WebAppManager of a conductor. Operations on different conductors don't wait
+/// for each other, and the LauncherState lock only needs to be held to look the handle up.
+pub type WebAppManagerHandle = Arc<Mutex<WebAppManager>>;
+
+/// An app window opened by the launcher
+#[derive(Debug, Clone)]
+struct AppWindow {
+  holochain_id: HolochainId,
+  app_id: String,
+  ui_name: String,
+}
+
+/// What launching a conductor needs. Taken from the LauncherManager such that the conductor can be
+/// launched without holding the LauncherState lock.
+struct HolochainLaunch {
+  version: HolochainVersion,
+  custom_binary_path: Option<String>,
+  config: LaunchHolochainConfig,
+  password: BufRead,
+  ui_revisions_to_keep: usize,
+  app_handle: Arc<AppHandle>,
+}
+
+impl HolochainLaunch {
+  /// Launches the conductor and its WebAppManager, installing the default apps for the default
+  /// Holochain version
+  async fn run(self) -> Result<RunningState<WebAppManager, LaunchWebAppManagerError>, String> {
+    let HolochainLaunch {
+      version,
+      config,
+      password,
+      ui_revisions_to_keep,
+      app_handle,
+      ..
+    } = self;
+
+    let version_str: String = version.into();
+
+    let admin_window = app_handle.get_window("admin").unwrap();
+
+    let state = match WebAppManager::launch(
+      version,
+      config,
+      app_handle.clone(),
+      password,
+      ui_revisions_to_keep,
+    )
+    .await
+    {
+      Ok(mut manager) => match version.eq(&HolochainVersion::default()) {
+        true => match install_default_apps_if_necessary(&mut manager, admin_window).await {
+          Ok(()) => {
+            log::info!("Launched Holochain {}", version_str);
+            RunningState::Running(manager)
+          }
+          Err(err) => {
+            manager.kill()?;
+            log::error!(
+              "Error launching Holochain {}: Could not install default apps: {}",
+              version_str,
+              err
+            );
+
+            RunningState::Error(LaunchWebAppManagerError::Other(format!(
+              "Could not install default apps: {}",
+              err
+            )))
+          }
+        },
+        false => {
+          let version_str: String = version.into();
+          log::info!("Launched Holochain {}", version_str);
+          RunningState::Running(manager)
+        }
+      },
+      Err(error) => {
+        log::error!("Error launching Holochain {}: {}", version_str, error);
+        match error.clone() {
+          LaunchWebAppManagerError::LaunchHolochainError(LaunchHolochainError::CouldNotInitializeConductor(ie)) => {
+            match ie {
+              InitializeConductorError::SqliteError(e) => {
+                if e.contains("DatabaseError(SqliteError(SqliteFailure(Error { code: NotADatabase, extended_code: 26 }") {
+                  app_handle.emit_all("WrongDatabaseFileType", ())
+                    .map_err(|e| format!("Failed to send WrongDatabaseFileType error to frontend: {}", e))?;
+                }
+              },
+              _ => (),
+            }
+          },
+          _ => (),
+        };
+        RunningState::Error(error)
+      }
+    };
+
+    Ok(state)
+  }
+}
+
 pub struct LauncherManager {
   app_handle: Arc<AppHandle>,
   config: LauncherConfig,
 
   pub holochain_managers:
-    HashMap<HolochainVersion, RunningState<WebAppManager, LaunchWebAppManagerError>>,
-  pub custom_binary_manager: Option<RunningState<WebAppManager, LaunchWebAppManagerError>>,
-  pub lair_keystore_manager: RunningState<Box<dyn LairKeystoreManager>, KeystoreStatus>,
+    HashMap<HolochainVersion, RunningState<WebAppManagerHandle, LaunchWebAppManagerError>>,
+  pub custom_binary_manager: Option<RunningState<WebAppManagerHandle, LaunchWebAppManagerError>>,
+  pub lair_keystore_manager: RunningState<Arc<dyn LairKeystoreManager>, KeystoreStatus>,
   pub profile: String,
+  /// Snapshots of the running WebAppManagers by version, the custom binary under its own version
+  snapshots: HashMap<HolochainVersion, WebAppManagerSnapshot>,
+  /// lair-keystore connection url the running conductors have been launched with
+  conductors_keystore_url: Option<Url2>,
+  /// Open app windows by window label
+  app_windows: HashMap<String, AppWindow>,
 }
 
 impl LauncherManager {
@@ -84,7 +229,6 @@ impl LauncherManager {
 
     let config = LauncherConfig::read(profile.clone());
 
-    let app_handle2 = app_handle.clone();
     let manager = LauncherManager {
       app_handle: app_handle.clone(),
       holochain_managers: HashMap::new(),
@@ -92,22 +236,11 @@ impl LauncherManager {
       config,
       lair_keystore_manager: RunningState::Error(keystore_status),
       profile,
+      snapshots: HashMap::new(),
+      conductors_keystore_url: None,
+      app_windows: HashMap::new(),
     };
 
-    // This doesn't work... TODO: Fix it
-    app_handle.listen_global("running_apps_changed", move |_| {
-      let launcher_state: &LauncherState = &app_handle2.state();
-      let result = tauri::async_runtime::block_on(async move {
-        let mut mutex = (*launcher_state).lock().await;
-        let manager = mutex.get_running()?;
-        manager.on_apps_changed().await
-      });
-
-      if let Err(err) = result {
-        log::error!("Couldn't refresh apps: {:?}", err);
-      }
-    });
-
     Ok(manager)
   }
 
@@ -115,7 +248,7 @@ impl LauncherManager {
   /// and WebAppManager(s).
   pub async fn initialize_keystore_and_launch(
     &mut self,
-    password: String,
+    password: BufRead,
     profile: Profile,
   ) -> Result<(), String> {
     // emitting signal to the front-end for progress indication
@@ -152,10 +285,11 @@ impl LauncherManager {
     Ok(())
   }
 
-  /// Launches LairKeystoreManager, HolochainManager(s) and WebAppManager(s).
+  /// Launches LairKeystoreManager, HolochainManager(s) and WebAppManager(s). The passphrase is
+  /// dropped, and zeroized with it, once everything is launched.
   pub async fn launch_managers(
     &mut self,
-    password: String,
+    password: BufRead,
     profile: Profile,
   ) -> Result<(), String> {
     let keystore_path = keystore_data_dir(
@@ -169,7 +303,20 @@ impl LauncherManager {
         .await
         .map_err(|err| format!("Error launching the keystore: {:?}", err))?;
 
-    self.lair_keystore_manager = RunningState::Running(Box::new(lair_keystore_manager));
+    self.watch_keystore_health(lair_keystore_manager.subscribe_health_events());
+
+    let lair_keystore_manager: Arc<dyn LairKeystoreManager> = Arc::new(lair_keystore_manager);
+    self
+      .app_handle
+      .state::<ZomeCallSigner>()
+      .set_keystore(lair_keystore_manager.clone());
+    self.conductors_keystore_url = Some(lair_keystore_manager.connection_url());
+    self.lair_keystore_manager = RunningState::Running(lair_keystore_manager);
+
+    self.app_handle.state::<UserActivity>().touch();
+    if let Some(minutes) = self.config.idle_lock_minutes {
+      watch_idle_lock(self.app_handle.clone(), std::time::Duration::from_secs(minutes * 60));
+    }
 
     // sleep for 300ms to prevent potential issue with DevHub's public key missing in lair keystore (https://github.com/holochain/launcher/issues/146)
     std::thread::sleep(std::time::Duration::from_millis(300));
@@ -191,13 +338,13 @@ impl LauncherManager {
         .map_err(|e| format!("Failed to send signal to the frontend: {:?}", e))?;
 
       self
-        .launch_holochain_manager(version, None, profile.clone())
+        .launch_holochain_manager(version, None, profile.clone(), password.clone())
         .await?;
     }
 
     if let Some(path) = self.config.custom_binary_path.clone() {
       self
-        .launch_holochain_manager(HolochainVersion::custom(), Some(path), profile.clone())
+        .launch_holochain_manager(HolochainVersion::custom(), Some(path), profile.clone(), password)
         .await?;
     } else {
       // If no custom holochain binary is specified in launcher-config.yaml, remove the data associated to previous
@@ -224,7 +371,28 @@ impl LauncherManager {
     version: HolochainVersion,
     custom_binary_path: Option<String>,
     profile: Profile, // custom root path for config files etc.
+    password: BufRead,
   ) -> Result<(), String> {
+    let launch = match self.prepare_holochain_launch(version, custom_binary_path, profile, password)? {
+      Some(launch) => launch,
+      None => return Ok(()),
+    };
+    let is_custom_binary = launch.custom_binary_path.is_some();
+
+    let state = launch.run().await?;
+
+    self.insert_holochain_manager(version, is_custom_binary, state)
+  }
+
+  /// Gathers what launching a conductor needs, such that it can be launched without holding the
+  /// LauncherState lock. Returns None if the custom binary doesn't exist.
+  fn prepare_holochain_launch(
+    &mut self,
+    version: HolochainVersion,
+    custom_binary_path: Option<String>,
+    profile: Profile,
+    password: BufRead,
+  ) -> Result<Option<HolochainLaunch>, String> {
     // If we are trying to launch Holochain from a custom binary path, but there is nothing in that path, error and exit immediately
     if let Some(path) = custom_binary_path.clone() {
       if !Path::new(&path).exists() {
@@ -233,7 +401,7 @@ impl LauncherManager {
             LaunchChildError::BinaryNotFound,
           )),
         ));
-        return Ok(());
+        return Ok(None);
       }
     }
 
@@ -258,10 +426,7 @@ impl LauncherManager {
         .map_err(|e| format!("Failed to get profile's holochain version data dir: {}", e))?,
     };
 
-    let lair_manager = self.get_lair_keystore_manager()?;
-
-    let keystore_connection_url = lair_manager.connection_url();
-    let password = lair_manager.password();
+    let keystore_connection_url = self.get_lair_keystore_manager()?.connection_url();
 
     let command = match custom_binary_path.clone() {
       Some(p) => Ok(Command::new(p)),
@@ -283,60 +448,40 @@ impl LauncherManager {
       signaling_server_url: self.config.signaling_server_url.clone(),
     };
 
-    let version_str: String = version.into();
-
-    let admin_window = self.app_handle.get_window("admin").unwrap();
-
-    let state = match WebAppManager::launch(version, config, self.app_handle.clone(), password)
-      .await
-    {
-      Ok(mut manager) => match version.eq(&HolochainVersion::default()) {
-        true => match install_default_apps_if_necessary(&mut manager, admin_window).await {
-          Ok(()) => {
-            log::info!("Launched Holochain {}", version_str);
-            RunningState::Running(manager)
-          }
-          Err(err) => {
-            manager.kill()?;
-            log::error!(
-              "Error launching Holochain {}: Could not install default apps: {}",
-              version_str,
-              err
-            );
+    Ok(Some(HolochainLaunch {
+      version,
+      custom_binary_path,
+      config,
+      password,
+      ui_revisions_to_keep: self.config.ui_revisions_to_keep,
+      app_handle: self.app_handle.clone(),
+    }))
+  }
 
-            RunningState::Error(LaunchWebAppManagerError::Other(format!(
-              "Could not install default apps: {}",
-              err
-            )))
-          }
-        },
-        false => {
-          let version_str: String = version.into();
-          log::info!("Launched Holochain {}", version_str);
-          RunningState::Running(manager)
-        }
-      },
-      Err(error) => {
-        log::error!("Error launching Holochain {}: {}", version_str, error);
-        match error.clone() {
-          LaunchWebAppManagerError::LaunchHolochainError(LaunchHolochainError::CouldNotInitializeConductor(ie)) => {
-            match ie {
-              InitializeConductorError::SqliteError(e) => {
-                if e.contains("DatabaseError(SqliteError(SqliteFailure(Error { code: NotADatabase, extended_code: 26 }") {
-                  self.app_handle.emit_all("WrongDatabaseFileType", ())
-                    .map_err(|e| format!("Failed to send WrongDatabaseFileType error to frontend: {}", e))?;
-                }
-              },
-              _ => (),
-            }
-          },
-          _ => (),
+  /// Watches the events of a launched WebAppManager and stores it in place of the previous one
+  fn insert_holochain_manager(
+    &mut self,
+    version: HolochainVersion,
+    is_custom_binary: bool,
+    state: RunningState<WebAppManager, LaunchWebAppManagerError>,
+  ) -> Result<(), String> {
+    let state = match state {
+      RunningState::Running(mut manager) => {
+        let holochain_id = match is_custom_binary {
+          true => HolochainId::CustomBinary,
+          false => HolochainId::HolochainVersion(version.clone()),
         };
+        self.watch_app_events(holochain_id, manager.subscribe_app_events());
+        self.snapshots.insert(version, manager.snapshot());
+        RunningState::Running(Arc::new(Mutex::new(manager)))
+      }
+      RunningState::Error(error) => {
+        self.snapshots.remove(&version);
         RunningState::Error(error)
       }
     };
 
-    if custom_binary_path.is_some() {
+    if is_custom_binary {
       self.custom_binary_manager = Some(state);
     } else {
       self.holochain_managers.insert(version.clone(), state);
@@ -348,12 +493,235 @@ impl LauncherManager {
       .write()
       .map_err(|err| format!("Could not write launcher config: {}", err))?;
 
-    self.on_apps_changed().await?;
+    self.request_apps_refresh();
 
     Ok(())
   }
 
-  pub fn get_lair_keystore_manager(&mut self) -> Result<&Box<dyn LairKeystoreManager>, String> {
+  /// Forwards health events of the supervised lair-keystore process to the admin window and asks
+  /// it to prompt for the passphrase if lair-keystore needs to be restarted
+  fn watch_keystore_health(&self, mut health_events: UnboundedReceiver<KeystoreHealthEvent>) {
+    let app_handle = self.app_handle.clone();
+
+    tauri::async_runtime::spawn(async move {
+      while let Some(event) = health_events.next().await {
+        if let Err(e) = app_handle.emit_all("keystore-health", event.clone()) {
+          log::error!("Failed to send keystore health event to the frontend: {:?}", e);
+        }
+
+        if let KeystoreHealthEvent::ProcessExited { .. } = event {
+          if let Err(e) = app_handle.emit_to("admin", "keystore-locked", ()) {
+            log::error!("Failed to send signal to the frontend: {:?}", e);
+          }
+        }
+      }
+    });
+  }
+
+  /// Updates the system tray and the windows of the changed app whenever an app of a WebAppManager
+  /// changed. The event stream ends once the WebAppManager is dropped.
+  fn watch_app_events(
+    &self,
+    holochain_id: HolochainId,
+    mut app_events: UnboundedReceiver<AppLifecycleEvent>,
+  ) {
+    let app_handle = self.app_handle.clone();
+
+    tauri::async_runtime::spawn(async move {
+      while let Some(event) = app_events.next().await {
+        log::info!("App lifecycle event: {:?}", event);
+
+        // events of the default apps installed while launching are covered by the refresh after it
+        let launcher_state = match app_handle.try_state::<LauncherState>() {
+          Some(launcher_state) => launcher_state,
+          None => continue,
+        };
+        match launcher_state.lock().await.get_running() {
+          Ok(manager) => manager.on_app_event(&holochain_id, &event),
+          Err(e) => log::error!("Failed to handle app lifecycle event: {}", e),
+        }
+      }
+    });
+  }
+
+  /// Updates the system tray from the snapshots, which the WebAppManager updated before emitting
+  /// the event, and closes the windows the event stopped
+  fn on_app_event(&mut self, holochain_id: &HolochainId, event: &AppLifecycleEvent) {
+    match event {
+      AppLifecycleEvent::AppUninstalled { app_id } | AppLifecycleEvent::AppDisabled { app_id } => {
+        self.close_app_windows(holochain_id, app_id, None);
+      }
+      AppLifecycleEvent::UiUpdated { app_id, ui_name } => {
+        let ui_removed = self
+          .get_snapshot(holochain_id)
+          .and_then(|snapshot| snapshot.installed_apps())
+          .map_or(false, |apps| {
+            !apps.iter().any(|app| {
+              app.installed_app_info.installed_app_id == *app_id && app.web_uis.contains_key(ui_name)
+            })
+          });
+        if ui_removed {
+          self.close_app_windows(holochain_id, app_id, Some(ui_name));
+        }
+      }
+      _ => (),
+    }
+
+    update_system_tray(&self.app_handle, &self.installed_apps_snapshot());
+  }
+
+  /// The installed apps of all running conductors, as of their last listing
+  fn installed_apps_snapshot(&self) -> AllInstalledApps {
+    let mut all_installed_apps = AllInstalledApps {
+      by_version: HashMap::new(),
+      custom_binary: None,
+    };
+
+    for (version, snapshot) in &self.snapshots {
+      let installed_apps = snapshot.installed_apps().unwrap_or_default();
+      match HolochainId::from(version.clone()) {
+        HolochainId::HolochainVersion(version) => {
+          all_installed_apps.by_version.insert(version, installed_apps);
+        }
+        HolochainId::CustomBinary => all_installed_apps.custom_binary = Some(installed_apps),
+      }
+    }
+
+    all_installed_apps
+  }
+
+  /// Updates the system tray and closes the windows of stopped apps in the background, once the
+  /// LauncherState lock is released
+  pub fn request_apps_refresh(&self) {
+    self.app_handle.state::<AppsRefresher>().request();
+  }
+
+  /// Takes the conductors that need to be launched once the keystore gets unlocked again, together
+  /// with the handles of the running ones: all of them if lair-keystore has been restarted at a
+  /// different connection url meanwhile, and otherwise the ones that are not running yet.
+  fn take_conductors_to_relaunch(
+    &mut self,
+  ) -> Result<Vec<(HolochainVersion, Option<String>, Option<WebAppManagerHandle>)>, String> {
+    let connection_url = self.get_lair_keystore_manager()?.connection_url();
+    let relaunch_all = match &self.conductors_keystore_url {
+      Some(url) => url.to_string() != connection_url.to_string(),
+      None => false,
+    };
+    self.conductors_keystore_url = Some(connection_url);
+
+    let running_handle = |state: &RunningState<WebAppManagerHandle, LaunchWebAppManagerError>| match state {
+      RunningState::Running(manager) => Some(manager.clone()),
+      RunningState::Error(_) => None,
+    };
+
+    let mut conductors = Vec::new();
+
+    for version in self.config.running_versions.iter() {
+      match self.holochain_managers.get(version) {
+        None => conductors.push((version.clone(), None, None)),
+        Some(state) if relaunch_all => conductors.push((version.clone(), None, running_handle(state))),
+        Some(_) => (),
+      }
+    }
+
+    if let Some(path) = self.config.custom_binary_path.clone() {
+      match &self.custom_binary_manager {
+        None => conductors.push((HolochainVersion::custom(), Some(path), None)),
+        Some(state) if relaunch_all => {
+          conductors.push((HolochainVersion::custom(), Some(path), running_handle(state)))
+        }
+        Some(_) => (),
+      }
+    }
+
+    Ok(conductors)
+  }
+
+  /// Keeps an error in place of a conductor that could not be relaunched
+  fn set_relaunch_error(&mut self, version: HolochainVersion, is_custom_binary: bool, error: String) {
+    let state = RunningState::Error(LaunchWebAppManagerError::Other(error));
+    self.snapshots.remove(&version);
+    match is_custom_binary {
+      true => self.custom_binary_manager = Some(state),
+      false => {
+        self.holochain_managers.insert(version, state);
+      }
+    }
+  }
+
+  /// Stops the launcher from signing for happ windows until the keystore gets unlocked again.
+  /// lair-keystore itself stays unlocked, and the conductors keep running and signing with it.
+  pub fn lock_keystore(&mut self) -> Result<(), String> {
+    let lair_keystore_manager = self.get_lair_keystore_manager()?;
+    if lair_keystore_manager.is_locked() {
+      return Ok(());
+    }
+    lair_keystore_manager.lock();
+
+    log::info!("Locked the keystore");
+
+    // ask the admin window to prompt for the password again
+    self
+      .app_handle
+      .emit_to("admin", "keystore-locked", ())
+      .map_err(|e| format!("Failed to send signal to the frontend: {:?}", e))
+  }
+
+  /// Unlocks a keystore that has been locked, without restarting the conductors. Conductors that
+  /// need the passphrase to be launched need to be relaunched afterwards.
+  pub async fn unlock_keystore(&mut self, password: BufRead) -> Result<(), String> {
+    self
+      .get_lair_keystore_manager()?
+      .unlock(password)
+      .await
+      .map_err(|err| format!("Error unlocking the keystore: {:?}", err))?;
+
+    self.app_handle.state::<UserActivity>().touch();
+
+    Ok(())
+  }
+
+  pub fn is_keystore_locked(&self) -> bool {
+    match &self.lair_keystore_manager {
+      RunningState::Running(m) => m.is_locked(),
+      RunningState::Error(_) => false,
+    }
+  }
+
+  /// Takes what operations on the agent keys of all conductors need. Conductors that are configured
+  /// but not running are passed on as unavailable, since their apps can't be accounted for.
+  pub fn agent_keys(&self) -> Result<AgentKeys, String> {
+    let mut holochain_ids: Vec<HolochainId> = self
+      .holochain_managers
+      .keys()
+      .chain(self.config.running_versions.iter())
+      .cloned()
+      .collect::<HashSet<HolochainVersion>>()
+      .into_iter()
+      .map(HolochainId::HolochainVersion)
+      .collect();
+    if self.custom_binary_manager.is_some() || self.config.custom_binary_path.is_some() {
+      holochain_ids.push(HolochainId::CustomBinary);
+    }
+
+    let mut web_app_managers = Vec::new();
+    let mut unavailable = Vec::new();
+    for holochain_id in holochain_ids {
+      match self.get_web_happ_manager(holochain_id.clone()) {
+        Ok(manager) => web_app_managers.push((holochain_id, manager)),
+        Err(_) => unavailable.push(holochain_id),
+      }
+    }
+
+    Ok(AgentKeys {
+      web_app_managers,
+      unavailable,
+      lair_keystore_manager: self.get_lair_keystore_manager()?.clone(),
+      profile: self.profile.clone(),
+    })
+  }
+
+  pub fn get_lair_keystore_manager(&self) -> Result<&Arc<dyn LairKeystoreManager>, String> {
     match &self.lair_keystore_manager {
       RunningState::Running(m) => Ok(m),
       RunningState::Error(error) => Err(format!(
@@ -363,55 +731,60 @@ impl LauncherManager {
     }
   }
 
-  /// Gets a mutable reference to the `WebAppManager` for a given holochain version
-  /// or custom binary if the `WebAppManager` already exists. Otherwise it launches
-  /// a corresponding `HolochainManager` and `WebAppManager`.
+  /// Gets the `WebAppManager` for a given holochain version or custom binary if the
+  /// `WebAppManager` already exists. Otherwise the conductor gets marked to be launched and the
+  /// admin window is asked to prompt for the passphrase, since it is not kept after launch.
   pub async fn get_or_launch_holochain(
     &mut self,
     holochain_id: HolochainId,
-    profile: String,
-  ) -> Result<&mut WebAppManager, String> {
-    match holochain_id {
-      HolochainId::HolochainVersion(version) => {
-        if let None = self.holochain_managers.get(&version) {
-          self
-            .launch_holochain_manager(version.clone(), None, profile)
-            .await?;
+  ) -> Result<WebAppManagerHandle, String> {
+    let is_launched = match &holochain_id {
+      HolochainId::HolochainVersion(version) => self.holochain_managers.contains_key(version),
+      HolochainId::CustomBinary => {
+        if self.config.custom_binary_path.is_none() {
+          return Err(String::from("There is no custom binary path specified"));
         }
+        self.custom_binary_manager.is_some()
       }
-      HolochainId::CustomBinary => {
-        let path = self
+    };
+
+    if !is_launched {
+      if let HolochainId::HolochainVersion(version) = &holochain_id {
+        self.config.running_versions.insert(version.clone());
+        self
           .config
-          .custom_binary_path
-          .clone()
-          .ok_or(String::from("There is no custom binary path specified"))?;
-
-        if let None = self.custom_binary_manager {
-          self
-            .launch_holochain_manager(HolochainVersion::custom(), Some(path), profile)
-            .await?;
-        }
+          .write()
+          .map_err(|err| format!("Could not write launcher config: {}", err))?;
       }
+
+      let holochain_id: String = holochain_id.into();
+      // the admin window prompts for the passphrase and calls launch_pending_holochains
+      self
+        .app_handle
+        .emit_to("admin", PASSWORD_NEEDED_TO_LAUNCH_EVENT, holochain_id.clone())
+        .map_err(|e| format!("Failed to send signal to the frontend: {:?}", e))?;
+
+      return Err(LauncherError::PasswordNeededToLaunch(holochain_id).to_string());
     }
 
     self.get_web_happ_manager(holochain_id)
   }
 
   pub fn get_web_happ_manager(
-    &mut self,
+    &self,
     holochain_id: HolochainId,
-  ) -> Result<&mut WebAppManager, String> {
+  ) -> Result<WebAppManagerHandle, String> {
     let manager_state = match holochain_id {
-      HolochainId::HolochainVersion(version) => self.holochain_managers.get_mut(&version).ok_or(
+      HolochainId::HolochainVersion(version) => self.holochain_managers.get(&version).ok_or(
         format!("Holochain version {} is not running.", version.to_string()),
       ),
-      HolochainId::CustomBinary => self.custom_binary_manager.as_mut().ok_or(String::from(
+      HolochainId::CustomBinary => self.custom_binary_manager.as_ref().ok_or(String::from(
         "There is no Holochain running with custom binary",
       )),
     }?;
 
     match manager_state {
-      RunningState::Running(m) => Ok(m),
+      RunningState::Running(m) => Ok(m.clone()),
       RunningState::Error(error) => match holochain_id {
         HolochainId::HolochainVersion(version) => Err(format!(
           "Holochain Version {} threw an exception: {:?}",
@@ -426,143 +799,356 @@ impl LauncherManager {
     }
   }
 
-  /// Connects to the conductor, requests the list of running apps and updates the system tray
-  pub async fn on_apps_changed(&mut self) -> Result<(), String> {
-    let versions: Vec<HolochainVersion> = self.holochain_managers.keys().cloned().collect();
+  /// Snapshot of the WebAppManager of a running conductor, readable without locking it
+  pub fn get_snapshot(&self, holochain_id: &HolochainId) -> Option<WebAppManagerSnapshot> {
+    self.snapshots.get(&holochain_id.version()).cloned()
+  }
+
+  /// The WebAppManagers of all running conductors
+  pub fn web_happ_managers(&self) -> Vec<(HolochainId, WebAppManagerHandle)> {
+    let versions = self
+      .holochain_managers
+      .iter()
+      .filter_map(|(version, state)| match state {
+        RunningState::Running(manager) => Some((HolochainId::HolochainVersion(version.clone()), manager.clone())),
+        RunningState::Error(_) => None,
+      });
 
-    let mut all_installed_apps = AllInstalledApps {
-      by_version: HashMap::new(),
-      custom_binary: None,
+    let custom_binary = match &self.custom_binary_manager {
+      Some(RunningState::Running(manager)) => Some((HolochainId::CustomBinary, manager.clone())),
+      _ => None,
     };
 
-    for version in versions {
-      if let Ok(web_app_manager) =
-        self.get_web_happ_manager(HolochainId::HolochainVersion(version.clone()))
+    versions.chain(custom_binary).collect()
+  }
+
+  /// Closes the open windows of an app, or only the ones of the given UI
+  fn close_app_windows(&mut self, holochain_id: &HolochainId, app_id: &String, ui_name: Option<&String>) {
+    let app_handle = self.app_handle.clone();
+
+    self.app_windows.retain(|window_label, app_window| {
+      // the window has been closed by the user
+      let window = match app_handle.get_window(window_label.as_str()) {
+        Some(window) => window,
+        None => return false,
+      };
+
+      if app_window.holochain_id != *holochain_id
+        || app_window.app_id != *app_id
+        || ui_name.map_or(false, |ui_name| app_window.ui_name != *ui_name)
       {
-        let running_apps = web_app_manager.list_apps().await?;
+        return true;
+      }
 
-        all_installed_apps
-          .by_version
-          .insert(version.clone(), running_apps);
+      log::info!(
+        "Closing window of UI '{}' of app '{}' since the app is not running anymore",
+        app_window.ui_name, app_window.app_id
+      );
+      if let Err(e) = window.close() {
+        log::error!("Failed to close window of app '{}': {:?}", app_window.app_id, e);
       }
-    }
+      false
+    });
+  }
 
-    if let Some(m) = &mut self.custom_binary_manager {
-      match m.get_running() {
-        Ok(web_app_manager) => {
-          let running_apps = web_app_manager.list_apps().await?;
+  /// Closes the open windows of apps that have been uninstalled or disabled, or whose UI has been removed
+  pub fn close_windows_of_stopped_apps(&mut self, all_installed_apps: &AllInstalledApps) {
+    let app_handle = self.app_handle.clone();
+
+    self.app_windows.retain(|window_label, app_window| {
+      // the window has been closed by the user
+      let window = match app_handle.get_window(window_label.as_str()) {
+        Some(window) => window,
+        None => return false,
+      };
+
+      let installed_apps = match &app_window.holochain_id {
+        HolochainId::HolochainVersion(version) => all_installed_apps.by_version.get(version),
+        HolochainId::CustomBinary => all_installed_apps.custom_binary.as_ref(),
+      };
+      let is_running = installed_apps.map_or(false, |apps| {
+        apps.iter().any(|app| {
+          app.installed_app_info.installed_app_id == app_window.app_id
+            && matches!(app.installed_app_info.status, AppInfoStatus::Running)
+            && app.web_uis.contains_key(&app_window.ui_name)
+        })
+      });
+      if is_running {
+        return true;
+      }
 
-          all_installed_apps.custom_binary = Some(running_apps);
-        }
-        Err(_) => {}
+      log::info!(
+        "Closing window of UI '{}' of app '{}' since the app is not running anymore",
+        app_window.ui_name, app_window.app_id
+      );
+      if let Err(e) = window.close() {
+        log::error!("Failed to close window of app '{}': {:?}", app_window.app_id, e);
       }
+      false
+    });
+  }
+}
+
+/// Launches the conductors that need the passphrase after the keystore got unlocked, restarting
+/// the running ones if lair-keystore has been restarted at a different connection url. The
+/// LauncherState lock is only held to look the conductors up and to swap in the relaunched ones,
+/// and each conductor is killed once its running operations finished. Conductors that fail to
+/// relaunch are kept as errors and don't keep the others from relaunching.
+pub async fn relaunch_holochain_managers(
+  launcher_state: &LauncherState,
+  password: BufRead,
+) -> Result<(), String> {
+  let conductors = (*launcher_state)
+    .lock()
+    .await
+    .get_running()?
+    .take_conductors_to_relaunch()?;
+
+  let mut errors = Vec::new();
+
+  for (version, custom_binary_path, handle) in conductors {
+    let is_custom_binary = custom_binary_path.is_some();
+
+    let result =
+      relaunch_holochain_manager(launcher_state, version, custom_binary_path, handle, password.clone()).await;
+
+    if let Err(e) = result {
+      log::error!("Failed to relaunch Holochain {}: {}", version.to_string(), e);
+      (*launcher_state)
+        .lock()
+        .await
+        .get_running()?
+        .set_relaunch_error(version, is_custom_binary, e.clone());
+
+      errors.push(match is_custom_binary {
+        true => format!("Custom binary: {}", e),
+        false => format!("Holochain {}: {}", version.to_string(), e),
+      });
     }
+  }
 
-    update_system_tray(&self.app_handle, &all_installed_apps);
+  match errors.is_empty() {
+    true => Ok(()),
+    false => Err(errors.join(", ")),
+  }
+}
 
-    // TODO: Iterate over the open windows, close any that has been uninstalled/disabled
+async fn relaunch_holochain_manager(
+  launcher_state: &LauncherState,
+  version: HolochainVersion,
+  custom_binary_path: Option<String>,
+  handle: Option<WebAppManagerHandle>,
+  password: BufRead,
+) -> Result<(), String> {
+  let is_custom_binary = custom_binary_path.is_some();
+
+  if let Some(manager) = handle {
+    manager.lock().await.kill()?;
+  }
 
-    Ok(())
+  let launch = {
+    let mut mutex = (*launcher_state).lock().await;
+    let manager = mutex.get_running()?;
+    let profile = manager.profile.clone();
+    manager.prepare_holochain_launch(version, custom_binary_path, profile, password)?
+  };
+
+  let launch = match launch {
+    Some(launch) => launch,
+    None => return Ok(()),
+  };
+
+  let state = launch.run().await?;
+
+  (*launcher_state)
+    .lock()
+    .await
+    .get_running()?
+    .insert_holochain_manager(version, is_custom_binary, state)
+}
+
+/// Opens the window of an app UI, or focuses it if it's already open. The LauncherState lock is
+/// only held to look up the WebAppManager and to register the window, not while verifying the UI
+/// assets or building the window.
+pub async fn open_app(
+  launcher_state: &LauncherState,
+  holochain_id: HolochainId,
+  app_id: &String,
+  ui_name: &String,
+  visible: bool,
+) -> Result<(), String> {
+  let window_label = derive_window_label(&holochain_id.version(), app_id, ui_name);
+
+  let (app_handle, web_app_manager) = {
+    let mut mutex = launcher_state.lock().await;
+    let manager = mutex.get_running()?;
+    (manager.app_handle.clone(), manager.get_web_happ_manager(holochain_id.clone()))
+  };
+
+  // Iterate over the open windows, focus if the app is already open
+
+  if let Some(w) = app_handle.get_window(window_label.as_str()) {
+    focus_app_window(&w, visible);
+    return Ok(());
   }
 
-  pub fn open_app(&mut self, holochain_id: HolochainId, app_id: &String, visible: bool) -> Result<(), String> {
-    let window_label = derive_window_label(&app_id);
+  let web_app_manager = web_app_manager?;
+  let manager = web_app_manager.lock().await;
 
-    // Iterate over the open windows, focus if the app is already open
+  let assets_path = manager.get_app_assets_dir(app_id, ui_name);
+  if !assets_path.exists() {
+    return Err(format!("The app '{}' has no UI with the name '{}'", app_id, ui_name));
+  }
+  let assets_manifest_path = manager.get_ui_assets_manifest_path(app_id, ui_name);
+  let local_storage_path = manager.get_app_local_storage_dir(app_id, ui_name);
+  let window_title = match ui_name == DEFAULT_UI_NAME {
+    true => app_id.clone(),
+    false => format!("{} ({})", app_id, ui_name),
+  };
+  let app_port = manager.holochain_manager.app_interface_port();
+  let admin_port = manager.holochain_manager.admin_interface_port();
+  drop(manager);
+
+  // hashing the assets may take a while, so it happens on a blocking thread without holding the WebAppManager
+  let assets_dir = assets_path.clone();
+  let verification = tauri::async_runtime::spawn_blocking(move || AssetsManifest::verify_at(&assets_manifest_path, &assets_dir))
+    .await
+    .map_err(|e| format!("Failed to verify UI assets: {}", e))??;
+
+  let tampered_files = match verification {
+    AssetsVerification::Valid => None,
+    AssetsVerification::NoManifest => Some(vec![String::from(".assets-manifest.yaml")]),
+    AssetsVerification::Tampered(files) => Some(files),
+  };
+  if let Some(files) = tampered_files {
+    log::error!("Refusing to open UI '{}' of app '{}': its assets have been modified since installation: {:?}", ui_name, app_id, files);
+    app_handle.emit_to("admin", "ui-assets-tampered", TamperedUiAssets {
+      app_id: app_id.clone(),
+      ui_name: ui_name.clone(),
+      files: files.clone(),
+    }).map_err(|e| format!("Failed to emit tampered UI assets event: {}", e))?;
+    return Err(format!(
+      "The assets of UI '{}' of app '{}' have been modified since installation ({} file(s)). Reinstall the UI or explicitly trust its current assets to open it.",
+      ui_name, app_id, files.len()
+    ));
+  }
 
-    if let Some(w) = self.app_handle.get_window(window_label.as_str()) {
-      if visible {
-        if let Err(err) = w.show() {
-          log::error!("Error showing the window: {:?}", err);
-        }
-        if let Err(err) = w.unminimize() {
-          log::error!("Error unminimizing the window: {:?}", err);
-        }
-        if let Err(err) = w.set_focus() {
-          log::error!("Error setting focus to the window: {:?}", err);
-        }
-      }
-      return Ok(());
-    }
+  // the window may have been opened concurrently while the assets got verified
+  if let Some(w) = app_handle.get_window(window_label.as_str()) {
+    focus_app_window(&w, visible);
+    return Ok(());
+  }
 
-    let manager = self.get_web_happ_manager(holochain_id)?;
-
-    // assuming at some point there may be multiple UI's for the same happ, open the "default" folder for now
-    let ui_name = String::from("default");
-    let assets_path = manager.get_app_assets_dir(app_id, &ui_name);
-    let local_storage_path = manager.get_app_local_storage_dir(app_id, &ui_name);
-    let app_port = manager.holochain_manager.app_interface_port();
-    let admin_port = manager.holochain_manager.admin_interface_port();
-
-    let mut window_builder = happ_window_builder(
-      &self.app_handle,
-      app_id.into(),
-      window_label.clone(),
-      app_id.into(),
-      UISource::Path(assets_path),
-      local_storage_path,
-      app_port,
-      admin_port,
-      true,
-    );
+  let mut window_builder = happ_window_builder(
+    &app_handle,
+    app_id.into(),
+    window_label.clone(),
+    window_title,
+    UISource::Path(assets_path),
+    local_storage_path,
+    app_port,
+    admin_port,
+    true,
+  );
+
+  if !visible {
+    window_builder = window_builder.visible(false);
+  }
 
-    if !visible {
-      window_builder = window_builder.visible(false);
-    }
+  // needs to be removed in order for set_size() to work apparently
+  // window_builder = window_builder.maximized(true);
 
-    // needs to be removed in order for set_size() to work apparently
-    // window_builder = window_builder.maximized(true);
+  // set window size to 80% of a common screen resolution of 1920 x 1080.
+  window_builder = window_builder.inner_size(1536.0, 864.0);
 
-    // set window size to 80% of a common screen resolution of 1920 x 1080.
-    window_builder = window_builder.inner_size(1536.0, 864.0);
+  // add launcher API scripts
+  window_builder = window_builder.initialization_script(include_str!("../../../api_scripts/dist/launcher-api-scripts.js"));
 
-    // add launcher API scripts
-    window_builder = window_builder.initialization_script(include_str!("../../../api_scripts/dist/launcher-api-scripts.js"));
+  // placeholder for when apps come shipped with their custom icons:
+  //
+  // window_builder
+  //  .icon(tauri::Icon::File(icon_path))
+  //  .map_err(|err| format!("Error adding icon: {:?}", err))?
 
-    // placeholder for when apps come shipped with their custom icons:
-    //
-    // window_builder
-    //  .icon(tauri::Icon::File(icon_path))
-    //  .map_err(|err| format!("Error adding icon: {:?}", err))?
+  // factor of the monitor size to which the window shall be scaled
+  let _scaling_factor = 0.8;
 
-    // factor of the monitor size to which the window shall be scaled
-    let _scaling_factor = 0.8;
+  if cfg!(target_os = "macos") {
+    if build_app_window(&app_handle, window_builder, &window_label, visible)?.is_none() {
+      return Ok(());
+    }
+    // removing this for now since it behaves inconsistently
+    // set_window_size(window, scaling_factor);
+  } else {
+    window_builder = window_builder.menu(Menu::new().add_submenu(Submenu::new(
+      // This overwrites the global menu on macOS (https://github.com/tauri-apps/tauri/issues/5768)
+      "Settings",
+      Menu::new().add_item(CustomMenuItem::new("show-devtools", "Show DevTools")),
+    )));
+
+    // Window opens weirdly out of bounds on windows if not centered.
+    if cfg!(target_os = "windows") {
+      window_builder = window_builder.center();
+    }
 
-    if cfg!(target_os = "macos") {
-      let _window = window_builder
-        .build()
-        .map_err(|err| format!("Error opening app: {:?}", err))?;
-      // removing this for now since it behaves inconsistently
-      // set_window_size(window, scaling_factor);
-    } else {
-      window_builder = window_builder.menu(Menu::new().add_submenu(Submenu::new(
-        // This overwrites the global menu on macOS (https://github.com/tauri-apps/tauri/issues/5768)
-        "Settings",
-        Menu::new().add_item(CustomMenuItem::new("show-devtools", "Show DevTools")),
-      )));
-
-      // Window opens weirdly out of bounds on windows if not centered.
-      if cfg!(target_os = "windows") {
-        window_builder = window_builder.center();
+    let window = match build_app_window(&app_handle, window_builder, &window_label, visible)? {
+      Some(window) => window,
+      None => return Ok(()),
+    };
+    // Listen to "open-devtools" command
+    let a = app_handle.clone();
+    let l = window_label.clone();
+    window.on_menu_event(move |_| {
+      if let Some(w) = a.get_window(l.as_str()) {
+        w.open_devtools();
       }
+    });
 
-      let window = window_builder
-        .build()
-        .map_err(|err| format!("Error opening app: {:?}", err))?;
-      // Listen to "open-devtools" command
-      let a = self.app_handle.clone();
-      let l = window_label.clone();
-      window.on_menu_event(move |_| {
-        if let Some(w) = a.get_window(l.as_str()) {
-          w.open_devtools();
-        }
-      });
+    // removing this for now because it behaves inconsistently
+    // set_window_size(window, scaling_factor);
+  }
+
+  launcher_state.lock().await.get_running()?.app_windows.insert(window_label, AppWindow {
+    holochain_id,
+    app_id: app_id.clone(),
+    ui_name: ui_name.clone(),
+  });
 
-      // removing this for now because it behaves inconsistently
-      // set_window_size(window, scaling_factor);
+  Ok(())
+}
+
+/// Builds the window of an app UI. Returns None after focusing the window instead if a concurrent
+/// open_app call built it first.
+fn build_app_window(
+  app_handle: &AppHandle,
+  window_builder: tauri::WindowBuilder<'_>,
+  window_label: &String,
+  visible: bool,
+) -> Result<Option<tauri::Window>, String> {
+  match window_builder.build() {
+    Ok(window) => Ok(Some(window)),
+    Err(tauri::Error::WindowLabelAlreadyExists(_)) => {
+      if let Some(w) = app_handle.get_window(window_label.as_str()) {
+        focus_app_window(&w, visible);
+      }
+      Ok(None)
     }
+    Err(err) => Err(format!("Error opening app: {:?}", err)),
+  }
+}
 
-    Ok(())
+fn focus_app_window(window: &tauri::Window, visible: bool) {
+  if !visible {
+    return;
+  }
+  if let Err(err) = window.show() {
+    log::error!("Error showing the window: {:?}", err);
+  }
+  if let Err(err) = window.unminimize() {
+    log::error!("Error unminimizing the window: {:?}", err);
+  }
+  if let Err(err) = window.set_focus() {
+    log::error!("Error setting focus to the window: {:?}", err);
   }
 }
 
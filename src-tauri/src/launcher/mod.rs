@@ -1,5 +1,12 @@
+pub mod agent_keys;
+pub mod apps_refresher;
 pub mod error;
 pub mod manager;
 pub mod state;
 pub mod config;
 pub mod default_apps;
+pub mod identities;
+pub mod idle_lock;
+pub mod signing_consents;
+pub mod zome_call_audit_log;
+pub mod zome_call_signer;
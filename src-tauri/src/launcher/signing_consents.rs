@@ -0,0 +1,84 @@
+use std::{collections::HashSet, fs};
+
+use futures::lock::Mutex;
+use hdk::prelude::AgentPubKey;
+use serde::{Deserialize, Serialize};
+
+use crate::file_system::{signing_consents_path, write_atomically, Profile};
+
+use super::manager::HolochainId;
+
+/// Signing consents of the profile, loaded once such that concurrent updates are serialized
+pub type SigningConsentsState = Mutex<SigningConsents>;
+
+/// An app the user allowed to sign arbitrary data with the given agent key without being asked again
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SigningConsent {
+  pub holochain_id: HolochainId,
+  pub app_id: String,
+  pub agent_pub_key: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SigningConsents {
+  #[serde(default)]
+  consents: HashSet<SigningConsent>,
+}
+
+impl SigningConsents {
+  pub fn read(profile: Profile) -> Self {
+    let path = match signing_consents_path(profile) {
+      Ok(path) => path,
+      Err(e) => {
+        log::error!("Failed to get signing consents path: {}", e);
+        return SigningConsents::default();
+      }
+    };
+
+    match fs::read_to_string(path) {
+      Ok(str) => serde_yaml::from_str(str.as_str()).unwrap_or_else(|e| {
+        log::error!("Failed to parse signing consents: {}", e);
+        SigningConsents::default()
+      }),
+      Err(_) => SigningConsents::default(),
+    }
+  }
+
+  fn write(&self, profile: Profile) -> Result<(), String> {
+    let path = signing_consents_path(profile)
+      .map_err(|e| format!("Failed to get signing consents path: {}", e))?;
+    let serialized = serde_yaml::to_string(&self)
+      .map_err(|e| format!("Failed to serialize signing consents: {}", e))?;
+
+    write_atomically(&path, serialized).map_err(|e| format!("Failed to write signing consents: {}", e))
+  }
+
+  pub fn contains(&self, consent: &SigningConsent) -> bool {
+    self.consents.contains(consent)
+  }
+
+  pub fn add(&mut self, consent: SigningConsent, profile: Profile) -> Result<(), String> {
+    if self.consents.insert(consent) {
+      self.write(profile)?;
+    }
+    Ok(())
+  }
+
+  /// Removes the consents given to an app, for all of its agent keys
+  pub fn remove_app(
+    &mut self,
+    holochain_id: &HolochainId,
+    app_id: &String,
+    profile: Profile,
+  ) -> Result<(), String> {
+    let count = self.consents.len();
+    self
+      .consents
+      .retain(|consent| &consent.holochain_id != holochain_id || &consent.app_id != app_id);
+
+    if self.consents.len() != count {
+      self.write(profile)?;
+    }
+    Ok(())
+  }
+}
@@ -1,4 +1,5 @@
 use futures::lock::Mutex;
+use hdk::prelude::AgentPubKey;
 use holochain_manager::versions::{HolochainVersion, HdiVersion, HdkVersion};
 use holochain_web_app_manager::installed_web_app_info::InstalledWebAppInfo;
 use serde::{Deserialize, Serialize};
@@ -7,10 +8,12 @@ use std::{collections::HashMap, sync::Arc};
 use super::{
   config::LauncherConfig,
   error::LauncherError,
-  manager::{KeystoreStatus, LauncherManager},
+  manager::{HolochainId, KeystoreStatus, LauncherManager},
 };
 use crate::running_state::RunningState;
 
+/// Only held to look up managers, to launch or kill conductors and to track the open app windows.
+/// Never wait for it while holding the lock of a WebAppManager or of the window registry.
 pub type LauncherState = Arc<Mutex<RunningState<LauncherManager, LauncherError>>>;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,3 +39,25 @@ pub struct RunningHolochainsStateInfo {
   pub versions: HashMap<HolochainVersion, HolochainState>,
   pub custom_binary: Option<HolochainState>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentKeyApp {
+  pub holochain_id: HolochainId,
+  pub app_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentKeyInfo {
+  pub agent_pub_key: AgentPubKey,
+  pub tag: String,
+  pub label: Option<String>,
+  /// Installed apps using this key, across all running Holochain versions
+  pub installed_apps: Vec<AgentKeyApp>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AgentKeyInventory {
+  pub agent_keys: Vec<AgentKeyInfo>,
+  /// Conductors whose apps could not be listed. If any, the installed apps of the keys are partial.
+  pub unavailable_holochains: Vec<HolochainId>,
+}
@@ -0,0 +1,369 @@
+use std::{
+  fs::{self, File, OpenOptions},
+  io::Write,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use hdk::prelude::AgentPubKey;
+use holochain_launcher_utils::zome_call_signing::ZomeCallUnsignedTauri;
+use serde::{Deserialize, Serialize};
+
+use super::config::ZomeCallAuditLogConfig;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum ZomeCallOutcome {
+  Signed,
+  /// The reason the signing request got rejected
+  Rejected(String),
+  /// The window exceeded its zome call rate limit
+  Throttled,
+}
+
+/// What a window requested to get signed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
+pub enum SigningRequest {
+  ZomeCall {
+    dna_hash: String,
+    zome_name: String,
+    fn_name: String,
+  },
+  /// Arbitrary data signed via `sign_bytes`
+  Bytes { length: usize },
+}
+
+/// A signing request. Neither the payload of a zome call nor the signed data get recorded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZomeCallAuditEntry {
+  /// Milliseconds since the unix epoch
+  pub timestamp: u64,
+  pub window_label: String,
+  /// None for the admin window
+  pub app_id: Option<String>,
+  /// The key the request was to be signed with
+  pub agent_pub_key: String,
+  pub request: SigningRequest,
+  pub outcome: ZomeCallOutcome,
+}
+
+impl ZomeCallAuditEntry {
+  pub fn zome_call(
+    window_label: &str,
+    app_id: Option<String>,
+    zome_call_unsigned: &ZomeCallUnsignedTauri,
+    outcome: ZomeCallOutcome,
+  ) -> Self {
+    let request = SigningRequest::ZomeCall {
+      dna_hash: zome_call_unsigned.cell_id.dna_hash().to_string(),
+      zome_name: zome_call_unsigned.zome_name.to_string(),
+      fn_name: zome_call_unsigned.fn_name.to_string(),
+    };
+    Self::new(window_label, app_id, &zome_call_unsigned.provenance, request, outcome)
+  }
+
+  pub fn sign_bytes(
+    window_label: &str,
+    app_id: Option<String>,
+    agent_pub_key: &AgentPubKey,
+    length: usize,
+    outcome: ZomeCallOutcome,
+  ) -> Self {
+    Self::new(window_label, app_id, agent_pub_key, SigningRequest::Bytes { length }, outcome)
+  }
+
+  fn new(
+    window_label: &str,
+    app_id: Option<String>,
+    agent_pub_key: &AgentPubKey,
+    request: SigningRequest,
+    outcome: ZomeCallOutcome,
+  ) -> Self {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_millis() as u64)
+      .unwrap_or(0);
+
+    ZomeCallAuditEntry {
+      timestamp,
+      window_label: window_label.to_string(),
+      app_id,
+      agent_pub_key: agent_pub_key.to_string(),
+      request,
+      outcome,
+    }
+  }
+}
+
+/// Filter for querying the audit log. Entries are returned newest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ZomeCallAuditQuery {
+  pub app_id: Option<String>,
+  pub window_label: Option<String>,
+  /// Milliseconds since the unix epoch
+  pub since: Option<u64>,
+  pub limit: Option<usize>,
+}
+
+impl ZomeCallAuditQuery {
+  fn matches(&self, entry: &ZomeCallAuditEntry) -> bool {
+    self.app_id.as_ref().map_or(true, |app_id| entry.app_id.as_ref() == Some(app_id))
+      && self.window_label.as_ref().map_or(true, |label| &entry.window_label == label)
+      && self.since.map_or(true, |since| entry.timestamp >= since)
+  }
+}
+
+/// Log of signing requests, one json object per line, rotated by size. Entries are written by a
+/// dedicated thread, so recording one never waits for the file system.
+pub struct ZomeCallAuditLog {
+  path: PathBuf,
+  /// Nothing gets recorded if not set
+  config: Option<ZomeCallAuditLogConfig>,
+  /// Feeds the writer thread, if the log is enabled
+  entries: Option<UnboundedSender<ZomeCallAuditEntry>>,
+}
+
+impl ZomeCallAuditLog {
+  pub fn new(path: PathBuf, config: Option<ZomeCallAuditLogConfig>) -> Self {
+    let entries = config.clone().map(|config| {
+      let (sender, receiver) = unbounded();
+      let writer = AuditLogWriter {
+        path: path.clone(),
+        config,
+        file: None,
+      };
+      std::thread::spawn(move || writer.run(receiver));
+      sender
+    });
+
+    ZomeCallAuditLog { path, config, entries }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.config.is_some()
+  }
+
+  /// Queues the entry for the writer thread
+  pub fn record(&self, entry: ZomeCallAuditEntry) {
+    if let Some(entries) = &self.entries {
+      if let Err(e) = entries.unbounded_send(entry) {
+        log::error!("Failed to record signing request in the audit log: {:?}", e);
+      }
+    }
+  }
+
+  /// Reads the entries matching the query from the current and the rotated files
+  pub fn query(&self, query: &ZomeCallAuditQuery) -> Result<Vec<ZomeCallAuditEntry>, String> {
+    let max_rotated_files = self.config.as_ref().map_or(0, |config| config.max_rotated_files);
+
+    let mut entries = Vec::new();
+    // newest file first
+    for index in 0..=max_rotated_files {
+      let content = match fs::read_to_string(file_path(&self.path, index)) {
+        Ok(content) => content,
+        Err(_) => continue,
+      };
+
+      for line in content.lines().rev() {
+        // skip lines that got cut off by a crash
+        let entry = match serde_json::from_str::<ZomeCallAuditEntry>(line) {
+          Ok(entry) => entry,
+          Err(_) => continue,
+        };
+        if query.matches(&entry) {
+          entries.push(entry);
+          if query.limit.map_or(false, |limit| entries.len() >= limit) {
+            return Ok(entries);
+          }
+        }
+      }
+    }
+
+    Ok(entries)
+  }
+}
+
+/// Appends the recorded entries to the current file, which stays open until it gets rotated
+struct AuditLogWriter {
+  path: PathBuf,
+  config: ZomeCallAuditLogConfig,
+  /// The current file and its size
+  file: Option<(File, u64)>,
+}
+
+impl AuditLogWriter {
+  /// Writes entries until the ZomeCallAuditLog is dropped
+  fn run(mut self, entries: UnboundedReceiver<ZomeCallAuditEntry>) {
+    for entry in futures::executor::block_on_stream(entries) {
+      if let Err(e) = self.write(&entry) {
+        log::error!("Failed to write to zome call audit log: {}", e);
+        // reopened for the next entry
+        self.file = None;
+      }
+    }
+  }
+
+  fn write(&mut self, entry: &ZomeCallAuditEntry) -> Result<(), String> {
+    let mut line = serde_json::to_string(entry)
+      .map_err(|e| format!("Failed to serialize zome call audit entry: {}", e))?;
+    line.push('\n');
+
+    if self.file.is_none() {
+      self.file = Some(open_log_file(&self.path)?);
+    }
+    if self.file.as_ref().map_or(false, |(_, size)| *size >= self.config.max_file_size) {
+      // closed before it gets renamed
+      self.file = None;
+      rotate(&self.path, self.config.max_rotated_files)?;
+      self.file = Some(open_log_file(&self.path)?);
+    }
+
+    if let Some((file, size)) = &mut self.file {
+      file
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write to zome call audit log: {}", e))?;
+      *size += line.len() as u64;
+    }
+
+    Ok(())
+  }
+}
+
+/// Opens the current file for appending, together with its size
+fn open_log_file(path: &Path) -> Result<(File, u64), String> {
+  let file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .map_err(|e| format!("Failed to open zome call audit log: {}", e))?;
+  let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+  Ok((file, size))
+}
+
+fn rotate(path: &Path, max_rotated_files: usize) -> Result<(), String> {
+  let oldest = file_path(path, max_rotated_files);
+  if max_rotated_files == 0 || oldest.exists() {
+    fs::remove_file(&oldest).map_err(|e| format!("Failed to remove oldest zome call audit log: {}", e))?;
+  }
+
+  for index in (1..max_rotated_files).rev() {
+    let rotated_path = file_path(path, index);
+    if rotated_path.exists() {
+      fs::rename(&rotated_path, file_path(path, index + 1))
+        .map_err(|e| format!("Failed to rotate zome call audit log: {}", e))?;
+    }
+  }
+
+  if max_rotated_files > 0 {
+    fs::rename(path, file_path(path, 1))
+      .map_err(|e| format!("Failed to rotate zome call audit log: {}", e))?;
+  }
+
+  Ok(())
+}
+
+/// Path of the current file for index 0, of the rotated files otherwise
+fn file_path(path: &Path, index: usize) -> PathBuf {
+  if index == 0 {
+    return path.to_path_buf();
+  }
+  path.with_extension(format!("{}.jsonl", index))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  /// Audit log writing every entry to a new file and keeping two rotated files, in the given directory
+  fn audit_log(dir: &TempDir) -> (ZomeCallAuditLog, AuditLogWriter) {
+    let path = dir.path().join("zome-call-audit.jsonl");
+    let config = ZomeCallAuditLogConfig {
+      max_file_size: 1,
+      max_rotated_files: 2,
+    };
+    let writer = AuditLogWriter {
+      path: path.clone(),
+      config: config.clone(),
+      file: None,
+    };
+    let log = ZomeCallAuditLog {
+      path,
+      config: Some(config),
+      entries: None,
+    };
+    (log, writer)
+  }
+
+  fn entry(app_id: &str, length: usize) -> ZomeCallAuditEntry {
+    ZomeCallAuditEntry::sign_bytes(
+      "window",
+      Some(app_id.to_string()),
+      &AgentPubKey::from_raw_36(vec![1; 36]),
+      length,
+      ZomeCallOutcome::Signed,
+    )
+  }
+
+  fn lengths(entries: Vec<ZomeCallAuditEntry>) -> Vec<usize> {
+    entries
+      .into_iter()
+      .map(|entry| match entry.request {
+        SigningRequest::Bytes { length } => length,
+        SigningRequest::ZomeCall { .. } => panic!("Expected a sign_bytes entry"),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn rotates_files_and_drops_the_oldest_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let (log, mut writer) = audit_log(&dir);
+    for length in 0..4 {
+      writer.write(&entry("app", length)).unwrap();
+    }
+
+    assert_eq!(lengths(log.query(&ZomeCallAuditQuery::default()).unwrap()), vec![3, 2, 1]);
+    assert!(!file_path(&log.path, 3).exists());
+  }
+
+  #[test]
+  fn queries_matching_entries_newest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let (log, mut writer) = audit_log(&dir);
+    writer.write(&entry("app", 1)).unwrap();
+    writer.write(&entry("other-app", 2)).unwrap();
+    writer.write(&entry("app", 3)).unwrap();
+
+    let query = ZomeCallAuditQuery {
+      app_id: Some(String::from("app")),
+      ..Default::default()
+    };
+    assert_eq!(lengths(log.query(&query).unwrap()), vec![3, 1]);
+
+    let query = ZomeCallAuditQuery {
+      limit: Some(2),
+      ..Default::default()
+    };
+    assert_eq!(lengths(log.query(&query).unwrap()), vec![3, 2]);
+  }
+
+  #[test]
+  fn skips_lines_cut_off_by_a_crash() {
+    let dir = tempfile::tempdir().unwrap();
+    let (log, mut writer) = audit_log(&dir);
+    writer.write(&entry("app", 1)).unwrap();
+    writer
+      .file
+      .as_mut()
+      .unwrap()
+      .0
+      .write_all(b"{\"timestamp\":")
+      .unwrap();
+
+    assert_eq!(lengths(log.query(&ZomeCallAuditQuery::default()).unwrap()), vec![1]);
+  }
+}
@@ -0,0 +1,266 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex, RwLock},
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use holochain_types::prelude::ZomeCallUnsigned;
+use lair_keystore_manager::{LairKeystoreManager, ZomeCall};
+use serde::{Deserialize, Serialize};
+
+use super::config::ZomeCallRateLimitConfig;
+
+/// A window's calls to unknown cells refresh its cells at most this often
+const CELL_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Event emitted to the admin window when a window starts getting throttled
+pub const ZOME_CALL_THROTTLED_EVENT: &str = "zome-call-throttled";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZomeCallThrottledPayload {
+  pub window_label: String,
+  pub app_id: Option<String>,
+}
+
+/// Zome call signing statistics of a window that got throttled at least once
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThrottledWindow {
+  pub window_label: String,
+  pub app_id: Option<String>,
+  /// Number of zome calls that were refused because of the rate limit
+  pub throttled_calls: u64,
+  /// Milliseconds since the unix epoch
+  pub last_throttled_at: u64,
+}
+
+/// Token bucket of a window
+struct WindowQuota {
+  tokens: f64,
+  refilled_at: Instant,
+  throttled_calls: u64,
+  last_throttled_at: Option<u64>,
+  /// Whether the last request of the window got throttled
+  throttled: bool,
+}
+
+/// How many of the requested zome calls a window may sign now
+pub struct QuotaDecision {
+  pub allowed: usize,
+  /// Whether the window got throttled after not having been throttled before
+  pub newly_throttled: bool,
+}
+
+/// Signs zome calls independently of the LauncherState lock and enforces the rate limit of each window
+pub struct ZomeCallSigner {
+  /// Set once lair-keystore is running
+  keystore: RwLock<Option<Arc<dyn LairKeystoreManager>>>,
+  rate_limit: ZomeCallRateLimitConfig,
+  quotas: Mutex<HashMap<String, WindowQuota>>,
+  /// When the cells of a window have last been refreshed because of a call to an unknown cell
+  cell_refreshes: Mutex<HashMap<String, Instant>>,
+}
+
+impl ZomeCallSigner {
+  pub fn new(rate_limit: ZomeCallRateLimitConfig) -> Self {
+    ZomeCallSigner {
+      keystore: RwLock::new(None),
+      rate_limit,
+      quotas: Mutex::new(HashMap::new()),
+      cell_refreshes: Mutex::new(HashMap::new()),
+    }
+  }
+
+  pub fn set_keystore(&self, keystore: Arc<dyn LairKeystoreManager>) {
+    *self.keystore.write().unwrap_or_else(|e| e.into_inner()) = Some(keystore);
+  }
+
+  /// Takes up to `requested` tokens from the bucket of the window
+  pub fn acquire(&self, window_label: &str, requested: usize) -> QuotaDecision {
+    let now = Instant::now();
+    let burst = self.rate_limit.burst as f64;
+
+    let mut quotas = self.quotas.lock().unwrap_or_else(|e| e.into_inner());
+    let quota = quotas
+      .entry(window_label.to_string())
+      .or_insert_with(|| WindowQuota {
+        tokens: burst,
+        refilled_at: now,
+        throttled_calls: 0,
+        last_throttled_at: None,
+        throttled: false,
+      });
+
+    let elapsed = now.duration_since(quota.refilled_at).as_secs_f64();
+    quota.tokens = (quota.tokens + elapsed * self.rate_limit.calls_per_second).min(burst);
+    quota.refilled_at = now;
+
+    let allowed = requested.min(quota.tokens.floor() as usize);
+    quota.tokens -= allowed as f64;
+
+    let throttled_calls = requested - allowed;
+    let was_throttled = quota.throttled;
+    quota.throttled = throttled_calls > 0;
+    if throttled_calls > 0 {
+      quota.throttled_calls += throttled_calls as u64;
+      quota.last_throttled_at = Some(
+        SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .map(|duration| duration.as_millis() as u64)
+          .unwrap_or(0),
+      );
+    }
+
+    QuotaDecision {
+      allowed,
+      newly_throttled: quota.throttled && !was_throttled,
+    }
+  }
+
+  /// Whether the cells of the window may be refreshed now, debouncing refreshes per window
+  pub fn try_refresh_cells(&self, window_label: &str) -> bool {
+    let now = Instant::now();
+    let mut cell_refreshes = self.cell_refreshes.lock().unwrap_or_else(|e| e.into_inner());
+
+    match cell_refreshes.get(window_label) {
+      Some(refreshed_at) if now.duration_since(*refreshed_at) < CELL_REFRESH_INTERVAL => false,
+      _ => {
+        cell_refreshes.insert(window_label.to_string(), now);
+        true
+      }
+    }
+  }
+
+  /// Drops the quota and the cell refresh time of a window that got closed
+  pub fn forget_window(&self, window_label: &str) {
+    self.quotas.lock().unwrap_or_else(|e| e.into_inner()).remove(window_label);
+    self.cell_refreshes.lock().unwrap_or_else(|e| e.into_inner()).remove(window_label);
+  }
+
+  /// Windows that got throttled at least once. The app ids are left for the caller to fill in.
+  pub fn throttled_windows(&self) -> Vec<ThrottledWindow> {
+    self
+      .quotas
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .iter()
+      .filter_map(|(window_label, quota)| {
+        quota.last_throttled_at.map(|last_throttled_at| ThrottledWindow {
+          window_label: window_label.clone(),
+          app_id: None,
+          throttled_calls: quota.throttled_calls,
+          last_throttled_at,
+        })
+      })
+      .collect()
+  }
+
+  /// Signs the zome calls concurrently, returning one result per zome call in the same order
+  pub async fn sign_zome_calls(
+    &self,
+    zome_calls_unsigned: Vec<ZomeCallUnsigned>,
+  ) -> Result<Vec<Result<ZomeCall, String>>, String> {
+    let keystore = self
+      .keystore
+      .read()
+      .unwrap_or_else(|e| e.into_inner())
+      .clone()
+      .ok_or(String::from("Signing zome call failed: lair-keystore is not running"))?;
+
+    let results = keystore
+      .sign_zome_calls(zome_calls_unsigned)
+      .await
+      .map_err(|e| format!("Signing zome call failed: {}", e))?;
+
+    Ok(
+      results
+        .into_iter()
+        .map(|result| result.map_err(|e| format!("Signing zome call failed: {}", e)))
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Signer whose windows may sign 3 zome calls at once, without refilling
+  fn signer() -> ZomeCallSigner {
+    ZomeCallSigner::new(ZomeCallRateLimitConfig {
+      calls_per_second: 0.0,
+      burst: 3,
+    })
+  }
+
+  #[test]
+  fn allows_bursts_up_to_the_limit() {
+    let signer = signer();
+
+    let decision = signer.acquire("window", 2);
+    assert_eq!(decision.allowed, 2);
+    assert!(!decision.newly_throttled);
+
+    let decision = signer.acquire("window", 2);
+    assert_eq!(decision.allowed, 1);
+    assert!(decision.newly_throttled);
+
+    let decision = signer.acquire("window", 1);
+    assert_eq!(decision.allowed, 0);
+    assert!(!decision.newly_throttled);
+  }
+
+  #[test]
+  fn limits_each_window_separately() {
+    let signer = signer();
+    signer.acquire("window", 3);
+
+    assert_eq!(signer.acquire("other-window", 3).allowed, 3);
+  }
+
+  #[test]
+  fn refills_the_bucket_over_time() {
+    let signer = ZomeCallSigner::new(ZomeCallRateLimitConfig {
+      calls_per_second: 1000.0,
+      burst: 3,
+    });
+    signer.acquire("window", 3);
+    std::thread::sleep(Duration::from_millis(10));
+
+    assert_eq!(signer.acquire("window", 3).allowed, 3);
+  }
+
+  #[test]
+  fn reports_throttled_windows() {
+    let signer = signer();
+    signer.acquire("window", 5);
+    signer.acquire("window", 1);
+    signer.acquire("other-window", 1);
+
+    let throttled_windows = signer.throttled_windows();
+    assert_eq!(throttled_windows.len(), 1);
+    assert_eq!(throttled_windows[0].window_label, "window");
+    assert_eq!(throttled_windows[0].throttled_calls, 3);
+  }
+
+  #[test]
+  fn debounces_cell_refreshes_per_window() {
+    let signer = signer();
+
+    assert!(signer.try_refresh_cells("window"));
+    assert!(!signer.try_refresh_cells("window"));
+    assert!(signer.try_refresh_cells("other-window"));
+  }
+
+  #[test]
+  fn forgets_closed_windows() {
+    let signer = signer();
+    signer.acquire("window", 5);
+    signer.try_refresh_cells("window");
+
+    signer.forget_window("window");
+
+    assert!(signer.throttled_windows().is_empty());
+    assert_eq!(signer.acquire("window", 3).allowed, 3);
+    assert!(signer.try_refresh_cells("window"));
+  }
+}
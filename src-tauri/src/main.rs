@@ -2,14 +2,15 @@
   all(not(debug_assertions), target_os = "windows"),
   windows_subsystem = "windows"
 )]
-use file_system::{profile_holochain_data_dir, profile_tauri_dir};
+use file_system::{profile_holochain_data_dir, profile_tauri_dir, zome_call_audit_log_path};
 use futures::lock::Mutex;
-use hdk::prelude::AgentPubKey;
+use holochain_web_app_manager::window_registry::{WindowRegistry, WindowRegistryState};
 use launcher::error::LauncherError;
+use lair_keystore_manager::versions::stop_supervision;
 use running_state::RunningState;
 use tauri::Window;
 use tauri::WindowEvent;
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -43,26 +44,44 @@ use crate::commands::save_app::{save_app, fetch_and_save_app, fetch_gui};
 use crate::commands::restart::restart;
 use crate::commands::quit::quit;
 use crate::commands::{
+  activity::report_user_activity,
+  agent_keys::{get_agent_key_inventory, hide_unused_agent_key, set_agent_key_label},
+  app_metadata::{get_app_metadata, set_app_label, set_zome_call_policy},
+  app_uis::{add_app_ui, remove_app_ui, rollback_app_ui, trust_app_ui_assets, update_app_ui},
   choose_version::choose_version_for_hdk,
   config::{write_config, get_default_bootstrap, get_default_signaling},
   enable_app::{disable_app, enable_app, delete_clone},
+  export_app::export_web_app,
   factory_reset::execute_factory_reset,
   get_app_info::get_app_info,
   icon_src::{get_icon_src, store_icon_src},
+  identities::{create_identity, list_identities, remove_identity, rename_identity},
   get_state_info::get_state_info,
   install_app::install_app,
   install_devhub::install_devhub,
   network_stats::dump_network_stats,
   notifications::{notify_os, notify_tauri, clear_happ_notifications, clear_systray_icon, reset_happ_notification_count},
   open_app::open_app_ui,
-  password::{initialize_keystore, unlock_and_launch},
+  orphaned_data::{find_orphaned_data, remove_orphaned_data},
+  password::{initialize_keystore, launch_pending_holochains, unlock_and_launch},
   uninstall_app::uninstall_app,
-  sign_zome_call::sign_zome_call,
-  storage::get_storage_info,
+  sign_bytes::{revoke_signing_consent, sign_bytes, verify_signature},
+  sign_zome_call::{sign_zome_call, sign_zome_calls},
+  storage::{get_app_storage_info, get_storage_info},
   update_default_ui::{fetch_and_update_default_gui, update_default_ui},
+  upgrade_app::{rollback_app_upgrade, upgrade_app},
+  zome_call_audit_log::{get_throttled_windows, get_zome_call_audit_log},
 };
+use crate::launcher::apps_refresher::AppsRefresher;
+use crate::launcher::default_apps::{APPSTORE_APP_ID, DEVHUB_APP_ID};
+use crate::launcher::config::LauncherConfig;
+use crate::launcher::idle_lock::UserActivity;
 use crate::launcher::manager::LauncherManager;
+use crate::launcher::identities::{Identities, IdentitiesState};
+use crate::launcher::signing_consents::{SigningConsents, SigningConsentsState};
 use crate::launcher::state::LauncherState;
+use crate::launcher::zome_call_audit_log::ZomeCallAuditLog;
+use crate::launcher::zome_call_signer::ZomeCallSigner;
 use crate::menu::build_menu;
 use crate::menu::handle_menu_event;
 use crate::setup::logs::setup_logs;
@@ -85,27 +104,38 @@ fn main() {
       _ => {}
     })
     .invoke_handler(tauri::generate_handler![
+      add_app_ui,
       choose_version_for_hdk,
       clear_happ_notifications,
       clear_systray_icon,
+      create_identity,
       disable_app,
       delete_clone,
       dump_network_stats,
       enable_app,
       execute_factory_reset,
+      export_web_app,
       fetch_and_save_app,
       fetch_and_update_default_gui,
       fetch_gui,
+      find_orphaned_data,
+      get_agent_key_inventory,
       get_app_info,
+      get_app_metadata,
+      get_app_storage_info,
       get_default_bootstrap,
       get_default_signaling,
       get_icon_src,
       get_state_info,
       get_storage_info,
       get_supported_versions,
+      get_throttled_windows,
+      get_zome_call_audit_log,
       initialize_keystore,
       install_app,
       install_devhub,
+      launch_pending_holochains,
+      list_identities,
       notify_os,
       notify_tauri,
       open_app_ui,
@@ -113,14 +143,32 @@ fn main() {
       // start_app,
       quit,
       report_issue_cmd,
+      report_user_activity,
+      remove_app_ui,
+      remove_identity,
+      remove_orphaned_data,
+      hide_unused_agent_key,
+      rename_identity,
       reset_happ_notification_count,
       restart,
+      revoke_signing_consent,
+      rollback_app_ui,
+      rollback_app_upgrade,
       save_app,
+      set_agent_key_label,
+      set_app_label,
+      set_zome_call_policy,
+      sign_bytes,
       sign_zome_call,
+      sign_zome_calls,
       store_icon_src,
+      trust_app_ui_assets,
+      update_app_ui,
       update_default_ui,
+      upgrade_app,
       uninstall_app,
       unlock_and_launch,
+      verify_signature,
       write_config,
       setup::logs::log,
     ])
@@ -178,6 +226,14 @@ fn main() {
 
       app.manage(Mutex::new(SysTrayIconState { icon_state: IconState::Clean }));
 
+      app.manage(UserActivity::new());
+
+      let signing_consents: SigningConsentsState = Mutex::new(SigningConsents::read(profile.clone()));
+      app.manage(signing_consents);
+
+      let identities: IdentitiesState = Mutex::new(Identities::read(profile.clone()));
+      app.manage(identities);
+
       if let Err(err) = setup_logs(profile.clone()) {
         println!("Error setting up the logs: {:?}", err);
       }
@@ -186,9 +242,21 @@ fn main() {
 
       let _admin_window = build_admin_window(&app.app_handle(), local_storage_path)?;
 
-      // manage the state of pubkeys associated to tauri windows. The keys of this hashmap are window labels
-      let pubkey_map: Arc<Mutex<HashMap<String, AgentPubKey>>> = Arc::new(Mutex::new(HashMap::new()));
-      app.manage(pubkey_map);
+      // manage the identities of the app windows, keyed by window label. The admin window may only
+      // make zome calls to the AppStore and the DevHub.
+      let admin_app_ids = HashSet::from([APPSTORE_APP_ID.to_string(), DEVHUB_APP_ID.to_string()]);
+      let window_registry: WindowRegistryState = Arc::new(Mutex::new(WindowRegistry::new(admin_app_ids)));
+      app.manage(window_registry);
+
+      let launcher_config = LauncherConfig::read(profile.clone());
+      let audit_log = ZomeCallAuditLog::new(zome_call_audit_log_path(profile.clone())?, launcher_config.zome_call_audit_log);
+      app.manage(audit_log);
+
+      // signs zome calls without the LauncherState lock, once the keystore is running
+      app.manage(ZomeCallSigner::new(launcher_config.zome_call_rate_limit));
+
+      let apps_refresher = AppsRefresher::spawn(app.handle());
+      app.manage(apps_refresher.clone());
 
       let handle = Arc::new(app.handle());
       let launcher_state =
@@ -196,6 +264,9 @@ fn main() {
 
       app.manage(Arc::new(Mutex::new(launcher_state)));
 
+      // refreshes requested while launching got skipped since the LauncherState wasn't managed yet
+      apps_refresher.request();
+
       Ok(())
     })
     .build(tauri::generate_context!());
@@ -207,24 +278,34 @@ fn main() {
         match event {
           // This event is emitted upon quitting the Launcher via cmq+Q on macOS.
           // Sidecar binaries need to get explicitly killed in this case (https://github.com/holochain/launcher/issues/141)
-          RunEvent::Exit => tauri::api::process::kill_children(),
+          RunEvent::Exit => {
+            stop_supervision();
+            tauri::api::process::kill_children()
+          },
 
           // This event is emitted upon pressing the x to close the Launcher admin window
           // The app is prevented from exiting to keep it running in the background with the system tray
           RunEvent::ExitRequested { api, .. } => api.prevent_exit(),
 
           // If a window is requested to be closed, hide it instead. This is to keep the UI running in the
-          // background to be able to send/receive notifications.
+          // background to be able to send/receive notifications. Windows of apps that are not running
+          // anymore don't have an identity in the window registry and get closed.
           // TODO garbage collect windows in the front-end if they have notificationSettings all turned off
           RunEvent::WindowEvent { label, event: window_event, .. } => {
             match window_event {
               WindowEvent::CloseRequested { api, .. } => {
+                let app_stopped = label != "admin" && app_handle
+                  .state::<WindowRegistryState>()
+                  .try_lock()
+                  .map_or(false, |registry| registry.get(&label).is_none());
                 let window_option = app_handle.get_window(&label);
-                if let Some(window) = window_option {
+                if let (Some(window), false) = (window_option, app_stopped) {
                   window.hide().unwrap();
                   api.prevent_close();
                 }
               },
+              WindowEvent::Focused(true) => app_handle.state::<UserActivity>().touch(),
+              WindowEvent::Destroyed => app_handle.state::<ZomeCallSigner>().forget_window(&label),
               _ => (),
             }
           },
@@ -272,6 +353,7 @@ async fn launch_manager(app_handle: Arc<AppHandle>, profile: Profile) -> Running
       RunningState::Running(launcher_manager)
     }
     Err(error) => {
+      stop_supervision();
       kill_children();
       log::error!("There was an error launching holochain: {:?}", error);
       RunningState::Error(error)
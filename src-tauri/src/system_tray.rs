@@ -1,21 +1,28 @@
 use std::collections::HashMap;
+use lair_keystore_manager::versions::stop_supervision;
 use tauri::api::process;
 
 use holochain_manager::versions::{
   holochain_conductor_api_latest::AppInfoStatus, HolochainVersion,
 };
-use holochain_web_app_manager::installed_web_app_info::{InstalledWebAppInfo, WebUiInfo};
+use holochain_web_app_manager::{
+  installed_web_app_info::{InstalledWebAppInfo, WebUiInfo},
+  window_registry::{derive_window_label, WindowRegistryState},
+  DEFAULT_UI_NAME,
+};
 use tauri::{ AppHandle, CustomMenuItem, Manager, SystemTrayMenu, SystemTrayMenuItem, Wry };
 
-use crate::{launcher::{state::LauncherState, manager::HolochainId}, build_admin_window, file_system::{profile_tauri_dir, Profile}};
+use crate::{launcher::{state::LauncherState, manager::{open_app, HolochainId}}, build_admin_window, file_system::{profile_tauri_dir, Profile}};
 
 pub fn handle_system_tray_event(app: &AppHandle<Wry>, event_id: String) {
   match event_id.as_str() {
     "quit" => {
+      stop_supervision();
       process::kill_children();
       app.exit(0);
     },
     "restart" => {
+      stop_supervision();
       process::kill_children();
       app.app_handle().restart();
     },
@@ -31,24 +38,23 @@ pub fn handle_system_tray_event(app: &AppHandle<Wry>, event_id: String) {
         let _r = build_admin_window(&app.app_handle(), local_storage_path).unwrap();
       }
     }
-    menu_item_id => {
-      let (version, app_id) =
-        expand_holochain_and_app_id(String::from(menu_item_id)).expect("Bad menu item?");
-
+    window_label => {
+      let window_registry = app.state::<WindowRegistryState>();
       let launcher_state = app.state::<LauncherState>();
       tauri::async_runtime::block_on(async move {
-        let mut mutex = (*launcher_state).lock().await;
-
-        match mutex.get_running() {
-          Ok(manager) => {
-            if let Err(err) = manager.open_app(version, &app_id, true) {
-              log::error!("Error opening app: {:?}", err);
-            }
-          }
-          Err(e) => {
-            log::error!("Error opening app: {:?}", e);
+        // the menu items of apps are identified by the label of the window of their default UI
+        let identity = match window_registry.lock().await.get(window_label) {
+          Some(identity) => identity.clone(),
+          None => {
+            log::error!("Error opening app: no running app found for menu item '{}'", window_label);
+            return;
           }
         };
+
+        let holochain_id = HolochainId::from(identity.holochain_version);
+        if let Err(err) = open_app(&launcher_state, holochain_id, &identity.app_id, &identity.ui_name, true).await {
+          log::error!("Error opening app: {:?}", err);
+        }
       });
 
       ()
@@ -93,14 +99,11 @@ pub fn update_system_tray(
   for (version, installed_apps) in &all_installed_apps.by_version {
     for app in installed_apps {
       if let AppInfoStatus::Running = app.installed_app_info.status {
-        if let Some(WebUiInfo::WebApp { .. }) = app.web_uis.get(&String::from("default")) {
+        if let Some(WebUiInfo::WebApp { .. }) = app.web_uis.get(DEFAULT_UI_NAME) {
           let app_id = app.installed_app_info.installed_app_id.clone();
 
           menu = menu.add_item(CustomMenuItem::new(
-            collapse_holochain_and_app_id(
-              HolochainId::HolochainVersion(version.clone()),
-              app_id.clone(),
-            ),
+            derive_window_label(version, &app_id, &String::from(DEFAULT_UI_NAME)),
             app_id.clone(),
           ));
         }
@@ -111,11 +114,11 @@ pub fn update_system_tray(
   if let Some(custom_binary_apps) = &all_installed_apps.custom_binary {
     for app in custom_binary_apps {
       if let AppInfoStatus::Running = app.installed_app_info.status {
-        if let Some(WebUiInfo::WebApp { .. }) = app.web_uis.get(&String::from("default")) {
+        if let Some(WebUiInfo::WebApp { .. }) = app.web_uis.get(DEFAULT_UI_NAME) {
           let app_id = app.installed_app_info.installed_app_id.clone();
 
           menu = menu.add_item(CustomMenuItem::new(
-            collapse_holochain_and_app_id(HolochainId::CustomBinary, app_id.clone()),
+            derive_window_label(&HolochainVersion::custom(), &app_id, &String::from(DEFAULT_UI_NAME)),
             app_id.clone(),
           ));
         }
@@ -127,30 +130,3 @@ pub fn update_system_tray(
     log::error!("Error setting the system tray: {:?}", err);
   }
 }
-
-pub fn collapse_holochain_and_app_id(holochain_id: HolochainId, app_id: String) -> String {
-  match holochain_id {
-    HolochainId::HolochainVersion(holochain_version) => {
-      let version_string: String = holochain_version.into();
-      format!("{}:{}", version_string, app_id)
-    }
-    HolochainId::CustomBinary => format!("custom_binary:{}", app_id),
-  }
-}
-
-pub fn expand_holochain_and_app_id(menu_item_id: String) -> Result<(HolochainId, String), String> {
-  let components: Vec<&str> = menu_item_id.split(":").collect();
-
-  let app_id = components[1..].join(":");
-
-  match components[0] {
-    "custom" => Ok((HolochainId::CustomBinary, app_id)),
-    version_str => {
-      let version = version_str
-        .parse::<HolochainVersion>()
-        .or(Err(String::from("Invalid version")))?;
-
-      Ok((HolochainId::HolochainVersion(version), app_id))
-    }
-  }
-}
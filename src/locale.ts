@@ -143,6 +143,8 @@ const messages = {
         restoreDefaults: "Reset to Default Values",
         saveAndRestart: "Save and Restart",
       },
+      launchHolochain:
+        "Holochain {version} is not running yet. Enter your password to launch it, then try again.",
       restart:
         "Do you want to clean up all holochain processes and restart the Holochain Launcher?",
       setupPassword: {
@@ -351,6 +353,8 @@ const messages = {
         restoreDefaults: "Zurücksetzen zu Standardwerten",
         saveAndRestart: "Speichern und Neustart",
       },
+      launchHolochain:
+        "Holochain {version} läuft noch nicht. Gib dein Passwort ein, um es zu starten, und versuche es dann erneut.",
       restart:
         "Möchtest du alle Holochain Prozesse beenden und den Holochain Launcher neu starten?",
       setupPassword: {
@@ -74,6 +74,19 @@ window.onkeyup = (ev) => {
   }
 };
 
+// report user input to reset the keystore's idle-lock timer, at most every 30 seconds
+let lastActivityReport = 0;
+const reportActivity = () => {
+  const now = Date.now();
+  if (now - lastActivityReport > 30000) {
+    lastActivityReport = now;
+    invoke("report_user_activity", {});
+  }
+};
+["keydown", "mousedown", "mousemove", "wheel"].forEach((eventType) =>
+  window.addEventListener(eventType, reportActivity, { passive: true })
+);
+
 type Locale = "en" | "de";
 
 const customLocale = window.localStorage.getItem("customLocale");
@@ -6,6 +6,7 @@ import {
   DnaHashB64,
   AnyDhtHashB64,
   ActionHashB64,
+  CellId,
 } from "@holochain/client";
 import { HappNotification, NotificationId } from "@holochain/launcher-api";
 import { Entity, GUIReleaseEntry, HappReleaseEntry } from "./appstore/types";
@@ -55,6 +56,29 @@ export type HolochainId =
       content: undefined;
     };
 
+export interface AgentKeyApp {
+  holochain_id: HolochainId;
+  app_id: string;
+}
+
+export interface AgentKeyInfo {
+  agent_pub_key: Uint8Array;
+  tag: string;
+  label: string | undefined;
+  installed_apps: AgentKeyApp[];
+}
+
+export interface AgentKeyInventory {
+  agent_keys: AgentKeyInfo[];
+  unavailable_holochains: HolochainId[];
+}
+
+export interface IdentityInfo {
+  name: string;
+  agent_pub_key: Uint8Array;
+  installed_apps: AgentKeyApp[];
+}
+
 export type LaunchTauriSidecarError =
   | {
       type: "BinaryNotFound";
@@ -120,6 +144,8 @@ export interface LauncherConfig {
   running_versions: HolochainVersion[];
   custom_binary_path: string | undefined;
   profile: string;
+  idle_lock_minutes: number | undefined;
+  ui_revisions_to_keep: number;
 }
 
 export type WebUiInfo =
@@ -131,6 +157,7 @@ export type WebUiInfo =
       path_to_web_app: string;
       app_ui_port: number;
       gui_release_info: ReleaseInfo | undefined;
+      assets_hash: string | undefined;
     };
 
 export interface ResourceLocator {
@@ -148,6 +175,8 @@ export interface InstalledWebAppInfo {
   happ_release_info: ReleaseInfo | undefined;
   web_uis: Record<string, WebUiInfo>;
   icon_src: string | undefined;
+  label: string | undefined;
+  installed_at: number | undefined;
 }
 
 export interface HolochainAppInfo {
@@ -178,6 +207,31 @@ export interface StorageInfo {
   wasm: number;
 }
 
+export interface CellStorageInfo {
+  role_name: string;
+  dna_hash: string;
+  authored: number;
+  dht: number;
+  cache: number;
+}
+
+export interface AppStorageInfo {
+  app_id: string;
+  uis: number;
+  local_storage: number;
+  other_app_data: number;
+  cells: CellStorageInfo[];
+  shares_dna_databases_with: string[];
+}
+
+export type OrphanedData =
+  | {
+      type: "AppDataDir";
+      content: { app_id: string; path: string; size: number };
+    }
+  | { type: "AppMetadata"; content: { app_id: string } }
+  | { type: "LeftoverFile"; content: { path: string; size: number } };
+
 export interface ReleaseData {
   devhubDnaHash: DnaHash;
   happRelease: Entity<HappReleaseEntry>;
@@ -189,6 +243,78 @@ export interface ReleaseInfo {
   version: string | undefined;
 }
 
+export interface ZomeCallPolicy {
+  allowed: Array<{ zome_name: string; fn_names: Array<string> | undefined }>;
+}
+
+export type SigningRequest =
+  | {
+      type: "ZomeCall";
+      content: { dna_hash: string; zome_name: string; fn_name: string };
+    }
+  | { type: "Bytes"; content: { length: number } };
+
+export interface ZomeCallAuditEntry {
+  timestamp: number;
+  window_label: string;
+  app_id: string | undefined;
+  agent_pub_key: string;
+  request: SigningRequest;
+  outcome:
+    | { type: "Signed" }
+    | { type: "Rejected"; content: string }
+    | { type: "Throttled" };
+}
+
+export interface ThrottledWindow {
+  window_label: string;
+  app_id: string | undefined;
+  throttled_calls: number;
+  last_throttled_at: number;
+}
+
+export interface ZomeCallThrottledPayload {
+  window_label: string;
+  app_id: string | undefined;
+}
+
+export interface ZomeCallAuditQuery {
+  app_id?: string;
+  window_label?: string;
+  since?: number;
+  limit?: number;
+}
+
+export type AppUpgrade =
+  | { type: "CoordinatorsUpdated" }
+  | {
+      type: "Migrated";
+      content: {
+        previous_app_id: string;
+        new_app_id: string;
+        changed_roles: string[];
+      };
+    };
+
+export interface AppUpgradeProgress {
+  app_id: string;
+  message: string;
+}
+
+export type AppLifecycleEvent =
+  | { type: "AppInstalled"; content: { app_id: string } }
+  | { type: "AppUninstalled"; content: { app_id: string } }
+  | { type: "AppEnabled"; content: { app_id: string } }
+  | { type: "AppDisabled"; content: { app_id: string } }
+  | { type: "AppUpgraded"; content: { app_id: string } }
+  | { type: "UiUpdated"; content: { app_id: string; ui_name: string } }
+  | { type: "CloneDeleted"; content: { app_id: string; cell_id: CellId } };
+
+export interface AppLifecycleEventPayload {
+  holochain_version: HolochainVersion;
+  event: AppLifecycleEvent;
+}
+
 export interface NotificationPayload {
   notifications: Array<HappNotification>;
   app_id: string;